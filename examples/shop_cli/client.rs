@@ -10,12 +10,16 @@ use zeroize::Zeroizing;
 use crate::common::*;
 
 pub async fn client_loop(term: &Term, mut backend: ShopBackend) -> Result<()> {
-    static CLIENT_OPTIONS: [&str; 6] = [
+    static CLIENT_OPTIONS: [&str; 10] = [
         "Register car",
         "Register order",
         "List orders",
         "List reports",
         "Print report summary",
+        "Service history",
+        "Messages",
+        "Notifications",
+        "Verify email",
         "Log out",
     ];
 
@@ -43,6 +47,10 @@ pub async fn client_loop(term: &Term, mut backend: ShopBackend) -> Result<()> {
                 2 => list_orders(term, &backend).await?,
                 3 => list_reports(term, &backend).await?,
                 4 => print_summary(term, &backend).await?,
+                5 => print_history(term, &backend, user.id()).await?,
+                6 => messages(term, &backend).await?,
+                7 => list_notifications(term, &backend).await?,
+                8 => verify_email(term, &backend).await?,
                 _ => {
                     backend.log_out().await?;
                     break;
@@ -78,14 +86,16 @@ async fn login_screen(term: &Term, backend: &mut ShopBackend) -> Result<User> {
 
         let email = input(term, "Email")?;
         let password = Zeroizing::new(Password::new().with_prompt("Password").interact_on(term)?);
-        let password_hash = bcrypt::hash(password, DEFAULT_COST)?;
 
         let user = match choice {
-            0 => match backend.client_login(&email, &password_hash).await {
+            0 => match backend.client_login(&email, &password).await {
                 Ok(u) => Ok(u),
                 Err(e) => Err(anyhow::Error::from(e)),
             },
-            1 => match backend.register_client(&name, &email, &password_hash).await {
+            1 => match backend
+                .register_client(&name, &email, &bcrypt::hash(&*password, DEFAULT_COST)?)
+                .await
+            {
                 Ok(_) => todo!(),
                 Err(e) => Err(anyhow::Error::from(e)),
             },
@@ -149,8 +159,19 @@ async fn register_order(
                 return Ok(());
             }
 
+            let Some(location_id) = select_location(term, backend).await? else {
+                return Ok(());
+            };
+
             let service = &SERVICES[service];
-            backend.register_order(client_id, service).await?;
+            let complaint: String = Input::new()
+                .with_prompt("Describe the problem (or nothing)")
+                .allow_empty(true)
+                .interact_text_on(term)?;
+            let complaint = Some(complaint.as_str()).filter(|c| !c.trim().is_empty());
+            backend
+                .register_order(client_id, location_id, service, complaint)
+                .await?;
             term.write_line(&format!(
                 "Order for {} registered",
                 format!("{service}").to_lowercase()
@@ -232,3 +253,110 @@ async fn print_summary(term: &Term, backend: &ShopBackend) -> Result<()> {
     wait_for_continue(term)?;
     Ok(())
 }
+
+async fn print_history(term: &Term, backend: &ShopBackend, client_id: u32) -> Result<()> {
+    term.write_line("Service history")?;
+    let history = match backend.get_vehicle_history(client_id).await {
+        Ok(history) => history,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    for service in history.services() {
+        let order = service.order();
+        let mileage = service
+            .mileage()
+            .map(|mileage| format!(", {mileage} km"))
+            .unwrap_or_default();
+        term.write_line(&format!("{}: {}{mileage}", order.id(), order.service()))?;
+        for part in service.parts() {
+            term.write_line(&format!("    {part}"))?;
+        }
+    }
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+/// Shows the thread of an order and lets the client reply
+async fn messages(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Messages")?;
+    let order_id: u32 = match input(term, "Order ID")?.parse() {
+        Ok(id) => id,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    match backend.get_order_messages(order_id).await {
+        Ok(thread) => {
+            for message in thread {
+                term.write_line(&format!("{message}"))?;
+            }
+            let reply: String = Input::new()
+                .with_prompt("Reply (or nothing)")
+                .allow_empty(true)
+                .interact_text_on(term)?;
+            if !reply.trim().is_empty() {
+                if let Err(e) = backend.send_order_message(order_id, &reply).await {
+                    term.write_line(&format_err(&e))?;
+                }
+            }
+        }
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+async fn list_notifications(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Notifications")?;
+    let notifications = backend.get_notifications().await?;
+
+    if notifications.is_empty() {
+        term.write_line("You have no notifications")?;
+        wait_for_continue(term)?;
+        return Ok(());
+    }
+
+    for notification in notifications {
+        term.write_line(&format!("{notification}"))?;
+        if !notification.read() {
+            backend.mark_notification_read(notification.id()).await?;
+        }
+    }
+    wait_for_continue(term)?;
+
+    Ok(())
+}
+
+async fn verify_email(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Verify email")?;
+    let token: String = Input::new()
+        .with_prompt("Verification token (or nothing to send it again)")
+        .allow_empty(true)
+        .interact_text_on(term)?;
+
+    let res = if token.is_empty() {
+        backend
+            .resend_verification_email()
+            .await
+            .map(|_| "Verification email sent")
+    } else {
+        backend
+            .verify_email(&token)
+            .await
+            .map(|_| "Email address verified")
+    };
+
+    match res {
+        Ok(msg) => term.write_line(msg)?,
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+    wait_for_continue(term)?;
+    Ok(())
+}
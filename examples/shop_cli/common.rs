@@ -1,5 +1,6 @@
 use anyhow::Result;
-use dialoguer::{console::Term, Input};
+use car_repair_shop_backend::ShopBackend;
+use dialoguer::{console::Term, Input, Select};
 
 pub fn wait_for_continue(term: &Term) -> Result<()> {
     term.write_line("Press any key to continue")?;
@@ -11,6 +12,25 @@ pub fn input(term: &Term, prompt: &str) -> Result<String> {
     Ok(Input::new().with_prompt(prompt).interact_text_on(term)?)
 }
 
+pub fn optional_input(term: &Term, prompt: &str) -> Result<String> {
+    Ok(Input::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .interact_text_on(term)?)
+}
+
 pub fn format_err(e: &dyn std::error::Error) -> String {
     format!("{e}")
 }
+
+/// Lets the user pick a workshop, `None` if they cancel
+pub async fn select_location(term: &Term, backend: &ShopBackend) -> Result<Option<u32>> {
+    let locations = backend.get_locations().await?;
+    let choice = Select::new()
+        .with_prompt("Location")
+        .items(&locations)
+        .item("Cancel")
+        .default(0)
+        .interact_on(term)?;
+    Ok(locations.get(choice).map(|location| location.id()))
+}
@@ -0,0 +1,266 @@
+use anyhow::Result;
+use car_repair_shop_backend::*;
+use dialoguer::{console::Term, *};
+use rand::Rng;
+
+use crate::common::*;
+
+use std::env;
+
+pub async fn manager_loop(term: &Term, backend: &mut ShopBackend, user: &User) -> Result<()> {
+    static MANAGER_OPTIONS: [&str; 6] = [
+        "Two-factor authentication policy",
+        "Webhooks",
+        "Order board",
+        "Analytics",
+        "Maintenance schedules",
+        "Log out",
+    ];
+
+    loop {
+        term.clear_screen()?;
+        term.write_line("Car Repair Shop Account")?;
+
+        term.write_line(&format!(
+            "{}: {}(ID: {})",
+            user.user_type(),
+            user.name(),
+            user.id()
+        ))?;
+
+        let choice = Select::new()
+            .items(&MANAGER_OPTIONS)
+            .default(0)
+            .interact()?;
+        term.clear_screen()?;
+
+        match choice {
+            0 => two_factor_policy(term, backend).await?,
+            1 => webhooks(term, backend).await?,
+            2 => order_board(term, backend).await?,
+            3 => analytics(term, backend).await?,
+            4 => maintenance_schedules(term, backend).await?,
+            5 => {
+                backend.log_out().await?;
+                break Ok(());
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+async fn two_factor_policy(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Two-factor authentication policy")?;
+    let required = backend.two_factor_required().await?;
+    term.write_line(&format!(
+        "Two-factor authentication is currently {}",
+        if required { "required" } else { "optional" }
+    ))?;
+
+    let required = Confirm::new()
+        .with_prompt("Require two-factor authentication for all employees?")
+        .default(required)
+        .interact_on(term)?;
+
+    match backend.set_two_factor_required(required).await {
+        Ok(_) => term.write_line("Policy updated")?,
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+async fn webhooks(term: &Term, backend: &ShopBackend) -> Result<()> {
+    static WEBHOOK_OPTIONS: [&str; 4] = [
+        "Add subscription",
+        "Delivery log",
+        "Replay delivery",
+        "Back",
+    ];
+    static EVENT_TYPES: [WebhookEventType; 4] = [
+        WebhookEventType::OrderCreated,
+        WebhookEventType::OrderStatusChanged,
+        WebhookEventType::ReportCreated,
+        WebhookEventType::PaymentRecorded,
+    ];
+
+    term.write_line("Webhooks")?;
+    for subscription in backend.get_webhook_subscriptions().await? {
+        term.write_line(&format!("{subscription}"))?;
+    }
+
+    let choice = Select::new()
+        .items(&WEBHOOK_OPTIONS)
+        .default(0)
+        .interact()?;
+
+    let result = match choice {
+        0 => {
+            let url = input(term, "URL")?;
+            let secret = Password::new().with_prompt("Secret").interact_on(term)?;
+            let selected = MultiSelect::new()
+                .with_prompt("Events")
+                .items(&EVENT_TYPES)
+                .interact_on(term)?;
+            let event_types = selected
+                .into_iter()
+                .map(|i| EVENT_TYPES[i])
+                .collect::<Vec<_>>();
+
+            backend
+                .create_webhook_subscription(&url, &secret, &event_types)
+                .await
+                .map(|subscription| format!("Added subscription {}", subscription.id()))
+        }
+        1 => {
+            let id = input(term, "Subscription ID")?.parse()?;
+            backend.get_webhook_deliveries(id).await.map(|deliveries| {
+                deliveries
+                    .iter()
+                    .map(|delivery| delivery.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        2 => {
+            let id = input(term, "Delivery ID")?.parse()?;
+            backend
+                .replay_webhook_delivery(id)
+                .await
+                .map(|delivery| format!("{delivery}"))
+        }
+        3 => return Ok(()),
+        _ => unreachable!(),
+    };
+
+    match result {
+        Ok(message) => term.write_line(&message)?,
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+async fn maintenance_schedules(term: &Term, backend: &ShopBackend) -> Result<()> {
+    static SCHEDULE_OPTIONS: [&str; 4] =
+        ["Add schedule", "Delete schedule", "Send reminders", "Back"];
+    static SERVICES: [Service; 2] = [Service::Repair, Service::Inspection];
+
+    term.write_line("Maintenance schedules")?;
+    for schedule in backend.get_maintenance_schedules().await? {
+        term.write_line(&format!("{schedule}"))?;
+    }
+
+    let choice = Select::new()
+        .items(&SCHEDULE_OPTIONS)
+        .default(0)
+        .interact()?;
+
+    let result = match choice {
+        0 => {
+            let name = input(term, "Name")?;
+            let service = Select::new()
+                .with_prompt("Service")
+                .items(&SERVICES)
+                .default(0)
+                .interact_on(term)?;
+            let make = optional_input(term, "Make (or nothing for every make)")?;
+            let interval_km = optional_input(term, "Every how many km (or nothing)")?;
+            let interval_months = optional_input(term, "Every how many months (or nothing)")?;
+
+            backend
+                .add_maintenance_schedule(
+                    &name,
+                    &SERVICES[service],
+                    Some(make.as_str()).filter(|make| !make.is_empty()),
+                    interval_km.parse().ok(),
+                    interval_months.parse().ok(),
+                )
+                .await
+                .map(|schedule| format!("Added schedule {}", schedule.id()))
+        }
+        1 => {
+            let id = input(term, "Schedule ID")?.parse()?;
+            backend
+                .delete_maintenance_schedule(id)
+                .await
+                .map(|_| format!("Deleted schedule {id}"))
+        }
+        2 => {
+            let days = input(term, "Remind of maintenance due within days")?.parse()?;
+            backend
+                .send_maintenance_reminders(days)
+                .await
+                .map(|count| format!("Sent {count} reminders"))
+        }
+        3 => return Ok(()),
+        _ => unreachable!(),
+    };
+
+    match result {
+        Ok(message) => term.write_line(&message)?,
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+/// Key figures of the last `days` days and their revenue as CSV
+async fn analytics(term: &Term, backend: &ShopBackend) -> Result<()> {
+    static PERIODS: [Period; 3] = [Period::Day, Period::Week, Period::Month];
+
+    term.write_line("Analytics")?;
+    let days: i64 = input(term, "Days")?.parse()?;
+    let period = Select::new()
+        .with_prompt("Revenue per")
+        .items(&["Day", "Week", "Month"])
+        .default(0)
+        .interact_on(term)?;
+
+    let now = chrono::Utc::now();
+    let filter = AnalyticsFilter::new(now - chrono::Duration::days(days), now);
+    match backend.operations_summary(&filter).await {
+        Ok(summary) => {
+            term.write_line(&to_csv(&[summary]))?;
+            term.write_line(&to_csv(
+                &backend.revenue_by_period(&filter, PERIODS[period]).await?,
+            ))?;
+            term.write_line(&to_csv(&backend.revenue_by_service(&filter).await?))?;
+            term.write_line(&to_csv(&backend.revenue_by_mechanic(&filter).await?))?;
+        }
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+/// Serves the live order board in the background at SHOP_BOARD_ADDRESS, 127.0.0.1:8080 by
+/// default. Viewers need SHOP_BOARD_TOKEN, a random token is made up if it is not set.
+async fn order_board(term: &Term, backend: &ShopBackend) -> Result<()> {
+    let address = env::var("SHOP_BOARD_ADDRESS").unwrap_or(String::from("127.0.0.1:8080"));
+    let token = env::var("SHOP_BOARD_TOKEN").unwrap_or_else(|_| {
+        rand::thread_rng()
+            .sample_iter(&rand::distributions::Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect()
+    });
+    let board = backend.order_board().await?;
+
+    match tokio::net::TcpListener::bind(&address).await {
+        Ok(listener) => {
+            term.write_line(&format!(
+                "Order board is running at http://{address}/?token={token}"
+            ))?;
+            async_std::task::spawn(async move { board.serve(listener, &token).await });
+        }
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
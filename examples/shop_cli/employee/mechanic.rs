@@ -5,10 +5,16 @@ use dialoguer::{console::Term, *};
 use crate::common::*;
 
 pub async fn mechanic_loop(term: &Term, backend: &mut ShopBackend, user: &User) -> Result<()> {
-    static MECHANIC_OPTIONS: [&str; 4] = [
+    static MECHANIC_OPTIONS: [&str; 10] = [
         "List unfinished orders",
         "Change inspection to repair",
         "Close order",
+        "Clock on",
+        "Clock off",
+        "Notes and messages",
+        "Inspection checklist",
+        "Trouble codes",
+        "Record maintenance done",
         "Log out",
     ];
 
@@ -33,7 +39,13 @@ pub async fn mechanic_loop(term: &Term, backend: &mut ShopBackend, user: &User)
             0 => list_unfinished_orders(term, backend).await?,
             1 => change_inspection_to_repair(term, backend).await?,
             2 => close_order(term, backend).await?,
-            3 => {
+            3 => clock(term, backend, true).await?,
+            4 => clock(term, backend, false).await?,
+            5 => notes_and_messages(term, backend).await?,
+            6 => inspection_checklist(term, backend).await?,
+            7 => trouble_codes(term, backend).await?,
+            8 => record_maintenance_done(term, backend).await?,
+            9 => {
                 backend.log_out().await?;
                 break Ok(());
             }
@@ -81,7 +93,13 @@ async fn change_inspection_to_repair(term: &Term, backend: &ShopBackend) -> Resu
         return Ok(());
     }
 
-    match backend.change_inspection_to_repair(order_id).await {
+    let Some(version) = order_version(backend, order_id).await? else {
+        term.write_line(&format!("Order {order_id} is not an unfinished order"))?;
+        wait_for_continue(term)?;
+        return Ok(());
+    };
+
+    match backend.change_inspection_to_repair(order_id, version).await {
         Ok(_) => term.write_line(&format!(
             "Order {order_id} changed from inspection to repair"
         ))?,
@@ -113,7 +131,13 @@ async fn close_order(term: &Term, backend: &ShopBackend) -> Result<()> {
         return Ok(());
     }
 
-    match backend.close_order(order_id).await {
+    let Some(version) = order_version(backend, order_id).await? else {
+        term.write_line(&format!("Order {order_id} is not an unfinished order"))?;
+        wait_for_continue(term)?;
+        return Ok(());
+    };
+
+    match backend.close_order(order_id, version).await {
         Ok(_) => term.write_line(&format!("Order {order_id} closed"))?,
         Err(e) => term.write_line(&format_err(&e))?,
     }
@@ -121,3 +145,315 @@ async fn close_order(term: &Term, backend: &ShopBackend) -> Result<()> {
     wait_for_continue(term)?;
     Ok(())
 }
+
+/// Starts or stops tracking time on an order, stopping and starting again is a pause
+/// Complaint, internal notes and client thread of an order, with a note or reply to add
+async fn notes_and_messages(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Notes and messages")?;
+    let order_id: String = Input::new()
+        .with_prompt("Order ID (or nothing to go back)")
+        .default("0".to_string())
+        .interact_text_on(term)?;
+    let order_id = match order_id.parse::<u32>() {
+        Ok(0) => return Ok(()),
+        Ok(i) => i,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    let complaint = match backend.get_complaint(order_id).await {
+        Ok(complaint) => complaint,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+    term.write_line(&format!(
+        "Complaint: {}",
+        complaint.as_deref().unwrap_or("none")
+    ))?;
+    term.write_line("Notes:")?;
+    for note in backend.get_order_notes(order_id).await? {
+        term.write_line(&format!("{note}"))?;
+    }
+    term.write_line("Messages:")?;
+    for message in backend.get_order_messages(order_id).await? {
+        term.write_line(&format!("{message}"))?;
+    }
+
+    let choice = Select::new()
+        .items(&["Add note", "Reply to client", "Back"])
+        .default(2)
+        .interact_on(term)?;
+    let result = match choice {
+        0 => backend
+            .add_order_note(order_id, &input(term, "Note")?)
+            .await
+            .map(|_| ()),
+        1 => backend
+            .send_order_message(order_id, &input(term, "Message")?)
+            .await
+            .map(|_| ()),
+        _ => return Ok(()),
+    };
+    if let Err(e) = result {
+        term.write_line(&format_err(&e))?;
+        wait_for_continue(term)?;
+    }
+    Ok(())
+}
+
+async fn inspection_checklist(term: &Term, backend: &ShopBackend) -> Result<()> {
+    static STATUSES: [InspectionStatus; 3] = [
+        InspectionStatus::Pass,
+        InspectionStatus::Advisory,
+        InspectionStatus::Fail,
+    ];
+
+    term.write_line("Inspection checklist")?;
+    let order_id: String = Input::new()
+        .with_prompt("Order ID (or nothing to go back)")
+        .default("0".to_string())
+        .interact_text_on(term)?;
+    let order_id = match order_id.parse::<u32>() {
+        Ok(0) => return Ok(()),
+        Ok(i) => i,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    let checklist = match backend.get_inspection_checklist(order_id).await {
+        Ok(checklist) => checklist,
+        Err(DbError::NoChecklist(_)) => {
+            let templates = backend.get_inspection_templates().await?;
+            let names = templates
+                .iter()
+                .map(|template| template.name())
+                .collect::<Vec<_>>();
+            let choice = Select::new()
+                .with_prompt("Checklist")
+                .items(&names)
+                .item("Cancel")
+                .default(0)
+                .interact_on(term)?;
+            let Some(template) = templates.get(choice) else {
+                return Ok(());
+            };
+            match backend.start_inspection(order_id, template.id()).await {
+                Ok(checklist) => checklist,
+                Err(e) => {
+                    term.write_line(&format_err(&e))?;
+                    wait_for_continue(term)?;
+                    return Ok(());
+                }
+            }
+        }
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    term.clear_screen()?;
+    term.write_line(&format!("{checklist}"))?;
+    let items = checklist.template().items();
+    let choice = Select::new()
+        .with_prompt("Check item")
+        .items(items)
+        .item("Back")
+        .default(0)
+        .interact_on(term)?;
+    let Some(item) = items.get(choice) else {
+        return Ok(());
+    };
+
+    let status = Select::new()
+        .with_prompt("Status")
+        .items(&STATUSES)
+        .default(0)
+        .interact_on(term)?;
+    let measurement = match item.unit() {
+        Some(unit) => match input(term, &format!("Measurement in {unit}"))?.parse::<f64>() {
+            Ok(measurement) => Some(measurement),
+            Err(e) => {
+                term.write_line(&format_err(&e))?;
+                wait_for_continue(term)?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    let comment = optional_input(term, "Comment (optional)")?;
+    if let Err(e) = backend
+        .record_inspection_result(
+            order_id,
+            item.id(),
+            STATUSES[status],
+            measurement,
+            Some(&comment),
+        )
+        .await
+    {
+        term.write_line(&format_err(&e))?;
+        wait_for_continue(term)?;
+    }
+    Ok(())
+}
+
+async fn trouble_codes(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Trouble codes")?;
+    let order_id: String = Input::new()
+        .with_prompt("Order ID (or nothing to go back)")
+        .default("0".to_string())
+        .interact_text_on(term)?;
+    let order_id = match order_id.parse::<u32>() {
+        Ok(0) => return Ok(()),
+        Ok(i) => i,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    match backend.get_trouble_codes(order_id).await {
+        Ok(codes) => {
+            for code in codes {
+                term.write_line(&format!("{code}"))?;
+            }
+        }
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    }
+
+    let choice = Select::new()
+        .items(&["Record code", "Clear code", "Back"])
+        .default(2)
+        .interact_on(term)?;
+    let result = match choice {
+        0 => {
+            let code = input(term, "Code, e.g. P0420")?;
+            let freeze_frame = optional_input(term, "Freeze frame as JSON (optional)")?;
+            let freeze_frame = match freeze_frame.trim() {
+                "" => None,
+                json => match serde_json::from_str(json) {
+                    Ok(value) => Some(value),
+                    Err(e) => {
+                        term.write_line(&format_err(&e))?;
+                        wait_for_continue(term)?;
+                        return Ok(());
+                    }
+                },
+            };
+            let cleared = Confirm::new()
+                .with_prompt("Cleared from the car's memory?")
+                .default(false)
+                .interact_on(term)?;
+            backend
+                .record_trouble_code(order_id, &code, freeze_frame, cleared)
+                .await
+                .map(|_| ())
+        }
+        1 => match input(term, "Trouble code ID")?.parse::<u32>() {
+            Ok(id) => backend.clear_trouble_code(id).await.map(|_| ()),
+            Err(e) => {
+                term.write_line(&format_err(&e))?;
+                wait_for_continue(term)?;
+                return Ok(());
+            }
+        },
+        _ => return Ok(()),
+    };
+    if let Err(e) = result {
+        term.write_line(&format_err(&e))?;
+        wait_for_continue(term)?;
+    }
+    Ok(())
+}
+
+async fn clock(term: &Term, backend: &ShopBackend, on: bool) -> Result<()> {
+    term.write_line(if on { "Clock on" } else { "Clock off" })?;
+    let order_id: String = Input::new()
+        .with_prompt("Order ID (or nothing to go back)")
+        .default("0".to_string())
+        .interact_text_on(term)?;
+    let order_id = match order_id.parse::<u32>() {
+        Ok(0) => return Ok(()),
+        Ok(i) => i,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+
+    let result = match on {
+        true => backend.clock_on(order_id).await,
+        false => backend.clock_off(order_id).await,
+    };
+    match result {
+        Ok(entry) => {
+            term.write_line(&entry.to_string())?;
+            let labor = backend.get_labor(order_id).await?;
+            term.write_line(&format!(
+                "Tracked: {:.2} h | Estimate: {}",
+                labor.actual_hours(),
+                labor
+                    .estimated_hours()
+                    .map_or(String::from("none"), |hours| format!("{hours:.2} h"))
+            ))?;
+        }
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
+
+/// Version of an unfinished order, which changing it requires
+async fn order_version(backend: &ShopBackend, order_id: u32) -> Result<Option<u32>> {
+    for order in backend.get_unfinished_orders().await? {
+        let order: Order = serde_json::from_str(&order)?;
+        if order.id() == order_id {
+            return Ok(Some(order.version()));
+        }
+    }
+    Ok(None)
+}
+
+async fn record_maintenance_done(term: &Term, backend: &ShopBackend) -> Result<()> {
+    term.write_line("Record maintenance done")?;
+    for schedule in backend.get_maintenance_schedules().await? {
+        term.write_line(&format!("{schedule}"))?;
+    }
+
+    let ids = (
+        input(term, "Order ID")?.parse::<u32>(),
+        input(term, "Schedule ID")?.parse::<u32>(),
+    );
+    match ids {
+        (Ok(order_id), Ok(schedule_id)) => {
+            match backend.record_maintenance_done(order_id, schedule_id).await {
+                Ok(()) => term.write_line(&format!(
+                    "Order {order_id} recorded as doing maintenance {schedule_id}"
+                ))?,
+                Err(e) => term.write_line(&format_err(&e))?,
+            }
+        }
+        (Err(e), _) | (_, Err(e)) => term.write_line(&format_err(&e))?,
+    }
+
+    wait_for_continue(term)?;
+    Ok(())
+}
@@ -1,8 +1,8 @@
+mod manager;
 mod mechanic;
 mod technician;
 
 use anyhow::Result;
-use bcrypt::DEFAULT_COST;
 use car_repair_shop_backend::*;
 use console::Term;
 use dialoguer::*;
@@ -10,7 +10,7 @@ use zeroize::Zeroizing;
 
 use crate::common::*;
 
-use self::{mechanic::mechanic_loop, technician::technician_loop};
+use self::{manager::manager_loop, mechanic::mechanic_loop, technician::technician_loop};
 
 pub async fn employee_loop(term: &Term, mut backend: ShopBackend) -> Result<()> {
     loop {
@@ -19,6 +19,7 @@ pub async fn employee_loop(term: &Term, mut backend: ShopBackend) -> Result<()>
         match &user.user_type() {
             UserType::Technician => technician_loop(term, &mut backend, &user).await?,
             UserType::Mechanic => mechanic_loop(term, &mut backend, &user).await?,
+            UserType::Manager => manager_loop(term, &mut backend, &user).await?,
             _ => unreachable!(),
         }
     }
@@ -45,7 +46,6 @@ async fn login_screen(term: &Term, backend: &mut ShopBackend) -> Result<User> {
 
         let id: String = input(term, "ID")?;
         let password = Zeroizing::new(Password::new().with_prompt("Password").interact_on(term)?);
-        let password_hash = bcrypt::hash(password, DEFAULT_COST)?;
 
         let id = match id.parse::<u32>() {
             Ok(i) => i,
@@ -55,7 +55,22 @@ async fn login_screen(term: &Term, backend: &mut ShopBackend) -> Result<User> {
             }
         };
 
-        let user = backend.employee_login(id, &password_hash).await;
+        let user = match backend.employee_login(id, &password).await {
+            Err(LoginError::SecondFactorRequired(_)) => {
+                let code = input(term, "Authentication or recovery code")?;
+                backend.employee_login_second_factor(&code).await
+            }
+            Err(LoginError::TwoFactorEnrollmentRequired(_)) => {
+                match enroll_two_factor(term, backend).await? {
+                    Some(employee) => Ok(employee),
+                    None => {
+                        wait_for_continue(term)?;
+                        continue;
+                    }
+                }
+            }
+            res => res,
+        };
 
         term.clear_screen()?;
         match user {
@@ -68,3 +83,36 @@ async fn login_screen(term: &Term, backend: &mut ShopBackend) -> Result<User> {
         }
     }
 }
+
+async fn enroll_two_factor(term: &Term, backend: &mut ShopBackend) -> Result<Option<User>> {
+    term.clear_screen()?;
+    term.write_line("Two-factor authentication is required")?;
+
+    let enrollment = match backend.begin_totp_enrollment().await {
+        Ok(enrollment) => enrollment,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            return Ok(None);
+        }
+    };
+
+    term.write_line("Add this account to your authenticator app:")?;
+    term.write_line(enrollment.uri())?;
+    term.write_line(&format!("Secret: {}", enrollment.secret()))?;
+
+    let code = input(term, "Authentication code")?;
+    match backend.confirm_totp_enrollment(&code).await {
+        Ok(recovery_codes) => {
+            term.write_line("Recovery codes, each can be used once instead of a code:")?;
+            for code in recovery_codes {
+                term.write_line(&code)?;
+            }
+            wait_for_continue(term)?;
+            Ok(Some(backend.user().clone()))
+        }
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            Ok(None)
+        }
+    }
+}
@@ -5,11 +5,12 @@ use dialoguer::{console::Term, *};
 use crate::common::*;
 
 pub async fn technician_loop(term: &Term, backend: &mut ShopBackend, user: &User) -> Result<()> {
-    static TECHNICIAN_OPTIONS: [&str; 5] = [
+    static TECHNICIAN_OPTIONS: [&str; 6] = [
         "Register car",
         "Register order",
         "List finished reports",
         "Create report",
+        "Correct report",
         "Log out",
     ];
 
@@ -35,7 +36,8 @@ pub async fn technician_loop(term: &Term, backend: &mut ShopBackend, user: &User
             1 => register_order(term, backend).await?,
             2 => list_finished_orders(term, backend).await?,
             3 => register_report(term, backend).await?,
-            4 => {
+            4 => correct_report(term, backend).await?,
+            5 => {
                 backend.log_out().await?;
                 break Ok(());
             }
@@ -113,8 +115,15 @@ async fn register_order(term: &Term, backend: &ShopBackend) -> Result<()> {
         return Ok(());
     }
 
+    let Some(location_id) = select_location(term, backend).await? else {
+        return Ok(());
+    };
+
     let service = &SERVICES[service];
-    match backend.register_order(client_id, service).await {
+    match backend
+        .register_order(client_id, location_id, service, None)
+        .await
+    {
         Ok(_) => {
             term.write_line(&format!(
                 "Order for {} for client {client_id} registered",
@@ -189,3 +198,63 @@ async fn register_report(term: &Term, backend: &ShopBackend) -> Result<()> {
 
     Ok(())
 }
+
+async fn correct_report(term: &Term, backend: &ShopBackend) -> Result<()> {
+    let report_id = loop {
+        term.write_line("Correct report")?;
+        let report_id: String = Input::new()
+            .with_prompt("Report ID (or nothing to go back)")
+            .default(String::from("0"))
+            .interact_text_on(term)?;
+        match report_id.parse::<u32>() {
+            Ok(i) => break i,
+            Err(e) => {
+                term.write_line(&format_err(&e))?;
+                wait_for_continue(term)?;
+                continue;
+            }
+        }
+    };
+
+    if report_id == 0 {
+        return Ok(());
+    }
+
+    let report = match backend.get_report(report_id).await {
+        Ok(report) => report,
+        Err(e) => {
+            term.write_line(&format_err(&e))?;
+            wait_for_continue(term)?;
+            return Ok(());
+        }
+    };
+    term.write_line(&format!("{report}"))?;
+
+    let cost = loop {
+        let cost: String = Input::new()
+            .with_prompt("Corrected cost")
+            .interact_text_on(term)?;
+        match cost.parse::<u32>() {
+            Ok(i) => break i,
+            Err(e) => {
+                term.write_line(&format_err(&e))?;
+                wait_for_continue(term)?;
+                continue;
+            }
+        }
+    };
+
+    match backend
+        .correct_report(report_id, report.version(), cost)
+        .await
+    {
+        Ok(corrected) => term.write_line(&format!(
+            "Report {report_id} has been replaced by report {}",
+            corrected.id()
+        ))?,
+        Err(e) => term.write_line(&format_err(&e))?,
+    }
+    wait_for_continue(term)?;
+
+    Ok(())
+}
@@ -6,7 +6,9 @@ use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
 use dialoguer::console::Term;
 
-use car_repair_shop_backend::ShopBackend;
+use car_repair_shop_backend::{ConsoleNotifier, ShopBackend};
+
+use std::time::Duration;
 
 use client::client_loop;
 use employee::employee_loop;
@@ -14,6 +16,9 @@ use employee::employee_loop;
 #[derive(Parser)]
 #[command(author, about, long_about = None)]
 struct Cli {
+    /// Print emails to standard error instead of sending them, tokens included
+    #[arg(long)]
+    print_emails: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -34,7 +39,19 @@ async fn main() -> Result<()> {
             bail!(e)
         }
     }
-    let backend = ShopBackend::init().await?;
+    let mut backend = ShopBackend::init().await?;
+    // Queued notifications and webhooks are sent in the background by a second backend
+    let mut dispatcher = ShopBackend::init().await?;
+    if cli.print_emails {
+        backend.set_notifier(ConsoleNotifier::new(std::io::stderr()));
+        dispatcher.set_notifier(ConsoleNotifier::new(std::io::stderr()));
+    }
+    async_std::task::spawn(async move {
+        loop {
+            let _ = dispatcher.deliver_pending().await;
+            async_std::task::sleep(Duration::from_secs(5)).await;
+        }
+    });
     let term = Term::stdout();
     match cli.command {
         Commands::Client => client_loop(&term, backend).await?,
@@ -0,0 +1,251 @@
+//! Numbers about the business of the shop for managers, see [`ShopBackend::revenue_by_period`]
+//!
+//! Revenue is what current reports charge, reports replaced by corrections do not count.
+//! Amounts are in cents like [`Report::cost`]. Every result exports to CSV with [`to_csv`].
+//!
+//! [`ShopBackend::revenue_by_period`]: crate::ShopBackend::revenue_by_period
+//! [`Report::cost`]: crate::Report::cost
+
+use crate::Service;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+/// Reports, orders and payments taken into account by analytics
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnalyticsFilter {
+    /// Inclusive
+    pub from: DateTime<Utc>,
+    /// Exclusive
+    pub until: DateTime<Utc>,
+    /// Managers assigned to a location only see their own location
+    pub location_id: Option<u32>,
+}
+
+impl AnalyticsFilter {
+    pub fn new(from: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        AnalyticsFilter {
+            from,
+            until,
+            location_id: None,
+        }
+    }
+}
+
+/// Length of the periods revenue is grouped by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Period {
+    Day,
+    /// Weeks start on Monday
+    Week,
+    Month,
+}
+
+/// Revenue of reports issued in a period
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeriodRevenue {
+    /// First day of the period
+    pub period_start: NaiveDate,
+    pub reports: u64,
+    pub revenue: u64,
+}
+
+/// Revenue of the reports of one kind of service
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ServiceRevenue {
+    pub service: Service,
+    pub reports: u64,
+    pub revenue: u64,
+}
+
+/// Revenue of the orders one mechanic finished
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MechanicRevenue {
+    pub mechanic_id: u32,
+    pub name: String,
+    pub reports: u64,
+    pub revenue: u64,
+}
+
+/// Report whose payments do not cover its cost yet
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Receivable {
+    pub report_id: u32,
+    pub order_id: u32,
+    pub client_id: u32,
+    pub location_id: u32,
+    pub cost: u64,
+    pub paid: u64,
+    /// Unknown for reports issued before issue dates were recorded
+    pub issued_at: Option<DateTime<Utc>>,
+}
+
+impl Receivable {
+    pub fn outstanding(&self) -> u64 {
+        self.cost.saturating_sub(self.paid)
+    }
+}
+
+/// Key figures of the shop in a date range
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OperationsSummary {
+    /// Reports issued in the range
+    pub reports: u64,
+    pub revenue: u64,
+    /// Average cost of a report, `None` without reports
+    pub average_ticket: Option<f64>,
+    /// Orders finished in the range
+    pub finished_orders: u64,
+    /// Average time from registering an order to finishing it, `None` without finished orders
+    pub average_turnaround_hours: Option<f64>,
+    /// Orders registered in the range as inspections, including those that became repairs
+    pub inspections: u64,
+    /// Inspections that became repairs
+    pub converted_to_repair: u64,
+    /// Unpaid costs of all current reports, regardless of the range
+    pub outstanding_receivables: u64,
+}
+
+impl OperationsSummary {
+    /// Share of inspections that became repairs, `None` without inspections
+    pub fn conversion_rate(&self) -> Option<f64> {
+        (self.inspections > 0).then(|| self.converted_to_repair as f64 / self.inspections as f64)
+    }
+}
+
+/// Analytics result that can be a row of a CSV file
+pub trait CsvRecord {
+    /// Names of the columns
+    fn header() -> &'static [&'static str];
+
+    /// Values in the order of [`CsvRecord::header`]
+    fn fields(&self) -> Vec<String>;
+}
+
+/// CSV file with a header line and a line for every record, as described in RFC 4180
+pub fn to_csv<T: CsvRecord>(records: &[T]) -> String {
+    let mut csv = csv_line(T::header().iter().map(|name| name.to_string()));
+    for record in records {
+        csv.push_str(&csv_line(record.fields()));
+    }
+    csv
+}
+
+fn csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl CsvRecord for PeriodRevenue {
+    fn header() -> &'static [&'static str] {
+        &["period_start", "reports", "revenue"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.period_start.to_string(),
+            self.reports.to_string(),
+            self.revenue.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for ServiceRevenue {
+    fn header() -> &'static [&'static str] {
+        &["service", "reports", "revenue"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.service.to_string(),
+            self.reports.to_string(),
+            self.revenue.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for MechanicRevenue {
+    fn header() -> &'static [&'static str] {
+        &["mechanic_id", "name", "reports", "revenue"]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.mechanic_id.to_string(),
+            self.name.clone(),
+            self.reports.to_string(),
+            self.revenue.to_string(),
+        ]
+    }
+}
+
+impl CsvRecord for Receivable {
+    fn header() -> &'static [&'static str] {
+        &[
+            "report_id",
+            "order_id",
+            "client_id",
+            "location_id",
+            "cost",
+            "paid",
+            "outstanding",
+            "issued_at",
+        ]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.report_id.to_string(),
+            self.order_id.to_string(),
+            self.client_id.to_string(),
+            self.location_id.to_string(),
+            self.cost.to_string(),
+            self.paid.to_string(),
+            self.outstanding().to_string(),
+            optional(self.issued_at.map(|at| at.to_rfc3339())),
+        ]
+    }
+}
+
+impl CsvRecord for OperationsSummary {
+    fn header() -> &'static [&'static str] {
+        &[
+            "reports",
+            "revenue",
+            "average_ticket",
+            "finished_orders",
+            "average_turnaround_hours",
+            "inspections",
+            "converted_to_repair",
+            "conversion_rate",
+            "outstanding_receivables",
+        ]
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.reports.to_string(),
+            self.revenue.to_string(),
+            optional(self.average_ticket.map(|v| format!("{v:.2}"))),
+            self.finished_orders.to_string(),
+            optional(self.average_turnaround_hours.map(|v| format!("{v:.2}"))),
+            self.inspections.to_string(),
+            self.converted_to_repair.to_string(),
+            optional(self.conversion_rate().map(|v| format!("{v:.4}"))),
+            self.outstanding_receivables.to_string(),
+        ]
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use rand::RngCore;
+
+use super::BlobStore;
+use crate::BlobError;
+
+use std::env;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Stores blobs as files in a directory, in subdirectories named after the first two
+/// characters of their keys
+#[derive(Clone, Debug)]
+pub struct FileBlobStore {
+    root: PathBuf,
+}
+
+impl FileBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileBlobStore { root: root.into() }
+    }
+
+    /// Stores blobs in the directory in SHOP_BLOB_DIR environment variable, ./attachments
+    /// by default
+    pub fn from_env() -> Self {
+        Self::new(env::var("SHOP_BLOB_DIR").unwrap_or(String::from("./attachments")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, BlobError> {
+        let valid = key.len() > 2
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(BlobError(format!("{key} is not a valid blob key")));
+        }
+        Ok(self.root.join(&key[..2]).join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for FileBlobStore {
+    async fn put(&self, key: &str, content: &[u8]) -> Result<(), BlobError> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| BlobError(e.to_string()))?;
+        }
+
+        // Writing next to the blob and renaming, so that readers never see half a file
+        let mut suffix = [0u8; 4];
+        rand::thread_rng().fill_bytes(&mut suffix);
+        let partial = path.with_extension(format!("partial-{}", hex::encode(suffix)));
+        tokio::fs::write(&partial, content)
+            .await
+            .map_err(|e| BlobError(e.to_string()))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .map_err(|e| BlobError(e.to_string()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BlobError> {
+        tokio::fs::read(self.path(key)?)
+            .await
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => BlobError(format!("blob {key} does not exist")),
+                _ => BlobError(e.to_string()),
+            })
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(BlobError(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+mod file;
+
+use async_trait::async_trait;
+use image::ImageFormat;
+
+use crate::BlobError;
+
+use std::io::Cursor;
+
+pub use file::FileBlobStore;
+
+/// Keeps the content of attachments, the database only has their metadata.
+///
+/// Content is stored under its SHA-256, so a file attached several times is stored once.
+/// Frontends plug in their own store, e.g. for object storage, with
+/// [`ShopBackend::set_blob_store`](crate::ShopBackend::set_blob_store).
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Stores `content` under `key`, replacing what was stored under it
+    async fn put(&self, key: &str, content: &[u8]) -> Result<(), BlobError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BlobError>;
+
+    /// Removes what is stored under `key`, succeeds if nothing is
+    async fn delete(&self, key: &str) -> Result<(), BlobError>;
+}
+
+/// Largest thumbnail side in pixels
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// MIME type of an attachment that can be stored, told by its first bytes rather than
+/// what the uploader claims
+pub(crate) fn content_type(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if content.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+/// PNG of at most [`THUMBNAIL_SIZE`] pixels per side, `None` for documents and images
+/// that cannot be decoded
+pub(crate) fn thumbnail(content: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(content).ok()?;
+    let mut png = Cursor::new(Vec::new());
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut png, ImageFormat::Png)
+        .ok()?;
+    Some(png.into_inner())
+}
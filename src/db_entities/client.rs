@@ -12,11 +12,26 @@ pub struct Model {
     pub email: String,
     pub password_hash: String,
     pub car: Option<Car>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub communication_preferences: Option<CommunicationPreferences>,
+    pub email_verified: bool,
+    pub deleted: bool,
+    pub verification_sent_at: Option<ChronoDateTimeUtc>,
 }
 
 impl From<Model> for crate::Client {
     fn from(val: Model) -> Self {
-        crate::Client::new(val.id as u32, &val.name, &val.email, val.car)
+        crate::Client::new(
+            val.id as u32,
+            &val.name,
+            &val.email,
+            val.car,
+            val.phone.as_deref(),
+            val.address.as_deref(),
+            val.communication_preferences.unwrap_or_default(),
+            val.email_verified,
+        )
     }
 }
 
@@ -32,20 +47,62 @@ impl std::fmt::Display for Car {
     }
 }
 
+/// Channels a client agreed to be contacted through
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct CommunicationPreferences {
+    pub email: bool,
+    pub sms: bool,
+    pub marketing: bool,
+    /// Reminders when maintenance of the car is due, on unless the client opts out
+    #[serde(default = "reminders_default")]
+    pub maintenance_reminders: bool,
+}
+
+fn reminders_default() -> bool {
+    true
+}
+
+impl Default for CommunicationPreferences {
+    fn default() -> Self {
+        CommunicationPreferences {
+            email: true,
+            sms: false,
+            marketing: false,
+            maintenance_reminders: true,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::notification::Entity")]
+    Notification,
     #[sea_orm(has_many = "super::order::Entity")]
     Order,
+    #[sea_orm(has_many = "super::payment::Entity")]
+    Payment,
     #[sea_orm(has_many = "super::report::Entity")]
     Report,
 }
 
+impl Related<super::notification::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notification.def()
+    }
+}
+
 impl Related<super::order::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Order.def()
     }
 }
 
+impl Related<super::payment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Payment.def()
+    }
+}
+
 impl Related<super::report::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Report.def()
@@ -11,6 +11,11 @@ pub struct Model {
     pub password_hash: String,
     pub name: String,
     pub role: Role,
+    pub email: Option<String>,
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub totp_last_step: Option<i64>,
+    pub location_id: Option<i32>,
 }
 
 impl From<Model> for crate::Employee {
@@ -19,6 +24,16 @@ impl From<Model> for crate::Employee {
     }
 }
 
+impl From<Role> for crate::UserType {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Technician => crate::UserType::Technician,
+            Role::Mechanic => crate::UserType::Mechanic,
+            Role::Manager => crate::UserType::Manager,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "role")]
 pub enum Role {
@@ -26,6 +41,8 @@ pub enum Role {
     Technician,
     #[sea_orm(string_value = "Mechanic")]
     Mechanic,
+    #[sea_orm(string_value = "Manager")]
+    Manager,
 }
 
 impl std::fmt::Display for Role {
@@ -33,11 +50,21 @@ impl std::fmt::Display for Role {
         match self {
             Role::Technician => f.write_str("technician"),
             Role::Mechanic => f.write_str("mechanic"),
+            Role::Manager => f.write_str("manager"),
         }
     }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::recovery_code::Entity")]
+    RecoveryCode,
+}
+
+impl Related<super::recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCode.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
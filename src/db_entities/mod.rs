@@ -2,7 +2,34 @@
 
 pub mod prelude;
 
+pub mod attachment;
+pub mod bay;
+pub mod blob;
 pub mod client;
 pub mod employee;
+pub mod inspection_item;
+pub mod inspection_result;
+pub mod inspection_template;
+pub mod labor_entry;
+pub mod labor_line;
+pub mod location;
+pub mod maintenance_done;
+pub mod maintenance_reminder;
+pub mod maintenance_schedule;
+pub mod mileage_reading;
+pub mod notification;
+pub mod notification_outbox;
 pub mod order;
+pub mod order_message;
+pub mod order_note;
+pub mod order_part;
+pub mod password_reset_token;
+pub mod payment;
+pub mod recovery_code;
+pub mod repair_suggestion;
 pub mod report;
+pub mod setting;
+pub mod stock_item;
+pub mod trouble_code;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
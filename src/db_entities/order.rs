@@ -11,8 +11,18 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub client_id: i32,
+    pub location_id: i32,
     pub service: Service,
     pub finished: bool,
+    pub version: i32,
+    pub created_at: Option<DateTimeUtc>,
+    pub finished_at: Option<DateTimeUtc>,
+    pub mechanic_id: Option<i32>,
+    pub converted_to_repair: bool,
+    pub estimated_minutes: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub complaint: Option<String>,
+    pub inspection_template_id: Option<i32>,
 }
 
 impl From<Model> for Order {
@@ -20,8 +30,14 @@ impl From<Model> for Order {
         Order::new(
             value.id as u32,
             value.client_id as u32,
+            value.location_id as u32,
             value.service,
             value.finished,
+            value.version as u32,
+            value.created_at,
+            value.finished_at,
+            value.mechanic_id.map(|id| id as u32),
+            value.converted_to_repair,
         )
     }
 }
@@ -1,6 +1,32 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
+pub use super::attachment::Entity as Attachment;
+pub use super::bay::Entity as Bay;
 pub use super::client::Entity as Client;
 pub use super::employee::Entity as Employee;
+pub use super::inspection_item::Entity as InspectionItem;
+pub use super::inspection_result::Entity as InspectionResult;
+pub use super::inspection_template::Entity as InspectionTemplate;
+pub use super::labor_entry::Entity as LaborEntry;
+pub use super::labor_line::Entity as LaborLine;
+pub use super::location::Entity as Location;
+pub use super::maintenance_done::Entity as MaintenanceDone;
+pub use super::maintenance_reminder::Entity as MaintenanceReminder;
+pub use super::maintenance_schedule::Entity as MaintenanceSchedule;
+pub use super::mileage_reading::Entity as MileageReading;
+pub use super::notification::Entity as Notification;
+pub use super::notification_outbox::Entity as NotificationOutbox;
 pub use super::order::Entity as Order;
+pub use super::order_message::Entity as OrderMessage;
+pub use super::order_note::Entity as OrderNote;
+pub use super::order_part::Entity as OrderPart;
+pub use super::password_reset_token::Entity as PasswordResetToken;
+pub use super::payment::Entity as Payment;
+pub use super::recovery_code::Entity as RecoveryCode;
+pub use super::repair_suggestion::Entity as RepairSuggestion;
 pub use super::report::Entity as Report;
+pub use super::setting::Entity as Setting;
+pub use super::stock_item::Entity as StockItem;
+pub use super::trouble_code::Entity as TroubleCode;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
+pub use super::webhook_subscription::Entity as WebhookSubscription;
@@ -10,7 +10,12 @@ pub struct Model {
     pub id: i32,
     pub client_id: i32,
     pub order_id: i32,
+    pub location_id: i32,
     pub cost: i32,
+    pub version: i32,
+    pub revision: i32,
+    pub superseded_by: Option<i32>,
+    pub created_at: Option<ChronoDateTimeUtc>,
 }
 
 impl From<Model> for crate::Report {
@@ -19,7 +24,12 @@ impl From<Model> for crate::Report {
             value.id as u32,
             value.client_id as u32,
             value.order_id as u32,
+            value.location_id as u32,
             value.cost as u32,
+            value.revision as u32,
+            value.superseded_by.map(|id| id as u32),
+            value.version as u32,
+            value.created_at,
         )
     }
 }
@@ -42,6 +52,8 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Order,
+    #[sea_orm(has_many = "super::payment::Entity")]
+    Payment,
 }
 
 impl Related<super::client::Entity> for Entity {
@@ -56,4 +68,10 @@ impl Related<super::order::Entity> for Entity {
     }
 }
 
+impl Related<super::payment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Payment.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
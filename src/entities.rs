@@ -1,5 +1,9 @@
-use crate::{db_entities::employee::Role, Car, Service};
+use crate::{
+    db_entities::employee::Role, describe_trouble_code, Car, CommunicationPreferences,
+    InspectionStatus, Service, Visibility, WebhookEventType,
+};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use std::fmt::Display;
@@ -10,15 +14,33 @@ pub struct Client {
     name: String,
     email: String,
     car: Option<Car>,
+    phone: Option<String>,
+    address: Option<String>,
+    communication_preferences: CommunicationPreferences,
+    email_verified: bool,
 }
 
 impl Client {
-    pub fn new(id: u32, name: &str, email: &str, car: Option<Car>) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        name: &str,
+        email: &str,
+        car: Option<Car>,
+        phone: Option<&str>,
+        address: Option<&str>,
+        communication_preferences: CommunicationPreferences,
+        email_verified: bool,
+    ) -> Self {
         Client {
             id,
             name: name.to_string(),
             email: email.to_string(),
             car,
+            phone: phone.map(str::to_string),
+            address: address.map(str::to_string),
+            communication_preferences,
+            email_verified,
         }
     }
 
@@ -37,31 +59,1059 @@ impl Client {
     pub fn car(&self) -> Option<Car> {
         self.car.clone()
     }
+
+    pub fn phone(&self) -> Option<&str> {
+        self.phone.as_deref()
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn communication_preferences(&self) -> CommunicationPreferences {
+        self.communication_preferences
+    }
+
+    pub fn email_verified(&self) -> bool {
+        self.email_verified
+    }
+}
+
+impl Display for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ID: {} | Name: {} | Email: {})",
+            self.name, self.id, self.email
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Employee {
+    id: u32,
+    name: String,
+    role: Role,
+}
+
+impl Employee {
+    pub fn new(id: u32, name: &str, role: Role) -> Self {
+        Employee {
+            id,
+            name: name.to_string(),
+            role,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+}
+
+impl Display for Employee {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ID: {} | Name: {} | Role: {})",
+            self.id, self.name, self.role
+        )
+    }
+}
+
+/// Workshop of the shop chain
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Location {
+    id: u32,
+    name: String,
+    address: Option<String>,
+}
+
+impl Location {
+    pub fn new(id: u32, name: &str, address: Option<&str>) -> Self {
+        Location {
+            id,
+            name: name.to_string(),
+            address: address.map(str::to_string),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ID: {} | Name: {}", self.id, self.name)?;
+        match &self.address {
+            Some(address) => write!(f, " | Address: {address}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Place in a workshop where a car is worked on
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bay {
+    id: u32,
+    location_id: u32,
+    name: String,
+}
+
+impl Bay {
+    pub fn new(id: u32, location_id: u32, name: &str) -> Self {
+        Bay {
+            id,
+            location_id,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn location_id(&self) -> u32 {
+        self.location_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for Bay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ID: {} | Location: {} | Name: {}",
+            self.id, self.location_id, self.name
+        )
+    }
+}
+
+/// Parts with the same part number kept at one location
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StockItem {
+    id: u32,
+    location_id: u32,
+    part_number: String,
+    name: String,
+    quantity: u32,
+}
+
+impl StockItem {
+    pub fn new(id: u32, location_id: u32, part_number: &str, name: &str, quantity: u32) -> Self {
+        StockItem {
+            id,
+            location_id,
+            part_number: part_number.to_string(),
+            name: name.to_string(),
+            quantity,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn location_id(&self) -> u32 {
+        self.location_id
+    }
+
+    pub fn part_number(&self) -> &str {
+        &self.part_number
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+}
+
+impl Display for StockItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | Location: {} | Quantity: {}",
+            self.part_number, self.name, self.location_id, self.quantity
+        )
+    }
+}
+
+/// Parts taken from stock for an order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PartUsage {
+    order_id: u32,
+    part_number: String,
+    name: String,
+    quantity: u32,
+}
+
+impl PartUsage {
+    pub fn new(order_id: u32, part_number: &str, name: &str, quantity: u32) -> Self {
+        PartUsage {
+            order_id,
+            part_number: part_number.to_string(),
+            name: name.to_string(),
+            quantity,
+        }
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    pub fn part_number(&self) -> &str {
+        &self.part_number
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+}
+
+impl Display for PartUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} x {} ({})",
+            self.quantity, self.name, self.part_number
+        )
+    }
+}
+
+/// Odometer of a client's car when it was checked in for an order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MileageReading {
+    id: u32,
+    client_id: u32,
+    order_id: u32,
+    mileage: u32,
+    recorded_at: DateTime<Utc>,
+    reason: Option<String>,
+}
+
+impl MileageReading {
+    pub fn new(
+        id: u32,
+        client_id: u32,
+        order_id: u32,
+        mileage: u32,
+        recorded_at: DateTime<Utc>,
+        reason: Option<&str>,
+    ) -> Self {
+        MileageReading {
+            id,
+            client_id,
+            order_id,
+            mileage,
+            recorded_at,
+            reason: reason.map(|r| r.to_string()),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn client_id(&self) -> u32 {
+        self.client_id
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    /// Kilometers on the odometer
+    pub fn mileage(&self) -> u32 {
+        self.mileage
+    }
+
+    pub fn recorded_at(&self) -> DateTime<Utc> {
+        self.recorded_at
+    }
+
+    /// Why the mileage is lower than the reading before it
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Whether the mileage went down, which is only allowed with a reason
+    pub fn flagged(&self) -> bool {
+        self.reason.is_some()
+    }
+}
+
+impl Display for MileageReading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} km | Order: {}",
+            self.recorded_at.format("%Y-%m-%d"),
+            self.mileage,
+            self.order_id
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " | Flagged: {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Finding or remark about an order, only employees see these
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderNote {
+    id: u32,
+    order_id: u32,
+    employee_id: u32,
+    body: String,
+    created_at: DateTime<Utc>,
+}
+
+impl OrderNote {
+    pub fn new(
+        id: u32,
+        order_id: u32,
+        employee_id: u32,
+        body: &str,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        OrderNote {
+            id,
+            order_id,
+            employee_id,
+            body: body.to_string(),
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    pub fn employee_id(&self) -> u32 {
+        self.employee_id
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Display for OrderNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | Employee: {} | {}",
+            self.created_at.format("%Y-%m-%d %H:%M"),
+            self.employee_id,
+            self.body
+        )
+    }
+}
+
+/// Message in the thread between a client and the shop about an order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderMessage {
+    id: u32,
+    order_id: u32,
+    employee_id: Option<u32>,
+    body: String,
+    sent_at: DateTime<Utc>,
+    read_at: Option<DateTime<Utc>>,
+}
+
+impl OrderMessage {
+    pub fn new(
+        id: u32,
+        order_id: u32,
+        employee_id: Option<u32>,
+        body: &str,
+        sent_at: DateTime<Utc>,
+        read_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        OrderMessage {
+            id,
+            order_id,
+            employee_id,
+            body: body.to_string(),
+            sent_at,
+            read_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    /// Employee who wrote for the shop, `None` if the client wrote the message
+    pub fn employee_id(&self) -> Option<u32> {
+        self.employee_id
+    }
+
+    pub fn from_client(&self) -> bool {
+        self.employee_id.is_none()
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn sent_at(&self) -> DateTime<Utc> {
+        self.sent_at
+    }
+
+    /// When the other side first read the message
+    pub fn read_at(&self) -> Option<DateTime<Utc>> {
+        self.read_at
+    }
+}
+
+impl Display for OrderMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} | ", self.sent_at.format("%Y-%m-%d %H:%M"))?;
+        match self.employee_id {
+            Some(id) => write!(f, "Shop (employee {id})")?,
+            None => f.write_str("Client")?,
+        }
+        write!(
+            f,
+            " | {} | {}",
+            if self.read_at.is_some() {
+                "read"
+            } else {
+                "unread"
+            },
+            self.body
+        )
+    }
+}
+
+/// Photo or document attached to an order, its content is in the blob store
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attachment {
+    id: u32,
+    order_id: u32,
+    employee_id: Option<u32>,
+    file_name: String,
+    content_type: String,
+    size: u64,
+    hash: String,
+    has_thumbnail: bool,
+    visibility: Visibility,
+    created_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        order_id: u32,
+        employee_id: Option<u32>,
+        file_name: &str,
+        content_type: &str,
+        size: u64,
+        hash: &str,
+        has_thumbnail: bool,
+        visibility: Visibility,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Attachment {
+            id,
+            order_id,
+            employee_id,
+            file_name: file_name.to_string(),
+            content_type: content_type.to_string(),
+            size,
+            hash: hash.to_string(),
+            has_thumbnail,
+            visibility,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    /// Employee who uploaded the attachment, `None` if the client did
+    pub fn employee_id(&self) -> Option<u32> {
+        self.employee_id
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// Bytes of content
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Hex encoded SHA-256 of the content
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    pub fn has_thumbnail(&self) -> bool {
+        self.has_thumbnail
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Display for Attachment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ID: {} | {} | {} | {} bytes | Visible to: {}",
+            self.id,
+            self.file_name,
+            self.content_type,
+            self.size,
+            match self.visibility {
+                Visibility::Internal => "employees",
+                Visibility::Client => "employees and client",
+            }
+        )
+    }
+}
+
+/// Something checked during an inspection, e.g. tread depth of the front left tire
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InspectionItem {
+    id: u32,
+    section: String,
+    name: String,
+    unit: Option<String>,
+}
+
+impl InspectionItem {
+    pub fn new(id: u32, section: &str, name: &str, unit: Option<&str>) -> Self {
+        InspectionItem {
+            id,
+            section: section.to_string(),
+            name: name.to_string(),
+            unit: unit.map(str::to_string),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Part of the checklist the item is in, e.g. Brakes
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Unit of the item's measurement, `None` for items that are only looked at
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+}
+
+impl Display for InspectionItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ID: {} | {}: {}", self.id, self.section, self.name)?;
+        if let Some(unit) = &self.unit {
+            write!(f, " ({unit})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checklist an inspection follows, its items are in the order they are checked
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InspectionTemplate {
+    id: u32,
+    name: String,
+    items: Vec<InspectionItem>,
+}
+
+impl InspectionTemplate {
+    pub fn new(id: u32, name: &str, items: Vec<InspectionItem>) -> Self {
+        InspectionTemplate {
+            id,
+            name: name.to_string(),
+            items,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn items(&self) -> &[InspectionItem] {
+        &self.items
+    }
+
+    /// Items grouped by section, sections in the order they first appear
+    pub fn sections(&self) -> Vec<(&str, Vec<&InspectionItem>)> {
+        let mut sections: Vec<(&str, Vec<&InspectionItem>)> = Vec::new();
+        for item in &self.items {
+            match sections.iter_mut().find(|(name, _)| *name == item.section) {
+                Some((_, items)) => items.push(item),
+                None => sections.push((&item.section, vec![item])),
+            }
+        }
+        sections
+    }
+}
+
+/// What a mechanic found when checking an item of an order's checklist
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InspectionResult {
+    item_id: u32,
+    status: InspectionStatus,
+    measurement: Option<f64>,
+    comment: Option<String>,
+    mechanic_id: u32,
+    recorded_at: DateTime<Utc>,
+}
+
+impl InspectionResult {
+    pub fn new(
+        item_id: u32,
+        status: InspectionStatus,
+        measurement: Option<f64>,
+        comment: Option<&str>,
+        mechanic_id: u32,
+        recorded_at: DateTime<Utc>,
+    ) -> Self {
+        InspectionResult {
+            item_id,
+            status,
+            measurement,
+            comment: comment.map(str::to_string),
+            mechanic_id,
+            recorded_at,
+        }
+    }
+
+    pub fn item_id(&self) -> u32 {
+        self.item_id
+    }
+
+    pub fn status(&self) -> InspectionStatus {
+        self.status
+    }
+
+    /// Measured value in the item's [`InspectionItem::unit`]
+    pub fn measurement(&self) -> Option<f64> {
+        self.measurement
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn mechanic_id(&self) -> u32 {
+        self.mechanic_id
+    }
+
+    pub fn recorded_at(&self) -> DateTime<Utc> {
+        self.recorded_at
+    }
+}
+
+/// Inspection checklist of an order with the results recorded so far
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InspectionChecklist {
+    order_id: u32,
+    template: InspectionTemplate,
+    results: Vec<InspectionResult>,
+}
+
+impl InspectionChecklist {
+    pub fn new(
+        order_id: u32,
+        template: InspectionTemplate,
+        results: Vec<InspectionResult>,
+    ) -> Self {
+        InspectionChecklist {
+            order_id,
+            template,
+            results,
+        }
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    pub fn template(&self) -> &InspectionTemplate {
+        &self.template
+    }
+
+    pub fn results(&self) -> &[InspectionResult] {
+        &self.results
+    }
+
+    pub fn result(&self, item_id: u32) -> Option<&InspectionResult> {
+        self.results.iter().find(|result| result.item_id == item_id)
+    }
+
+    /// Items that have not been checked yet
+    pub fn unchecked(&self) -> Vec<&InspectionItem> {
+        self.template
+            .items
+            .iter()
+            .filter(|item| self.result(item.id).is_none())
+            .collect()
+    }
+
+    /// Items with `status`, with their results
+    pub fn with_status(
+        &self,
+        status: InspectionStatus,
+    ) -> Vec<(&InspectionItem, &InspectionResult)> {
+        self.template
+            .items
+            .iter()
+            .filter_map(|item| Some((item, self.result(item.id)?)))
+            .filter(|(_, result)| result.status == status)
+            .collect()
+    }
+}
+
+impl Display for InspectionChecklist {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} for order {}", self.template.name, self.order_id)?;
+        for (section, items) in self.template.sections() {
+            write!(f, "\n{section}")?;
+            for item in items {
+                write!(f, "\n  {}: ", item.name)?;
+                match self.result(item.id) {
+                    Some(result) => {
+                        write!(f, "{}", result.status)?;
+                        if let (Some(measurement), Some(unit)) = (result.measurement, &item.unit) {
+                            write!(f, " ({measurement} {unit})")?;
+                        }
+                        if let Some(comment) = &result.comment {
+                            write!(f, " - {comment}")?;
+                        }
+                    }
+                    None => write!(f, "not checked")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Repair suggested for an item that failed the inspection of an order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RepairSuggestion {
+    id: u32,
+    order_id: u32,
+    item_id: u32,
+    description: String,
+    created_at: DateTime<Utc>,
+}
+
+impl RepairSuggestion {
+    pub fn new(
+        id: u32,
+        order_id: u32,
+        item_id: u32,
+        description: &str,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        RepairSuggestion {
+            id,
+            order_id,
+            item_id,
+            description: description.to_string(),
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    /// Failed checklist item the repair is suggested for
+    pub fn item_id(&self) -> u32 {
+        self.item_id
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Display for RepairSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ID: {} | {}", self.id, self.description)
+    }
+}
+
+/// Diagnostic trouble code a mechanic read off the car of an order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TroubleCode {
+    id: u32,
+    order_id: u32,
+    code: String,
+    description: Option<String>,
+    freeze_frame: Option<serde_json::Value>,
+    cleared: bool,
+    mechanic_id: u32,
+    recorded_at: DateTime<Utc>,
+}
+
+impl TroubleCode {
+    pub fn new(
+        id: u32,
+        order_id: u32,
+        code: &str,
+        freeze_frame: Option<serde_json::Value>,
+        cleared: bool,
+        mechanic_id: u32,
+        recorded_at: DateTime<Utc>,
+    ) -> Self {
+        TroubleCode {
+            id,
+            order_id,
+            code: code.to_string(),
+            description: describe_trouble_code(code).map(str::to_string),
+            freeze_frame,
+            cleared,
+            mechanic_id,
+            recorded_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    /// E.g. P0420
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Description of generic codes, see [`describe_trouble_code`]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Sensor values the car stored when it set the code
+    pub fn freeze_frame(&self) -> Option<&serde_json::Value> {
+        self.freeze_frame.as_ref()
+    }
+
+    /// Whether the code was cleared from the car's memory
+    pub fn cleared(&self) -> bool {
+        self.cleared
+    }
+
+    pub fn mechanic_id(&self) -> u32 {
+        self.mechanic_id
+    }
+
+    pub fn recorded_at(&self) -> DateTime<Utc> {
+        self.recorded_at
+    }
+}
+
+impl Display for TroubleCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ID: {} | Order: {} | {} | {}",
+            self.id,
+            self.order_id,
+            self.code,
+            self.description
+                .as_deref()
+                .unwrap_or("manufacturer specific or unknown")
+        )?;
+        if self.cleared {
+            write!(f, " | cleared")?;
+        }
+        Ok(())
+    }
+}
+
+/// Maintenance a car needs regularly, e.g. an oil change every 10,000 km or 12 months
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaintenanceSchedule {
+    id: u32,
+    name: String,
+    service: Service,
+    make: Option<String>,
+    interval_km: Option<u32>,
+    interval_months: Option<u32>,
+}
+
+impl MaintenanceSchedule {
+    pub fn new(
+        id: u32,
+        name: &str,
+        service: Service,
+        make: Option<&str>,
+        interval_km: Option<u32>,
+        interval_months: Option<u32>,
+    ) -> Self {
+        MaintenanceSchedule {
+            id,
+            name: name.to_string(),
+            service,
+            make: make.map(str::to_string),
+            interval_km,
+            interval_months,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Service to book for the maintenance. Orders of this service only count as doing
+    /// the maintenance once recorded with [`ShopBackend::record_maintenance_done`].
+    pub fn service(&self) -> Service {
+        self.service
+    }
+
+    /// Make of car the schedule is for, `None` for every make
+    pub fn make(&self) -> Option<&str> {
+        self.make.as_deref()
+    }
+
+    pub fn interval_km(&self) -> Option<u32> {
+        self.interval_km
+    }
+
+    pub fn interval_months(&self) -> Option<u32> {
+        self.interval_months
+    }
+
+    pub fn applies_to(&self, car: &Car) -> bool {
+        self.make
+            .as_ref()
+            .is_none_or(|make| make.eq_ignore_ascii_case(&car.make))
+    }
 }
 
-impl Display for Client {
+impl Display for MaintenanceSchedule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "ID: {} | Name: {} | Email: {})",
-            self.name, self.id, self.email
-        )
+            "ID: {} | {} | Service: {} | Make: {}",
+            self.id,
+            self.name,
+            self.service,
+            self.make.as_deref().unwrap_or("any")
+        )?;
+        if let Some(km) = self.interval_km {
+            write!(f, " | Every {km} km")?;
+        }
+        if let Some(months) = self.interval_months {
+            write!(f, " | Every {months} months")?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Employee {
+/// Time a mechanic worked on an order without a pause
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LaborEntry {
     id: u32,
-    name: String,
-    role: Role,
+    order_id: u32,
+    mechanic_id: u32,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
 }
 
-impl Employee {
-    pub fn new(id: u32, name: &str, role: Role) -> Self {
-        Employee {
+impl LaborEntry {
+    pub fn new(
+        id: u32,
+        order_id: u32,
+        mechanic_id: u32,
+        started_at: DateTime<Utc>,
+        ended_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        LaborEntry {
             id,
-            name: name.to_string(),
-            role,
+            order_id,
+            mechanic_id,
+            started_at,
+            ended_at,
         }
     }
 
@@ -69,21 +1119,102 @@ impl Employee {
         self.id
     }
 
-    pub fn name(&self) -> &str {
-        &self.name
+    pub fn order_id(&self) -> u32 {
+        self.order_id
     }
 
-    pub fn role(&self) -> Role {
-        self.role
+    pub fn mechanic_id(&self) -> u32 {
+        self.mechanic_id
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// `None` while the mechanic is clocked on
+    pub fn ended_at(&self) -> Option<DateTime<Utc>> {
+        self.ended_at
+    }
+
+    /// Time worked until `now` for entries the mechanic is still clocked on to
+    pub fn duration(&self, now: DateTime<Utc>) -> chrono::Duration {
+        (self.ended_at.unwrap_or(now) - self.started_at).max(chrono::Duration::zero())
     }
 }
 
-impl Display for Employee {
+impl Display for LaborEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "ID: {} | Name: {} | Role: {})",
-            self.id, self.name, self.role
+            "ID: {} | Order: {} | Mechanic: {} | From: {}",
+            self.id,
+            self.order_id,
+            self.mechanic_id,
+            self.started_at.format("%Y-%m-%d %H:%M")
+        )?;
+        match self.ended_at {
+            Some(ended_at) => write!(f, " | Until: {}", ended_at.format("%Y-%m-%d %H:%M")),
+            None => write!(f, " | Clocked on"),
+        }
+    }
+}
+
+/// Labor of one mechanic billed on a report
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LaborLine {
+    report_id: u32,
+    mechanic_id: u32,
+    minutes: u32,
+    rate: u32,
+    amount: u32,
+}
+
+impl LaborLine {
+    pub fn new(report_id: u32, mechanic_id: u32, minutes: u32, rate: u32, amount: u32) -> Self {
+        LaborLine {
+            report_id,
+            mechanic_id,
+            minutes,
+            rate,
+            amount,
+        }
+    }
+
+    pub fn report_id(&self) -> u32 {
+        self.report_id
+    }
+
+    pub fn mechanic_id(&self) -> u32 {
+        self.mechanic_id
+    }
+
+    /// Tracked time, rounded up to whole minutes
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    /// Labor rate in cents per hour when the report was issued
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Cost of the labor in cents, included in [`Report::cost`]
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+}
+
+impl Display for LaborLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Mechanic: {} | {} min at ${}.{:02}/h | ${}.{:02}",
+            self.mechanic_id,
+            self.minutes,
+            self.rate / 100,
+            self.rate % 100,
+            self.amount / 100,
+            self.amount % 100
         )
     }
 }
@@ -92,20 +1223,98 @@ impl Display for Employee {
 pub struct Order {
     id: u32,
     client_id: u32,
+    location_id: u32,
     service: Service,
     finished: bool,
+    version: u32,
+    created_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+    mechanic_id: Option<u32>,
+    converted_to_repair: bool,
 }
 
 impl Order {
-    pub fn new(id: u32, client_id: u32, service: Service, finished: bool) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        client_id: u32,
+        location_id: u32,
+        service: Service,
+        finished: bool,
+        version: u32,
+        created_at: Option<DateTime<Utc>>,
+        finished_at: Option<DateTime<Utc>>,
+        mechanic_id: Option<u32>,
+        converted_to_repair: bool,
+    ) -> Self {
         Order {
             id,
             client_id,
+            location_id,
             service,
             finished,
+            version,
+            created_at,
+            finished_at,
+            mechanic_id,
+            converted_to_repair,
+        }
+    }
+
+    /// Order that was just registered
+    pub(crate) fn registered(
+        id: u32,
+        client_id: u32,
+        location_id: u32,
+        service: Service,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Order::new(
+            id,
+            client_id,
+            location_id,
+            service,
+            false,
+            1,
+            created_at,
+            None,
+            None,
+            false,
+        )
+    }
+
+    /// The order after its inspection turned into a repair
+    pub(crate) fn into_repair(self) -> Self {
+        Order {
+            service: Service::Repair,
+            converted_to_repair: true,
+            ..self
+        }
+    }
+
+    /// The order after `mechanic_id` finished it at `finished_at`
+    pub(crate) fn close(self, mechanic_id: u32, finished_at: Option<DateTime<Utc>>) -> Self {
+        Order {
+            finished: true,
+            finished_at,
+            mechanic_id: Some(mechanic_id),
+            ..self
+        }
+    }
+
+    /// The order after moving it to the workshop `location_id`
+    pub(crate) fn move_to(self, location_id: u32) -> Self {
+        Order {
+            location_id,
+            ..self
         }
     }
 
+    /// The same order at `version`
+    pub(crate) fn at_version(self, version: u32) -> Self {
+        Order { version, ..self }
+    }
+
     pub fn id(&self) -> u32 {
         self.id
     }
@@ -114,6 +1323,11 @@ impl Order {
         self.client_id
     }
 
+    /// Workshop doing the work
+    pub fn location_id(&self) -> u32 {
+        self.location_id
+    }
+
     pub fn service(&self) -> Service {
         self.service
     }
@@ -121,14 +1335,39 @@ impl Order {
     pub fn finished(&self) -> bool {
         self.finished
     }
+
+    /// Starts at 1 and grows with every change, changing the order requires the current version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// When the order was registered, unknown for orders registered before this was recorded
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// When a mechanic finished the work
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.finished_at
+    }
+
+    /// Mechanic who finished the work
+    pub fn mechanic_id(&self) -> Option<u32> {
+        self.mechanic_id
+    }
+
+    /// Whether the order was registered as an inspection and turned into a repair
+    pub fn converted_to_repair(&self) -> bool {
+        self.converted_to_repair
+    }
 }
 
 impl Display for Order {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "ID: {} | Client: {} | Service: {} | Finished: {}",
-            self.id, self.client_id, self.service, self.finished
+            "ID: {} | Client: {} | Location: {} | Service: {} | Finished: {}",
+            self.id, self.client_id, self.location_id, self.service, self.finished
         )
     }
 }
@@ -138,16 +1377,37 @@ pub struct Report {
     id: u32,
     client_id: u32,
     order_id: u32,
+    location_id: u32,
     cost: u32,
+    revision: u32,
+    superseded_by: Option<u32>,
+    version: u32,
+    created_at: Option<DateTime<Utc>>,
 }
 
 impl Report {
-    pub fn new(id: u32, client_id: u32, order_id: u32, cost: u32) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        client_id: u32,
+        order_id: u32,
+        location_id: u32,
+        cost: u32,
+        revision: u32,
+        superseded_by: Option<u32>,
+        version: u32,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Self {
         Report {
             id,
             client_id,
             order_id,
+            location_id,
             cost,
+            revision,
+            superseded_by,
+            version,
+            created_at,
         }
     }
 
@@ -163,9 +1423,34 @@ impl Report {
         self.order_id
     }
 
+    /// Workshop that issued the report
+    pub fn location_id(&self) -> u32 {
+        self.location_id
+    }
+
     pub fn cost(&self) -> u32 {
         self.cost
     }
+
+    /// 1 for the first report of an order, each correction has the next revision
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    /// Corrected report replacing this one, superseded reports are kept for history
+    pub fn superseded_by(&self) -> Option<u32> {
+        self.superseded_by
+    }
+
+    /// Starts at 1 and grows with every change, changing the report requires the current version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// When the report was issued, unknown for reports issued before this was recorded
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
 }
 
 impl Display for Report {
@@ -178,6 +1463,315 @@ impl Display for Report {
             self.client_id,
             self.cost / 100,
             self.cost % 100
+        )?;
+        if let Some(created_at) = self.created_at {
+            write!(f, " | Date: {}", created_at.format("%Y-%m-%d"))?;
+        }
+        match self.superseded_by {
+            Some(id) => write!(f, " | Superseded by: {id}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Payment {
+    id: u32,
+    report_id: u32,
+    client_id: u32,
+    amount: u32,
+    created_at: DateTime<Utc>,
+}
+
+impl Payment {
+    pub fn new(
+        id: u32,
+        report_id: u32,
+        client_id: u32,
+        amount: u32,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Payment {
+            id,
+            report_id,
+            client_id,
+            amount,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn report_id(&self) -> u32 {
+        self.report_id
+    }
+
+    pub fn client_id(&self) -> u32 {
+        self.client_id
+    }
+
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Display for Payment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | Report: {} | Client: {} | Amount: ${}.{:02} | Date: {}",
+            self.id,
+            self.report_id,
+            self.client_id,
+            self.amount / 100,
+            self.amount % 100,
+            self.created_at.format("%Y-%m-%d")
+        )
+    }
+}
+
+/// Message shown to a client inside the application
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Notification {
+    id: u32,
+    client_id: u32,
+    subject: String,
+    body: String,
+    created_at: DateTime<Utc>,
+    read: bool,
+}
+
+impl Notification {
+    pub fn new(
+        id: u32,
+        client_id: u32,
+        subject: &str,
+        body: &str,
+        created_at: DateTime<Utc>,
+        read: bool,
+    ) -> Self {
+        Notification {
+            id,
+            client_id,
+            subject: subject.to_string(),
+            body: body.to_string(),
+            created_at,
+            read,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn client_id(&self) -> u32 {
+        self.client_id
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn read(&self) -> bool {
+        self.read
+    }
+}
+
+impl Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | {}{}",
+            self.created_at.format("%Y-%m-%d %H:%M"),
+            self.subject,
+            self.body,
+            if self.read { "" } else { " (new)" }
+        )
+    }
+}
+
+/// Endpoint that receives signed event payloads, the secret is never returned
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    id: u32,
+    url: String,
+    event_types: Vec<WebhookEventType>,
+    active: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    pub fn new(
+        id: u32,
+        url: &str,
+        event_types: Vec<WebhookEventType>,
+        active: bool,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        WebhookSubscription {
+            id,
+            url: url.to_string(),
+            event_types,
+            active,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn event_types(&self) -> &[WebhookEventType] {
+        &self.event_types
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Display for WebhookSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | Events: {} | {}",
+            self.id,
+            self.url,
+            self.event_types
+                .iter()
+                .map(|event_type| event_type.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            if self.active { "active" } else { "disabled" }
         )
     }
 }
+
+/// One attempt at sending an event to a webhook subscription, kept as a delivery log
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    id: u32,
+    subscription_id: u32,
+    event_id: String,
+    event_type: WebhookEventType,
+    payload: String,
+    attempts: u32,
+    delivered_at: Option<DateTime<Utc>>,
+    response_status: Option<u16>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        subscription_id: u32,
+        event_id: &str,
+        event_type: WebhookEventType,
+        payload: &str,
+        attempts: u32,
+        delivered_at: Option<DateTime<Utc>>,
+        response_status: Option<u16>,
+        last_error: Option<&str>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        WebhookDelivery {
+            id,
+            subscription_id,
+            event_id: event_id.to_string(),
+            event_type,
+            payload: payload.to_string(),
+            attempts,
+            delivered_at,
+            response_status,
+            last_error: last_error.map(str::to_string),
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn subscription_id(&self) -> u32 {
+        self.subscription_id
+    }
+
+    /// Same for every delivery of one event, including replays
+    pub fn event_id(&self) -> &str {
+        &self.event_id
+    }
+
+    pub fn event_type(&self) -> WebhookEventType {
+        self.event_type
+    }
+
+    /// JSON body sent to the subscriber
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn delivered_at(&self) -> Option<DateTime<Utc>> {
+        self.delivered_at
+    }
+
+    /// HTTP status of the last response, if the subscriber answered at all
+    pub fn response_status(&self) -> Option<u16> {
+        self.response_status
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Display for WebhookDelivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | {} | Attempts: {} | ",
+            self.id,
+            self.created_at.format("%Y-%m-%d %H:%M"),
+            self.event_type,
+            self.attempts
+        )?;
+        match (self.delivered_at, &self.last_error) {
+            (Some(delivered_at), _) => {
+                write!(f, "delivered {}", delivered_at.format("%Y-%m-%d %H:%M"))
+            }
+            (None, Some(error)) => write!(f, "failed: {error}"),
+            (None, None) => write!(f, "pending"),
+        }
+    }
+}
@@ -1,6 +1,28 @@
 use sea_orm::DbErr;
+use serde::ser::{Serialize, SerializeMap, Serializer};
 use thiserror::Error;
 
+// The errors returned to API consumers serialize to an object with a stable `code`, the
+// human readable `message` and the IDs or values the error is about, e.g.
+// `{"code": "order_not_found", "message": "order 3 does not exist", "order_id": 3}`.
+// Internal errors, such as database errors, serialize with a generic message, their
+// details are only in the `Display` output meant for logs.
+
+/// Adds the `code` and `message` entries of `error`, hiding the details of internal errors
+fn serialize_code_and_message<M: SerializeMap>(
+    map: &mut M,
+    code: &str,
+    error: &dyn std::fmt::Display,
+    internal: bool,
+) -> Result<(), M::Error> {
+    map.serialize_entry("code", code)?;
+    if internal {
+        map.serialize_entry("message", "internal error, try again later")
+    } else {
+        map.serialize_entry("message", &error.to_string())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum InitError {
     #[error("problem reading .env file")]
@@ -9,6 +31,25 @@ pub enum InitError {
     Database(#[from] DbErr),
 }
 
+impl InitError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            InitError::Dotenv(_) => "dotenv_error",
+            InitError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for InitError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, InitError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        map.end()
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum DbError {
     #[error("client {0} does not exist")]
@@ -19,14 +60,199 @@ pub enum DbError {
     Order(u32),
     #[error("report {0} does not exist")]
     Report(u32),
+    #[error("notification {0} does not exist")]
+    Notification(u32),
     #[error("permission denied")]
     Permission,
+    #[error("email address has to be verified first")]
+    EmailNotVerified,
+    #[error("{0} {1} was changed by someone else, reload it and try again")]
+    Conflict(&'static str, u32),
+    #[error("client {0} already has a car registered")]
+    CarAlreadyRegistered(u32),
+    #[error("client {0} has no car registered")]
+    NoCarRegistered(u32),
+    #[error("location {0} does not exist")]
+    Location(u32),
+    #[error("only {available} of part {part_number} in stock at location {location_id}")]
+    InsufficientStock {
+        location_id: u32,
+        part_number: String,
+        available: u32,
+    },
+    #[error("order {0} is not an inspection")]
+    NotInspection(u32),
+    #[error("order {0} is not finished")]
+    OrderNotFinished(u32),
+    #[error("order {0} is already finished")]
+    OrderFinished(u32),
+    #[error("already clocked on to order {0}, clock off first")]
+    AlreadyClockedOn(u32),
+    #[error("not clocked on to order {0}")]
+    NotClockedOn(u32),
+    #[error("labor rate has not been set")]
+    LaborRateNotSet,
+    #[error("the report costs more than can be billed")]
+    CostTooHigh,
+    #[error("mileage {mileage} of client {client_id}'s car is below {previous}, give a reason")]
+    MileageDecreased {
+        client_id: u32,
+        previous: u32,
+        mileage: u32,
+    },
+    #[error("order {order_id} already has report {report_id}, correct it instead")]
+    ReportAlreadyIssued { order_id: u32, report_id: u32 },
+    #[error("pages have to hold at least one item")]
+    EmptyPage,
+    #[error("maintenance schedule {0} does not exist")]
+    MaintenanceSchedule(u32),
+    #[error("maintenance schedules need a name")]
+    EmptyScheduleName,
+    #[error("maintenance schedules need an interval in kilometers or months")]
+    NoMaintenanceInterval,
+    #[error("text cannot be empty")]
+    EmptyText,
+    #[error("text cannot be longer than {0} characters")]
+    TextTooLong(usize),
+    #[error("inspection template {0} does not exist")]
+    InspectionTemplate(u32),
+    #[error("inspection templates need a name and items with a section and a name")]
+    EmptyInspectionTemplate,
+    #[error("no inspection checklist was started for order {0}")]
+    NoChecklist(u32),
+    #[error("item {0} is not on the order's inspection checklist")]
+    InspectionItem(u32),
+    #[error("item {0} has to be measured")]
+    MissingMeasurement(u32),
+    #[error("{0} is not a diagnostic trouble code, e.g. P0420")]
+    InvalidTroubleCode(String),
+    #[error("trouble code {0} does not exist")]
+    TroubleCode(u32),
     #[error("{0}")]
     NotLoggedIn(#[from] NotLoggedInError),
+    #[error("{0}")]
+    Blob(#[from] BlobError),
     #[error("database error: {0}")]
     Database(#[from] DbErr),
-    #[error("{0}")]
-    Other(String),
+}
+
+impl DbError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            DbError::Client(_) => "client_not_found",
+            DbError::Employee(_) => "employee_not_found",
+            DbError::Order(_) => "order_not_found",
+            DbError::Report(_) => "report_not_found",
+            DbError::Notification(_) => "notification_not_found",
+            DbError::Permission => "permission_denied",
+            DbError::EmailNotVerified => "email_not_verified",
+            DbError::Conflict(..) => "conflict",
+            DbError::CarAlreadyRegistered(_) => "car_already_registered",
+            DbError::NoCarRegistered(_) => "no_car_registered",
+            DbError::Location(_) => "location_not_found",
+            DbError::InsufficientStock { .. } => "insufficient_stock",
+            DbError::NotInspection(_) => "not_inspection",
+            DbError::OrderNotFinished(_) => "order_not_finished",
+            DbError::OrderFinished(_) => "order_finished",
+            DbError::AlreadyClockedOn(_) => "already_clocked_on",
+            DbError::NotClockedOn(_) => "not_clocked_on",
+            DbError::LaborRateNotSet => "labor_rate_not_set",
+            DbError::CostTooHigh => "cost_too_high",
+            DbError::MileageDecreased { .. } => "mileage_decreased",
+            DbError::ReportAlreadyIssued { .. } => "report_already_issued",
+            DbError::EmptyPage => "empty_page",
+            DbError::MaintenanceSchedule(_) => "maintenance_schedule_not_found",
+            DbError::EmptyScheduleName => "empty_schedule_name",
+            DbError::NoMaintenanceInterval => "no_maintenance_interval",
+            DbError::EmptyText => "empty_text",
+            DbError::TextTooLong(_) => "text_too_long",
+            DbError::InspectionTemplate(_) => "inspection_template_not_found",
+            DbError::EmptyInspectionTemplate => "empty_inspection_template",
+            DbError::NoChecklist(_) => "no_checklist",
+            DbError::InspectionItem(_) => "inspection_item_not_found",
+            DbError::MissingMeasurement(_) => "missing_measurement",
+            DbError::InvalidTroubleCode(_) => "invalid_trouble_code",
+            DbError::TroubleCode(_) => "trouble_code_not_found",
+            DbError::NotLoggedIn(_) => "not_logged_in",
+            DbError::Blob(_) => "blob_store_error",
+            DbError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for DbError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, DbError::Blob(_) | DbError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            DbError::Client(id)
+            | DbError::CarAlreadyRegistered(id)
+            | DbError::NoCarRegistered(id) => map.serialize_entry("client_id", id)?,
+            DbError::Employee(id) => map.serialize_entry("employee_id", id)?,
+            DbError::Order(id)
+            | DbError::NotInspection(id)
+            | DbError::OrderNotFinished(id)
+            | DbError::OrderFinished(id)
+            | DbError::AlreadyClockedOn(id)
+            | DbError::NotClockedOn(id)
+            | DbError::NoChecklist(id) => map.serialize_entry("order_id", id)?,
+            DbError::Report(id) => map.serialize_entry("report_id", id)?,
+            DbError::Location(id) => map.serialize_entry("location_id", id)?,
+            DbError::MaintenanceSchedule(id) => map.serialize_entry("schedule_id", id)?,
+            DbError::TextTooLong(max) => map.serialize_entry("max_length", max)?,
+            DbError::InspectionTemplate(id) => map.serialize_entry("template_id", id)?,
+            DbError::InspectionItem(id) | DbError::MissingMeasurement(id) => {
+                map.serialize_entry("item_id", id)?
+            }
+            DbError::InvalidTroubleCode(code) => map.serialize_entry("trouble_code", code)?,
+            DbError::TroubleCode(id) => map.serialize_entry("trouble_code_id", id)?,
+            DbError::InsufficientStock {
+                location_id,
+                part_number,
+                available,
+            } => {
+                map.serialize_entry("location_id", location_id)?;
+                map.serialize_entry("part_number", part_number)?;
+                map.serialize_entry("available", available)?;
+            }
+            DbError::MileageDecreased {
+                client_id,
+                previous,
+                mileage,
+            } => {
+                map.serialize_entry("client_id", client_id)?;
+                map.serialize_entry("previous", previous)?;
+                map.serialize_entry("mileage", mileage)?;
+            }
+            DbError::Notification(id) => map.serialize_entry("notification_id", id)?,
+            DbError::Conflict(entity, id) => {
+                map.serialize_entry("entity", entity)?;
+                map.serialize_entry("id", id)?;
+            }
+            DbError::ReportAlreadyIssued {
+                order_id,
+                report_id,
+            } => {
+                map.serialize_entry("order_id", order_id)?;
+                map.serialize_entry("report_id", report_id)?;
+            }
+            DbError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            DbError::Permission
+            | DbError::EmailNotVerified
+            | DbError::EmptyPage
+            | DbError::EmptyScheduleName
+            | DbError::NoMaintenanceInterval
+            | DbError::EmptyInspectionTemplate
+            | DbError::EmptyText
+            | DbError::LaborRateNotSet
+            | DbError::CostTooHigh
+            | DbError::Blob(_)
+            | DbError::Database(_) => {}
+        }
+        map.end()
+    }
 }
 
 #[derive(Debug, Error)]
@@ -43,6 +269,37 @@ pub enum RegisterClientError {
     Database(#[from] DbErr),
 }
 
+impl RegisterClientError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            RegisterClientError::AlreadyLoggedIn => "already_logged_in",
+            RegisterClientError::EmailAlreadyRegistered(_) => "email_already_registered",
+            RegisterClientError::EmailIncorrectFormat(_) => "email_incorrect_format",
+            RegisterClientError::PasswordNotHashed => "password_not_hashed",
+            RegisterClientError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for RegisterClientError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, RegisterClientError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            RegisterClientError::EmailAlreadyRegistered(email)
+            | RegisterClientError::EmailIncorrectFormat(email) => {
+                map.serialize_entry("email", email)?
+            }
+            RegisterClientError::AlreadyLoggedIn
+            | RegisterClientError::PasswordNotHashed
+            | RegisterClientError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum LoginError {
     #[error("already logged in")]
@@ -57,12 +314,545 @@ pub enum LoginError {
     EmailIncorrectFormat(String),
     #[error("incorrect password for {0}")]
     ClientIncorrectPassword(String),
-    #[error("password hash is not a bcrypt hash")]
-    PasswordNotHashed,
+    #[error("employee {0} has to enter a two-factor authentication code")]
+    SecondFactorRequired(u32),
+    #[error("employee {0} has to enroll in two-factor authentication before logging in")]
+    TwoFactorEnrollmentRequired(u32),
+    #[error("incorrect two-factor authentication code")]
+    InvalidSecondFactor,
+    #[error("no login is waiting for two-factor authentication")]
+    NoPendingLogin,
+    #[error("too many incorrect two-factor authentication codes, log in again")]
+    TooManySecondFactorAttempts,
+    #[error("{0}")]
+    Tenant(#[from] TenantError),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl LoginError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            LoginError::AlreadyLoggedIn => "already_logged_in",
+            LoginError::EmployeeNotRegistered(_) => "employee_not_registered",
+            LoginError::EmployeeIncorrectPassword(_) => "employee_incorrect_password",
+            LoginError::EmailNotRegistered(_) => "email_not_registered",
+            LoginError::EmailIncorrectFormat(_) => "email_incorrect_format",
+            LoginError::ClientIncorrectPassword(_) => "client_incorrect_password",
+            LoginError::SecondFactorRequired(_) => "second_factor_required",
+            LoginError::TwoFactorEnrollmentRequired(_) => "two_factor_enrollment_required",
+            LoginError::InvalidSecondFactor => "invalid_second_factor",
+            LoginError::NoPendingLogin => "no_pending_login",
+            LoginError::TooManySecondFactorAttempts => "too_many_second_factor_attempts",
+            LoginError::Tenant(e) => e.code(),
+            LoginError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for LoginError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(
+            self,
+            LoginError::Database(_) | LoginError::Tenant(TenantError::Database(_))
+        );
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            LoginError::EmployeeNotRegistered(id)
+            | LoginError::EmployeeIncorrectPassword(id)
+            | LoginError::SecondFactorRequired(id)
+            | LoginError::TwoFactorEnrollmentRequired(id) => {
+                map.serialize_entry("employee_id", id)?
+            }
+            LoginError::EmailNotRegistered(email)
+            | LoginError::EmailIncorrectFormat(email)
+            | LoginError::ClientIncorrectPassword(email) => map.serialize_entry("email", email)?,
+            LoginError::Tenant(TenantError::UnknownTenant(tenant))
+            | LoginError::Tenant(TenantError::InvalidName(tenant)) => {
+                map.serialize_entry("tenant", tenant)?
+            }
+            LoginError::AlreadyLoggedIn
+            | LoginError::Tenant(TenantError::Database(_))
+            | LoginError::InvalidSecondFactor
+            | LoginError::NoPendingLogin
+            | LoginError::TooManySecondFactorAttempts
+            | LoginError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TenantError {
+    #[error("no shop called {0} is hosted here")]
+    UnknownTenant(String),
+    #[error("{0} is not a valid shop name, use lowercase letters, digits and dashes")]
+    InvalidName(String),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl TenantError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            TenantError::UnknownTenant(_) => "unknown_tenant",
+            TenantError::InvalidName(_) => "invalid_tenant_name",
+            TenantError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for TenantError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, TenantError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            TenantError::UnknownTenant(tenant) | TenantError::InvalidName(tenant) => {
+                map.serialize_entry("tenant", tenant)?
+            }
+            TenantError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TwoFactorError {
+    #[error("{0}")]
+    NotLoggedIn(#[from] NotLoggedInError),
+    #[error("permission denied")]
+    Permission,
+    #[error("two-factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("two-factor authentication enrollment was not started")]
+    NotEnrolled,
+    #[error("incorrect two-factor authentication code")]
+    InvalidCode,
+    #[error("two-factor authentication is required for all employees")]
+    RequiredByPolicy,
     #[error("database error: {0}")]
     Database(#[from] DbErr),
 }
 
+impl TwoFactorError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            TwoFactorError::NotLoggedIn(_) => "not_logged_in",
+            TwoFactorError::Permission => "permission_denied",
+            TwoFactorError::AlreadyEnabled => "two_factor_already_enabled",
+            TwoFactorError::NotEnrolled => "two_factor_not_enrolled",
+            TwoFactorError::InvalidCode => "invalid_two_factor_code",
+            TwoFactorError::RequiredByPolicy => "two_factor_required_by_policy",
+            TwoFactorError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for TwoFactorError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, TwoFactorError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            TwoFactorError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            TwoFactorError::Permission
+            | TwoFactorError::AlreadyEnabled
+            | TwoFactorError::NotEnrolled
+            | TwoFactorError::InvalidCode
+            | TwoFactorError::RequiredByPolicy
+            | TwoFactorError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("function {0} requires being logged in")]
 pub struct NotLoggedInError(pub String);
+
+#[derive(Debug, Error)]
+#[error("could not deliver notification: {0}")]
+pub struct NotifyError(pub String);
+
+#[derive(Debug, Error)]
+#[error("blob store error: {0}")]
+pub struct BlobError(pub String);
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error("{0}")]
+    NotLoggedIn(#[from] NotLoggedInError),
+    #[error("permission denied")]
+    Permission,
+    #[error("order {0} does not exist")]
+    Order(u32),
+    #[error("attachment {0} does not exist")]
+    Attachment(u32),
+    #[error("file name cannot be empty")]
+    EmptyFileName,
+    #[error("attachment is empty")]
+    Empty,
+    #[error("attachment has {size} bytes, at most {max_size} are allowed")]
+    TooLarge { size: u64, max_size: u64 },
+    #[error("only JPEG and PNG images and PDF documents can be attached")]
+    UnsupportedType,
+    #[error("{0}")]
+    Blob(#[from] BlobError),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl AttachmentError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            AttachmentError::NotLoggedIn(_) => "not_logged_in",
+            AttachmentError::Permission => "permission_denied",
+            AttachmentError::Order(_) => "order_not_found",
+            AttachmentError::Attachment(_) => "attachment_not_found",
+            AttachmentError::EmptyFileName => "empty_file_name",
+            AttachmentError::Empty => "empty_attachment",
+            AttachmentError::TooLarge { .. } => "attachment_too_large",
+            AttachmentError::UnsupportedType => "unsupported_attachment_type",
+            AttachmentError::Blob(_) => "blob_store_error",
+            AttachmentError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for AttachmentError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(
+            self,
+            AttachmentError::Blob(_) | AttachmentError::Database(_)
+        );
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            AttachmentError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            AttachmentError::Order(id) => map.serialize_entry("order_id", id)?,
+            AttachmentError::Attachment(id) => map.serialize_entry("attachment_id", id)?,
+            AttachmentError::TooLarge { size, max_size } => {
+                map.serialize_entry("size", size)?;
+                map.serialize_entry("max_size", max_size)?;
+            }
+            AttachmentError::Permission
+            | AttachmentError::EmptyFileName
+            | AttachmentError::Empty
+            | AttachmentError::UnsupportedType
+            | AttachmentError::Blob(_)
+            | AttachmentError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PasswordPolicyError {
+    #[error("password must be at least {0} characters long")]
+    TooShort(usize),
+    #[error("password must contain an uppercase letter")]
+    MissingUppercase,
+    #[error("password must contain a lowercase letter")]
+    MissingLowercase,
+    #[error("password must contain a digit")]
+    MissingDigit,
+    #[error("password must contain a symbol")]
+    MissingSymbol,
+}
+
+impl PasswordPolicyError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            PasswordPolicyError::TooShort(_) => "password_too_short",
+            PasswordPolicyError::MissingUppercase => "password_missing_uppercase",
+            PasswordPolicyError::MissingLowercase => "password_missing_lowercase",
+            PasswordPolicyError::MissingDigit => "password_missing_digit",
+            PasswordPolicyError::MissingSymbol => "password_missing_symbol",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PasswordChangeError {
+    #[error("{0}")]
+    NotLoggedIn(#[from] NotLoggedInError),
+    #[error("incorrect password")]
+    IncorrectPassword,
+    #[error("new password must differ from the old one")]
+    PasswordUnchanged,
+    #[error("password reset token is invalid or has already been used")]
+    InvalidResetToken,
+    #[error("password reset token has expired")]
+    ResetTokenExpired,
+    #[error("the account of the logged in user does not exist anymore")]
+    AccountNotFound,
+    #[error("{0}")]
+    Policy(#[from] PasswordPolicyError),
+    #[error("{0}")]
+    Notify(#[from] NotifyError),
+    #[error("hashing error: {0}")]
+    Hash(#[from] bcrypt::BcryptError),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl PasswordChangeError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            PasswordChangeError::NotLoggedIn(_) => "not_logged_in",
+            PasswordChangeError::IncorrectPassword => "incorrect_password",
+            PasswordChangeError::PasswordUnchanged => "password_unchanged",
+            PasswordChangeError::InvalidResetToken => "invalid_reset_token",
+            PasswordChangeError::ResetTokenExpired => "reset_token_expired",
+            PasswordChangeError::AccountNotFound => "account_not_found",
+            PasswordChangeError::Policy(e) => e.code(),
+            PasswordChangeError::Notify(_) => "notify_error",
+            PasswordChangeError::Hash(_) => "hash_error",
+            PasswordChangeError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for PasswordChangeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(
+            self,
+            PasswordChangeError::Notify(_)
+                | PasswordChangeError::Hash(_)
+                | PasswordChangeError::Database(_)
+        );
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            PasswordChangeError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            PasswordChangeError::Policy(PasswordPolicyError::TooShort(length)) => {
+                map.serialize_entry("min_length", length)?
+            }
+            PasswordChangeError::IncorrectPassword
+            | PasswordChangeError::PasswordUnchanged
+            | PasswordChangeError::InvalidResetToken
+            | PasswordChangeError::ResetTokenExpired
+            | PasswordChangeError::AccountNotFound
+            | PasswordChangeError::Policy(_)
+            | PasswordChangeError::Notify(_)
+            | PasswordChangeError::Hash(_)
+            | PasswordChangeError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("{0}")]
+    NotLoggedIn(#[from] NotLoggedInError),
+    #[error("permission denied")]
+    Permission,
+    #[error("client {0} does not exist")]
+    Client(u32),
+    #[error("name cannot be empty")]
+    EmptyName,
+    #[error("email {0} already registered")]
+    EmailAlreadyRegistered(String),
+    #[error("{0} is not a correct email address")]
+    EmailIncorrectFormat(String),
+    #[error("{0} is not a correct phone number")]
+    PhoneIncorrectFormat(String),
+    #[error("{0}")]
+    Notify(#[from] NotifyError),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl ProfileError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProfileError::NotLoggedIn(_) => "not_logged_in",
+            ProfileError::Permission => "permission_denied",
+            ProfileError::Client(_) => "client_not_found",
+            ProfileError::EmptyName => "empty_name",
+            ProfileError::EmailAlreadyRegistered(_) => "email_already_registered",
+            ProfileError::EmailIncorrectFormat(_) => "email_incorrect_format",
+            ProfileError::PhoneIncorrectFormat(_) => "phone_incorrect_format",
+            ProfileError::Notify(_) => "notify_error",
+            ProfileError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for ProfileError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, ProfileError::Notify(_) | ProfileError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            ProfileError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            ProfileError::Client(id) => map.serialize_entry("client_id", id)?,
+            ProfileError::EmailAlreadyRegistered(email)
+            | ProfileError::EmailIncorrectFormat(email) => map.serialize_entry("email", email)?,
+            ProfileError::PhoneIncorrectFormat(phone) => map.serialize_entry("phone", phone)?,
+            ProfileError::Permission
+            | ProfileError::EmptyName
+            | ProfileError::Notify(_)
+            | ProfileError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("archive error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl ExportError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExportError::Json(_) => "serialization_error",
+            ExportError::Zip(_) => "archive_error",
+            ExportError::Io(_) => "io_error",
+        }
+    }
+}
+
+impl Serialize for ExportError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        serialize_code_and_message(&mut map, self.code(), self, true)?;
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    #[error("{0}")]
+    NotLoggedIn(#[from] NotLoggedInError),
+    #[error("permission denied")]
+    Permission,
+    #[error("client {0} does not exist")]
+    Client(u32),
+    #[error("email address is already verified")]
+    AlreadyVerified,
+    #[error("verification token is invalid")]
+    InvalidToken,
+    #[error("verification token has expired")]
+    TokenExpired,
+    #[error("verification email was sent recently, try again in {0} seconds")]
+    TooManyRequests(i64),
+    #[error("{0}")]
+    Notify(#[from] NotifyError),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl VerificationError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerificationError::NotLoggedIn(_) => "not_logged_in",
+            VerificationError::Permission => "permission_denied",
+            VerificationError::Client(_) => "client_not_found",
+            VerificationError::AlreadyVerified => "already_verified",
+            VerificationError::InvalidToken => "invalid_verification_token",
+            VerificationError::TokenExpired => "verification_token_expired",
+            VerificationError::TooManyRequests(_) => "too_many_requests",
+            VerificationError::Notify(_) => "notify_error",
+            VerificationError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for VerificationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(
+            self,
+            VerificationError::Notify(_) | VerificationError::Database(_)
+        );
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            VerificationError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            VerificationError::Client(id) => map.serialize_entry("client_id", id)?,
+            VerificationError::TooManyRequests(seconds) => {
+                map.serialize_entry("retry_after_seconds", seconds)?
+            }
+            VerificationError::Permission
+            | VerificationError::AlreadyVerified
+            | VerificationError::InvalidToken
+            | VerificationError::TokenExpired
+            | VerificationError::Notify(_)
+            | VerificationError::Database(_) => {}
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("{0}")]
+    NotLoggedIn(#[from] NotLoggedInError),
+    #[error("permission denied")]
+    Permission,
+    #[error("{0} is not an http or https URL")]
+    InvalidUrl(String),
+    #[error("webhook secret cannot be empty")]
+    EmptySecret,
+    #[error("webhook subscription has to include at least one event type")]
+    NoEventTypes,
+    #[error("webhook subscription {0} does not exist")]
+    Subscription(u32),
+    #[error("webhook delivery {0} does not exist")]
+    Delivery(u32),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl WebhookError {
+    /// Machine readable name of the error, which does not change between versions
+    pub fn code(&self) -> &'static str {
+        match self {
+            WebhookError::NotLoggedIn(_) => "not_logged_in",
+            WebhookError::Permission => "permission_denied",
+            WebhookError::InvalidUrl(_) => "invalid_url",
+            WebhookError::EmptySecret => "empty_secret",
+            WebhookError::NoEventTypes => "no_event_types",
+            WebhookError::Subscription(_) => "webhook_subscription_not_found",
+            WebhookError::Delivery(_) => "webhook_delivery_not_found",
+            WebhookError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl Serialize for WebhookError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let internal = matches!(self, WebhookError::Database(_));
+        serialize_code_and_message(&mut map, self.code(), self, internal)?;
+        match self {
+            WebhookError::NotLoggedIn(e) => map.serialize_entry("function", &e.0)?,
+            WebhookError::InvalidUrl(url) => map.serialize_entry("url", url)?,
+            WebhookError::Subscription(id) => map.serialize_entry("subscription_id", id)?,
+            WebhookError::Delivery(id) => map.serialize_entry("delivery_id", id)?,
+            WebhookError::Permission
+            | WebhookError::EmptySecret
+            | WebhookError::NoEventTypes
+            | WebhookError::Database(_) => {}
+        }
+        map.end()
+    }
+}
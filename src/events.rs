@@ -0,0 +1,112 @@
+//! In-process notifications about changes made through [`ShopBackend`](crate::ShopBackend)
+
+use crate::{Car, Service, User};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Number of events kept for subscribers that fall behind, older events are dropped
+/// and the subscriber receives [`broadcast::error::RecvError::Lagged`]
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Change made through the backend, emitted only after it was saved
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShopEvent {
+    LoggedIn {
+        user: User,
+    },
+    ClientRegistered {
+        client_id: u32,
+    },
+    CarRegistered {
+        client_id: u32,
+        car: Car,
+    },
+    OrderRegistered {
+        order_id: u32,
+        client_id: u32,
+        location_id: u32,
+        service: Service,
+    },
+    ServiceChanged {
+        order_id: u32,
+        client_id: u32,
+        service: Service,
+        /// Version of the order after the change
+        version: u32,
+    },
+    OrderClosed {
+        order_id: u32,
+        client_id: u32,
+        /// Mechanic who finished the work
+        mechanic_id: u32,
+        version: u32,
+    },
+    OrderTransferred {
+        order_id: u32,
+        client_id: u32,
+        from_location_id: u32,
+        location_id: u32,
+        version: u32,
+    },
+    OrderMessageSent {
+        message_id: u32,
+        order_id: u32,
+        client_id: u32,
+        /// `None` for messages from the client
+        employee_id: Option<u32>,
+    },
+    ReportRegistered {
+        report_id: u32,
+        order_id: u32,
+        client_id: u32,
+        cost: u32,
+    },
+    ReportCorrected {
+        report_id: u32,
+        superseded_report_id: u32,
+        order_id: u32,
+        client_id: u32,
+        cost: u32,
+    },
+    PaymentRecorded {
+        payment_id: u32,
+        report_id: u32,
+        client_id: u32,
+        amount: u32,
+    },
+}
+
+/// Broadcast channel for [`ShopEvent`]s, cheap to clone.
+///
+/// Every backend has its own bus, backends serving different users in one process
+/// can share a bus with [`ShopBackend::set_event_bus`](crate::ShopBackend::set_event_bus).
+#[derive(Clone, Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<ShopEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus {
+            sender: broadcast::channel(EVENT_BUS_CAPACITY).0,
+        }
+    }
+
+    /// Receives every event published after this call
+    pub fn subscribe(&self) -> broadcast::Receiver<ShopEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn publish(&self, event: ShopEvent) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    Attachment, Car, Client, ExportError, MileageReading, Notification, Order, OrderMessage,
+    Payment, Report, TroubleCode,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use zip::{write::FileOptions, ZipWriter};
+
+use std::io::{Cursor, Write};
+
+/// Everything the shop stores about a client
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientDataExport {
+    exported_at: DateTime<Utc>,
+    client: Client,
+    vehicles: Vec<Car>,
+    orders: Vec<Order>,
+    reports: Vec<Report>,
+    payments: Vec<Payment>,
+    notifications: Vec<Notification>,
+    complaints: Vec<OrderComplaint>,
+    messages: Vec<OrderMessage>,
+    mileage_readings: Vec<MileageReading>,
+    trouble_codes: Vec<TroubleCode>,
+    attachments: Vec<Attachment>,
+    /// Content of every attachment, in the same order
+    #[serde(skip)]
+    attachment_contents: Vec<Vec<u8>>,
+}
+
+/// What the client said was wrong when an order was registered
+#[derive(Clone, Debug, Serialize)]
+pub struct OrderComplaint {
+    order_id: u32,
+    complaint: String,
+}
+
+impl OrderComplaint {
+    pub(crate) fn new(order_id: u32, complaint: &str) -> Self {
+        OrderComplaint {
+            order_id,
+            complaint: complaint.to_owned(),
+        }
+    }
+
+    pub fn order_id(&self) -> u32 {
+        self.order_id
+    }
+
+    pub fn complaint(&self) -> &str {
+        &self.complaint
+    }
+}
+
+impl ClientDataExport {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        client: Client,
+        orders: Vec<Order>,
+        reports: Vec<Report>,
+        payments: Vec<Payment>,
+        notifications: Vec<Notification>,
+        complaints: Vec<OrderComplaint>,
+        messages: Vec<OrderMessage>,
+        mileage_readings: Vec<MileageReading>,
+        trouble_codes: Vec<TroubleCode>,
+        attachments: Vec<(Attachment, Vec<u8>)>,
+    ) -> Self {
+        let (attachments, attachment_contents) = attachments.into_iter().unzip();
+        ClientDataExport {
+            exported_at: Utc::now(),
+            vehicles: client.car().into_iter().collect(),
+            client,
+            orders,
+            reports,
+            payments,
+            notifications,
+            complaints,
+            messages,
+            mileage_readings,
+            trouble_codes,
+            attachments,
+            attachment_contents,
+        }
+    }
+
+    pub fn exported_at(&self) -> DateTime<Utc> {
+        self.exported_at
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn vehicles(&self) -> &[Car] {
+        &self.vehicles
+    }
+
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    pub fn reports(&self) -> &[Report] {
+        &self.reports
+    }
+
+    pub fn payments(&self) -> &[Payment] {
+        &self.payments
+    }
+
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+
+    pub fn complaints(&self) -> &[OrderComplaint] {
+        &self.complaints
+    }
+
+    pub fn messages(&self) -> &[OrderMessage] {
+        &self.messages
+    }
+
+    pub fn mileage_readings(&self) -> &[MileageReading] {
+        &self.mileage_readings
+    }
+
+    pub fn trouble_codes(&self) -> &[TroubleCode] {
+        &self.trouble_codes
+    }
+
+    /// Attachments visible to the client, their content is only in [`ClientDataExport::to_zip`]
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    pub fn to_json(&self) -> Result<String, ExportError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Zip archive with `data.json`, an HTML invoice for every report and the attached files
+    pub fn to_zip(&self) -> Result<Vec<u8>, ExportError> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+
+        zip.start_file("data.json", options)?;
+        zip.write_all(self.to_json()?.as_bytes())?;
+
+        for report in &self.reports {
+            zip.start_file(format!("invoices/report-{}.html", report.id()), options)?;
+            zip.write_all(self.invoice_html(report).as_bytes())?;
+        }
+
+        for (attachment, content) in self.attachments.iter().zip(&self.attachment_contents) {
+            let file_name = attachment.file_name().replace(['/', '\\'], "_");
+            zip.start_file(
+                format!("attachments/{}-{file_name}", attachment.id()),
+                options,
+            )?;
+            zip.write_all(content)?;
+        }
+
+        Ok(zip.finish()?.into_inner())
+    }
+
+    fn invoice_html(&self, report: &Report) -> String {
+        let service = self
+            .orders
+            .iter()
+            .find(|order| order.id() == report.order_id())
+            .map(|order| order.service().to_string())
+            .unwrap_or_default();
+        let paid: u32 = self
+            .payments
+            .iter()
+            .filter(|payment| payment.report_id() == report.id())
+            .map(|payment| payment.amount())
+            .sum();
+
+        format!(
+            "<!DOCTYPE html>\n\
+             <html>\n\
+             <head><meta charset=\"utf-8\"><title>Invoice {id}</title></head>\n\
+             <body>\n\
+             <h1>Invoice {id}</h1>\n\
+             <p>Client: {name} ({email})</p>\n\
+             <table>\n\
+             <tr><th>Order</th><th>Service</th><th>Cost</th></tr>\n\
+             <tr><td>{order}</td><td>{service}</td><td>${dollars}.{cents:02}</td></tr>\n\
+             </table>\n\
+             <p>Paid: ${paid_dollars}.{paid_cents:02}</p>\n\
+             </body>\n\
+             </html>\n",
+            id = report.id(),
+            name = escape_html(self.client.name()),
+            email = escape_html(self.client.email()),
+            order = report.order_id(),
+            dollars = report.cost() / 100,
+            cents = report.cost() % 100,
+            paid_dollars = paid / 100,
+            paid_cents = paid % 100,
+        )
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -1,12 +1,62 @@
+mod analytics;
+mod attachments;
 mod db_entities;
 mod entities;
 mod errors;
+mod events;
+mod export;
+mod maintenance;
 mod migrator;
+mod notifications;
+mod order_board;
+mod password;
 mod shop_backend;
+mod storage;
+mod tenants;
+mod totp;
+mod trouble_codes;
 mod user;
+mod vehicle_history;
+mod webhooks;
 
-pub use db_entities::{client::Car, order::Service};
+pub use analytics::{
+    to_csv, AnalyticsFilter, CsvRecord, MechanicRevenue, OperationsSummary, Period, PeriodRevenue,
+    Receivable, ServiceRevenue,
+};
+pub use attachments::{BlobStore, FileBlobStore};
+pub use db_entities::{
+    attachment::Visibility,
+    client::{Car, CommunicationPreferences},
+    employee::Role,
+    inspection_result::InspectionStatus,
+    order::Service,
+    webhook_delivery::WebhookEventType,
+};
 pub use entities::*;
 pub use errors::*;
-pub use shop_backend::ShopBackend;
+pub use events::{EventBus, ShopEvent};
+pub use export::{ClientDataExport, OrderComplaint};
+pub use maintenance::MaintenanceDue;
+pub use notifications::*;
+pub use order_board::{BoardEvent, BoardSubscription, BoardUpdate, OrderBoard, OrderChange};
+pub use password::PasswordPolicy;
+pub use shop_backend::{
+    LaborSummary, LocationSummary, NewInspectionItem, ReportPage, ReportSearch, ShopBackend,
+    UnverifiedClientRestrictions, MAX_ATTACHMENT_SIZE,
+};
+pub use storage::{
+    ClientRecord, ClientRepository, EmployeeRecord, EmployeeRepository, InMemoryStorage,
+    LocationRepository, OrderRepository, ReportRepository, SeaOrmStorage, SettingsRepository,
+    Storage, Transaction, VehicleRepository,
+};
+pub use tenants::TenantDirectory;
+pub use totp::{base32_decode, base32_encode, totp_code, TotpEnrollment};
+pub use trouble_codes::{
+    describe_trouble_code, is_generic_trouble_code, normalize_trouble_code, TroubleCodeMatch,
+};
 pub use user::*;
+pub use vehicle_history::{ServiceRecord, VehicleHistory};
+pub use webhooks::{
+    verify_webhook_signature, webhook_signature, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
@@ -0,0 +1,116 @@
+use crate::vehicle_history::VehicleHistory;
+use crate::MaintenanceSchedule;
+
+use chrono::{Duration, Months, NaiveDate};
+use serde::Serialize;
+
+/// When a car next needs the maintenance of a schedule
+#[derive(Clone, Debug, Serialize)]
+pub struct MaintenanceDue {
+    pub schedule: MaintenanceSchedule,
+    /// Finished order that last did the maintenance, `None` if the shop never did it.
+    /// Only orders recorded with [`ShopBackend::record_maintenance_done`] count.
+    ///
+    /// [`ShopBackend::record_maintenance_done`]: crate::ShopBackend::record_maintenance_done
+    pub last_order_id: Option<u32>,
+    /// When the maintenance was last done, or the car's first order if it never was
+    pub last_done_on: Option<NaiveDate>,
+    /// Mileage when the maintenance was last done, or the car's first reading if it never was
+    pub last_done_mileage: Option<u32>,
+    pub due_mileage: Option<u32>,
+    /// Earliest of the end of the time interval and the day the car is expected to reach
+    /// the due mileage at its average daily driving
+    pub due_on: Option<NaiveDate>,
+}
+
+impl MaintenanceDue {
+    /// `done` are the orders recorded as doing the maintenance of `schedule`
+    pub(crate) fn new(
+        schedule: MaintenanceSchedule,
+        history: &VehicleHistory,
+        done: &[u32],
+    ) -> Self {
+        let last = history
+            .services()
+            .iter()
+            .rev()
+            .find(|record| record.order().finished() && done.contains(&record.order().id()));
+        let (last_order_id, last_done_on, last_done_mileage) = match last {
+            Some(record) => {
+                let order = record.order();
+                (
+                    Some(order.id()),
+                    order
+                        .finished_at()
+                        .or(order.created_at())
+                        .map(|at| at.date_naive()),
+                    record.mileage(),
+                )
+            }
+            None => (
+                None,
+                history
+                    .services()
+                    .iter()
+                    .filter_map(|record| record.order().created_at())
+                    .min()
+                    .map(|at| at.date_naive()),
+                history.mileage().first().map(|reading| reading.mileage()),
+            ),
+        };
+
+        let due_by_time = last_done_on
+            .zip(schedule.interval_months())
+            .and_then(|(date, months)| date.checked_add_months(Months::new(months)));
+        let due_mileage = last_done_mileage
+            .zip(schedule.interval_km())
+            .map(|(mileage, km)| mileage.saturating_add(km));
+        let due_by_mileage = due_mileage.and_then(|due| reaches_mileage(history, due));
+        let due_on = match (due_by_time, due_by_mileage) {
+            (Some(time), Some(mileage)) => Some(time.min(mileage)),
+            (time, mileage) => time.or(mileage),
+        };
+
+        MaintenanceDue {
+            schedule,
+            last_order_id,
+            last_done_on,
+            last_done_mileage,
+            due_mileage,
+            due_on,
+        }
+    }
+
+    /// Whether the maintenance is due at most `days` after `today`, or overdue
+    pub fn due_within(&self, today: NaiveDate, days: u32) -> bool {
+        self.due_on
+            .is_some_and(|due_on| due_on <= today + Duration::days(days as i64))
+    }
+}
+
+/// Day the car reaches `mileage`, projected from the readings since the odometer last went
+/// down, or the day of the reading that already reached it. `None` without two readings on
+/// different days to project from.
+fn reaches_mileage(history: &VehicleHistory, mileage: u32) -> Option<NaiveDate> {
+    let readings = history.mileage();
+    let since = readings
+        .iter()
+        .rposition(|reading| reading.flagged())
+        .unwrap_or(0);
+    let (first, latest) = (readings.get(since)?, readings.last()?);
+    if latest.mileage() >= mileage {
+        return Some(latest.recorded_at().date_naive());
+    }
+
+    let days = (latest.recorded_at() - first.recorded_at()).num_days();
+    let driven = latest.mileage().checked_sub(first.mileage())?;
+    if days <= 0 || driven == 0 {
+        return None;
+    }
+    let remaining = (mileage - latest.mileage()) as u64;
+    let days_left = (remaining * days as u64).div_ceil(driven as u64);
+    latest
+        .recorded_at()
+        .date_naive()
+        .checked_add_signed(Duration::days(days_left as i64))
+}
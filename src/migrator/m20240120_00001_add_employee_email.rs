@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum EmployeeEmail {
+    Email,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .add_column(ColumnDef::new(EmployeeEmail::Email).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .drop_column(EmployeeEmail::Email)
+                    .to_owned(),
+            )
+            .await
+    }
+}
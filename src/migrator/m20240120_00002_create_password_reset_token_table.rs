@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+use super::{
+    m20240111_00001_create_client_table::Client, m20240111_00001_create_employee_table::Employee,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum PasswordResetToken {
+    Table,
+    Id,
+    ClientId,
+    EmployeeId,
+    TokenHash,
+    ExpiresAt,
+    Used,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordResetToken::Table)
+                    .col(
+                        ColumnDef::new(PasswordResetToken::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PasswordResetToken::ClientId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-password_reset_token-client_id")
+                            .from(PasswordResetToken::Table, PasswordResetToken::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .col(ColumnDef::new(PasswordResetToken::EmployeeId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-password_reset_token-employee_id")
+                            .from(PasswordResetToken::Table, PasswordResetToken::EmployeeId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::TokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordResetToken::Used)
+                            .boolean()
+                            .default(false)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PasswordResetToken::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,73 @@
+use sea_orm::{DbBackend, EnumIter, Iterable};
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+use super::m20240111_00001_create_employee_table::Employee;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden, EnumIter)]
+pub enum Role {
+    Table,
+    #[iden = "Technician"]
+    Technician,
+    #[iden = "Mechanic"]
+    Mechanic,
+    #[iden = "Manager"]
+    Manager,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        match manager.get_database_backend() {
+            DbBackend::Postgres => {
+                manager
+                    .alter_type(
+                        Type::alter()
+                            .name(Role::Table)
+                            .add_value(Role::Manager)
+                            .to_owned(),
+                    )
+                    .await
+            }
+            DbBackend::MySql => {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(Employee::Table)
+                            .modify_column(
+                                ColumnDef::new(Employee::Role)
+                                    .enumeration(Role::Table, Role::iter().skip(1))
+                                    .not_null(),
+                            )
+                            .to_owned(),
+                    )
+                    .await
+            }
+            // SQLite stores enums as text
+            DbBackend::Sqlite => Ok(()),
+        }
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        match manager.get_database_backend() {
+            DbBackend::MySql => {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(Employee::Table)
+                            .modify_column(
+                                ColumnDef::new(Employee::Role)
+                                    .enumeration(Role::Table, [Role::Technician, Role::Mechanic])
+                                    .not_null(),
+                            )
+                            .to_owned(),
+                    )
+                    .await
+            }
+            // Postgres can't remove values from an enum type
+            _ => Ok(()),
+        }
+    }
+}
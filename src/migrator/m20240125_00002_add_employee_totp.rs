@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum EmployeeTotp {
+    #[iden = "totp_secret"]
+    Secret,
+    #[iden = "totp_enabled"]
+    Enabled,
+    #[iden = "totp_last_step"]
+    LastStep,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .add_column(ColumnDef::new(EmployeeTotp::Secret).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .add_column(
+                        ColumnDef::new(EmployeeTotp::Enabled)
+                            .boolean()
+                            .default(false)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .add_column(ColumnDef::new(EmployeeTotp::LastStep).big_integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            EmployeeTotp::Secret,
+            EmployeeTotp::Enabled,
+            EmployeeTotp::LastStep,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Employee::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
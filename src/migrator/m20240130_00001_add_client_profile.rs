@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_client_table::Client;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum ClientProfile {
+    Phone,
+    Address,
+    CommunicationPreferences,
+    EmailVerified,
+    Deleted,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite can only add one column per statement
+        for mut column in [
+            ColumnDef::new(ClientProfile::Phone).string().to_owned(),
+            ColumnDef::new(ClientProfile::Address).string().to_owned(),
+            ColumnDef::new(ClientProfile::CommunicationPreferences)
+                .json()
+                .to_owned(),
+            // Accounts created before verification existed count as verified
+            ColumnDef::new(ClientProfile::EmailVerified)
+                .boolean()
+                .default(true)
+                .not_null()
+                .to_owned(),
+            ColumnDef::new(ClientProfile::Deleted)
+                .boolean()
+                .default(false)
+                .not_null()
+                .to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Client::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            ClientProfile::Phone,
+            ClientProfile::Address,
+            ClientProfile::CommunicationPreferences,
+            ClientProfile::EmailVerified,
+            ClientProfile::Deleted,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Client::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use super::{
+    m20240111_00001_create_client_table::Client, m20240111_00001_create_report_table::Report,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum Payment {
+    Table,
+    Id,
+    ReportId,
+    ClientId,
+    Amount,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Payment::Table)
+                    .col(
+                        ColumnDef::new(Payment::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Payment::ReportId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-payment-report_id")
+                            .from(Payment::Table, Payment::ReportId)
+                            .to(Report::Table, Report::Id),
+                    )
+                    .col(ColumnDef::new(Payment::ClientId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-payment-client_id")
+                            .from(Payment::Table, Payment::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .col(ColumnDef::new(Payment::Amount).integer().not_null())
+                    .col(
+                        ColumnDef::new(Payment::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Payment::Table).to_owned())
+            .await
+    }
+}
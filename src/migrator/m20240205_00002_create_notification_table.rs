@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_client_table::Client;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum Notification {
+    Table,
+    Id,
+    ClientId,
+    Subject,
+    Body,
+    CreatedAt,
+    Read,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .col(
+                        ColumnDef::new(Notification::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Notification::ClientId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification-client_id")
+                            .from(Notification::Table, Notification::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .col(ColumnDef::new(Notification::Subject).string().not_null())
+                    .col(ColumnDef::new(Notification::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(Notification::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Notification::Read)
+                            .boolean()
+                            .default(false)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notification::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,105 @@
+use sea_orm::{EnumIter, Iterable};
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_client_table::Client;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum NotificationOutbox {
+    Table,
+    Id,
+    ClientId,
+    Channel,
+    Recipient,
+    Subject,
+    Body,
+    Attempts,
+    NextAttemptAt,
+    SentAt,
+    LastError,
+    CreatedAt,
+}
+
+#[derive(Iden, EnumIter)]
+pub enum Channel {
+    Table,
+    #[iden = "Email"]
+    Email,
+    #[iden = "Sms"]
+    Sms,
+    #[iden = "InApp"]
+    InApp,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationOutbox::Table)
+                    .col(
+                        ColumnDef::new(NotificationOutbox::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationOutbox::ClientId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notification_outbox-client_id")
+                            .from(NotificationOutbox::Table, NotificationOutbox::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationOutbox::Channel)
+                            .enumeration(Channel::Table, Channel::iter().skip(1))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationOutbox::Recipient)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationOutbox::Subject)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(NotificationOutbox::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(NotificationOutbox::Attempts)
+                            .integer()
+                            .default(0)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(NotificationOutbox::NextAttemptAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(NotificationOutbox::SentAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(NotificationOutbox::LastError).text())
+                    .col(
+                        ColumnDef::new(NotificationOutbox::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NotificationOutbox::Table).to_owned())
+            .await
+    }
+}
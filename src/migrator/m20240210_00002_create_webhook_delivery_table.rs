@@ -0,0 +1,100 @@
+use sea_orm::{EnumIter, Iterable};
+use sea_orm_migration::prelude::*;
+
+use super::m20240210_00001_create_webhook_subscription_table::WebhookSubscription;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum WebhookDelivery {
+    Table,
+    Id,
+    SubscriptionId,
+    EventId,
+    EventType,
+    Payload,
+    Attempts,
+    NextAttemptAt,
+    DeliveredAt,
+    ResponseStatus,
+    LastError,
+    CreatedAt,
+}
+
+#[derive(Iden, EnumIter)]
+pub enum WebhookEventType {
+    Table,
+    #[iden = "order.created"]
+    OrderCreated,
+    #[iden = "order.status_changed"]
+    OrderStatusChanged,
+    #[iden = "report.created"]
+    ReportCreated,
+    #[iden = "payment.recorded"]
+    PaymentRecorded,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::SubscriptionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-webhook_delivery-subscription_id")
+                            .from(WebhookDelivery::Table, WebhookDelivery::SubscriptionId)
+                            .to(WebhookSubscription::Table, WebhookSubscription::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::EventId).string().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::EventType)
+                            .enumeration(WebhookEventType::Table, WebhookEventType::iter().skip(1))
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::Payload).text().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempts)
+                            .integer()
+                            .default(0)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::NextAttemptAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::DeliveredAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(WebhookDelivery::ResponseStatus).integer())
+                    .col(ColumnDef::new(WebhookDelivery::LastError).text())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,127 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+use super::m20240111_00001_create_report_table::Report;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Revisions {
+    Revision,
+    SupersededBy,
+}
+
+const INDEX: &str = "idx-report-order-revision";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Report::Table)
+                    .add_column(
+                        ColumnDef::new(Revisions::Revision)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Report::Table)
+                    .add_column(ColumnDef::new(Revisions::SupersededBy).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Orders with several reports keep all of them, the later ones become
+        // corrections of the earlier ones
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Report::Id, Report::OrderId])
+                        .from(Report::Table)
+                        .order_by(Report::OrderId, Order::Asc)
+                        .order_by(Report::Id, Order::Asc),
+                ),
+            )
+            .await?;
+        let mut reports = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: i32 = row.try_get("", &Report::Id.to_string())?;
+            let order_id: i32 = row.try_get("", &Report::OrderId.to_string())?;
+            reports.push((id, order_id));
+        }
+
+        let mut revision = 0;
+        for (i, &(id, order_id)) in reports.iter().enumerate() {
+            revision = match i.checked_sub(1).map(|prev| reports[prev].1) {
+                Some(prev_order_id) if prev_order_id == order_id => revision + 1,
+                _ => 1,
+            };
+            let superseded_by = reports
+                .get(i + 1)
+                .filter(|next| next.1 == order_id)
+                .map(|next| next.0);
+            if revision == 1 && superseded_by.is_none() {
+                continue;
+            }
+
+            db.execute(
+                backend.build(
+                    Query::update()
+                        .table(Report::Table)
+                        .value(Revisions::Revision, revision)
+                        .value(Revisions::SupersededBy, superseded_by)
+                        .and_where(Expr::col(Report::Id).eq(id)),
+                ),
+            )
+            .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX)
+                    .table(Report::Table)
+                    .col(Report::OrderId)
+                    .col(Revisions::Revision)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name(INDEX).table(Report::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Report::Table)
+                    .drop_column(Revisions::SupersededBy)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Report::Table)
+                    .drop_column(Revisions::Revision)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum Location {
+    Table,
+    Id,
+    Name,
+    Address,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Location::Table)
+                    .col(
+                        ColumnDef::new(Location::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Location::Name).string().not_null())
+                    .col(ColumnDef::new(Location::Address).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Everything from before locations existed belongs to the first one
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Location::Table)
+                    .columns([Location::Name])
+                    .values_panic(["Main workshop".into()])
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Location::Table).to_owned())
+            .await
+    }
+}
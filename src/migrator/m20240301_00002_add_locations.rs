@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+use super::m20240111_00001_create_report_table::Report;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Located {
+    LocationId,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite cannot add foreign keys to existing tables, the backend checks locations
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .add_column(
+                        ColumnDef::new(Located::LocationId)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Report::Table)
+                    .add_column(
+                        ColumnDef::new(Located::LocationId)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Employees without a location work at all of them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .add_column(ColumnDef::new(Located::LocationId).integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .drop_column(Located::LocationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Report::Table)
+                    .drop_column(Located::LocationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .drop_column(Located::LocationId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
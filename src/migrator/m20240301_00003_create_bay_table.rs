@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240301_00001_create_location_table::Location;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum Bay {
+    Table,
+    Id,
+    LocationId,
+    Name,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Bay::Table)
+                    .col(
+                        ColumnDef::new(Bay::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Bay::LocationId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-bay-location_id")
+                            .from(Bay::Table, Bay::LocationId)
+                            .to(Location::Table, Location::Id),
+                    )
+                    .col(ColumnDef::new(Bay::Name).string().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Bay::Table).to_owned())
+            .await
+    }
+}
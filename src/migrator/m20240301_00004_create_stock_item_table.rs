@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240301_00001_create_location_table::Location;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum StockItem {
+    Table,
+    Id,
+    LocationId,
+    PartNumber,
+    Name,
+    Quantity,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockItem::Table)
+                    .col(
+                        ColumnDef::new(StockItem::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StockItem::LocationId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-stock_item-location_id")
+                            .from(StockItem::Table, StockItem::LocationId)
+                            .to(Location::Table, Location::Id),
+                    )
+                    .col(ColumnDef::new(StockItem::PartNumber).string().not_null())
+                    .col(ColumnDef::new(StockItem::Name).string().not_null())
+                    .col(ColumnDef::new(StockItem::Quantity).integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-stock_item-location-part")
+                    .table(StockItem::Table)
+                    .col(StockItem::LocationId)
+                    .col(StockItem::PartNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StockItem::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum OrderTracking {
+    CreatedAt,
+    FinishedAt,
+    MechanicId,
+    ConvertedToRepair,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Orders registered before this migration have no dates and no mechanic
+        for mut column in [
+            ColumnDef::new(OrderTracking::CreatedAt)
+                .timestamp_with_time_zone()
+                .to_owned(),
+            ColumnDef::new(OrderTracking::FinishedAt)
+                .timestamp_with_time_zone()
+                .to_owned(),
+            ColumnDef::new(OrderTracking::MechanicId)
+                .integer()
+                .to_owned(),
+            ColumnDef::new(OrderTracking::ConvertedToRepair)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Order::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            OrderTracking::CreatedAt,
+            OrderTracking::FinishedAt,
+            OrderTracking::MechanicId,
+            OrderTracking::ConvertedToRepair,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Order::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use sea_orm::DbBackend;
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum LaborEntry {
+    Table,
+    Id,
+    OrderId,
+    MechanicId,
+    StartedAt,
+    EndedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LaborEntry::Table)
+                    .col(
+                        ColumnDef::new(LaborEntry::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LaborEntry::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-labor_entry-order_id")
+                            .from(LaborEntry::Table, LaborEntry::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    .col(ColumnDef::new(LaborEntry::MechanicId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-labor_entry-mechanic_id")
+                            .from(LaborEntry::Table, LaborEntry::MechanicId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(
+                        ColumnDef::new(LaborEntry::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    // Open while the mechanic is clocked on
+                    .col(ColumnDef::new(LaborEntry::EndedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-labor_entry-mechanic_id")
+                    .table(LaborEntry::Table)
+                    .col(LaborEntry::MechanicId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Mechanics are clocked on to one order at a time. MySQL has no partial indexes,
+        // there clock_on's locking read of the mechanic's entries keeps others out.
+        match manager.get_database_backend() {
+            DbBackend::Postgres | DbBackend::Sqlite => {
+                manager
+                    .get_connection()
+                    .execute_unprepared(
+                        "CREATE UNIQUE INDEX \"idx-labor_entry-open\" ON labor_entry (mechanic_id) \
+                         WHERE ended_at IS NULL",
+                    )
+                    .await?;
+                Ok(())
+            }
+            DbBackend::MySql => Ok(()),
+        }
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LaborEntry::Table).to_owned())
+            .await
+    }
+}
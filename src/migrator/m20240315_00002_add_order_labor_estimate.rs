@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum LaborEstimate {
+    EstimatedMinutes,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .add_column(ColumnDef::new(LaborEstimate::EstimatedMinutes).integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .drop_column(LaborEstimate::EstimatedMinutes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_report_table::Report;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum LaborLine {
+    Table,
+    Id,
+    ReportId,
+    MechanicId,
+    Minutes,
+    Rate,
+    Amount,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LaborLine::Table)
+                    .col(
+                        ColumnDef::new(LaborLine::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LaborLine::ReportId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-labor_line-report_id")
+                            .from(LaborLine::Table, LaborLine::ReportId)
+                            .to(Report::Table, Report::Id),
+                    )
+                    .col(ColumnDef::new(LaborLine::MechanicId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-labor_line-mechanic_id")
+                            .from(LaborLine::Table, LaborLine::MechanicId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(ColumnDef::new(LaborLine::Minutes).integer().not_null())
+                    .col(ColumnDef::new(LaborLine::Rate).integer().not_null())
+                    .col(ColumnDef::new(LaborLine::Amount).integer().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LaborLine::Table).to_owned())
+            .await
+    }
+}
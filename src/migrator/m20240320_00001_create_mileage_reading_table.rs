@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_client_table::Client;
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum MileageReading {
+    Table,
+    Id,
+    ClientId,
+    OrderId,
+    Mileage,
+    RecordedAt,
+    Reason,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MileageReading::Table)
+                    .col(
+                        ColumnDef::new(MileageReading::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // Clients have one car, which the reading is of
+                    .col(
+                        ColumnDef::new(MileageReading::ClientId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-mileage_reading-client_id")
+                            .from(MileageReading::Table, MileageReading::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .col(ColumnDef::new(MileageReading::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-mileage_reading-order_id")
+                            .from(MileageReading::Table, MileageReading::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    .col(ColumnDef::new(MileageReading::Mileage).integer().not_null())
+                    .col(
+                        ColumnDef::new(MileageReading::RecordedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    // Why the mileage is lower than before, e.g. a replaced odometer
+                    .col(ColumnDef::new(MileageReading::Reason).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-mileage_reading-client_id")
+                    .table(MileageReading::Table)
+                    .col(MileageReading::ClientId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MileageReading::Table).to_owned())
+            .await
+    }
+}
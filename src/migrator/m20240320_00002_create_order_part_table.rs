@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum OrderPart {
+    Table,
+    Id,
+    OrderId,
+    PartNumber,
+    Name,
+    Quantity,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrderPart::Table)
+                    .col(
+                        ColumnDef::new(OrderPart::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OrderPart::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-order_part-order_id")
+                            .from(OrderPart::Table, OrderPart::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    .col(ColumnDef::new(OrderPart::PartNumber).string().not_null())
+                    .col(ColumnDef::new(OrderPart::Name).string().not_null())
+                    .col(ColumnDef::new(OrderPart::Quantity).integer().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrderPart::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,65 @@
+use sea_orm::Iterable;
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_order_table::Service;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum MaintenanceSchedule {
+    Table,
+    Id,
+    Name,
+    Service,
+    Make,
+    IntervalKm,
+    IntervalMonths,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MaintenanceSchedule::Table)
+                    .col(
+                        ColumnDef::new(MaintenanceSchedule::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceSchedule::Name)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceSchedule::Service)
+                            .enumeration(Service::Table, Service::iter().skip(1))
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MaintenanceSchedule::Make).string().null())
+                    .col(
+                        ColumnDef::new(MaintenanceSchedule::IntervalKm)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceSchedule::IntervalMonths)
+                            .integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MaintenanceSchedule::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_client_table::Client;
+use super::m20240325_00001_create_maintenance_schedule_table::MaintenanceSchedule;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum MaintenanceReminder {
+    Table,
+    Id,
+    ClientId,
+    ScheduleId,
+    LastOrderId,
+    SentAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MaintenanceReminder::Table)
+                    .col(
+                        ColumnDef::new(MaintenanceReminder::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceReminder::ClientId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-maintenance_reminder-client_id")
+                            .from(MaintenanceReminder::Table, MaintenanceReminder::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceReminder::ScheduleId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-maintenance_reminder-schedule_id")
+                            .from(MaintenanceReminder::Table, MaintenanceReminder::ScheduleId)
+                            .to(MaintenanceSchedule::Table, MaintenanceSchedule::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceReminder::LastOrderId)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceReminder::SentAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-maintenance_reminder-client_id")
+                    .table(MaintenanceReminder::Table)
+                    .col(MaintenanceReminder::ClientId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MaintenanceReminder::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_order_table::Order;
+use super::m20240325_00001_create_maintenance_schedule_table::MaintenanceSchedule;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum MaintenanceDone {
+    Table,
+    Id,
+    ScheduleId,
+    OrderId,
+    RecordedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MaintenanceDone::Table)
+                    .col(
+                        ColumnDef::new(MaintenanceDone::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceDone::ScheduleId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-maintenance_done-schedule_id")
+                            .from(MaintenanceDone::Table, MaintenanceDone::ScheduleId)
+                            .to(MaintenanceSchedule::Table, MaintenanceSchedule::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceDone::OrderId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-maintenance_done-order_id")
+                            .from(MaintenanceDone::Table, MaintenanceDone::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceDone::RecordedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An order does the maintenance of a schedule at most once
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-maintenance_done-order_id-schedule_id")
+                    .table(MaintenanceDone::Table)
+                    .col(MaintenanceDone::OrderId)
+                    .col(MaintenanceDone::ScheduleId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MaintenanceDone::Table).to_owned())
+            .await
+    }
+}
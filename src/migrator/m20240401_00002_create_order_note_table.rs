@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum OrderNote {
+    Table,
+    Id,
+    OrderId,
+    EmployeeId,
+    Body,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrderNote::Table)
+                    .col(
+                        ColumnDef::new(OrderNote::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OrderNote::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-order_note-order_id")
+                            .from(OrderNote::Table, OrderNote::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    .col(ColumnDef::new(OrderNote::EmployeeId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-order_note-employee_id")
+                            .from(OrderNote::Table, OrderNote::EmployeeId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(ColumnDef::new(OrderNote::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(OrderNote::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrderNote::Table).to_owned())
+            .await
+    }
+}
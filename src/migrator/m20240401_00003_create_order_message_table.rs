@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum OrderMessage {
+    Table,
+    Id,
+    OrderId,
+    EmployeeId,
+    Body,
+    SentAt,
+    ReadAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrderMessage::Table)
+                    .col(
+                        ColumnDef::new(OrderMessage::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OrderMessage::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-order_message-order_id")
+                            .from(OrderMessage::Table, OrderMessage::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    // Empty for messages from the client
+                    .col(ColumnDef::new(OrderMessage::EmployeeId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-order_message-employee_id")
+                            .from(OrderMessage::Table, OrderMessage::EmployeeId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(ColumnDef::new(OrderMessage::Body).text().not_null())
+                    .col(
+                        ColumnDef::new(OrderMessage::SentAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OrderMessage::ReadAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-order_message-order_id")
+                    .table(OrderMessage::Table)
+                    .col(OrderMessage::OrderId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrderMessage::Table).to_owned())
+            .await
+    }
+}
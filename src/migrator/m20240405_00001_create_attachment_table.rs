@@ -0,0 +1,110 @@
+use sea_orm::{EnumIter, Iterable};
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum Attachment {
+    Table,
+    Id,
+    OrderId,
+    EmployeeId,
+    FileName,
+    ContentType,
+    Size,
+    Hash,
+    HasThumbnail,
+    Visibility,
+    CreatedAt,
+}
+
+#[derive(Iden, EnumIter)]
+pub enum Visibility {
+    Table,
+    #[iden = "Internal"]
+    Internal,
+    #[iden = "Client"]
+    Client,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attachment::Table)
+                    .col(
+                        ColumnDef::new(Attachment::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Attachment::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachment-order_id")
+                            .from(Attachment::Table, Attachment::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    // Empty for attachments uploaded by the client
+                    .col(ColumnDef::new(Attachment::EmployeeId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachment-employee_id")
+                            .from(Attachment::Table, Attachment::EmployeeId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(ColumnDef::new(Attachment::FileName).string().not_null())
+                    .col(ColumnDef::new(Attachment::ContentType).string().not_null())
+                    .col(ColumnDef::new(Attachment::Size).big_integer().not_null())
+                    // SHA-256 of the content, which is its key in the blob store
+                    .col(ColumnDef::new(Attachment::Hash).string().not_null())
+                    .col(
+                        ColumnDef::new(Attachment::HasThumbnail)
+                            .boolean()
+                            .default(false)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Attachment::Visibility)
+                            .enumeration(Visibility::Table, Visibility::iter().skip(1))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Attachment::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, column) in [
+            ("idx-attachment-order_id", Attachment::OrderId),
+            ("idx-attachment-hash", Attachment::Hash),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(name)
+                        .table(Attachment::Table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Attachment::Table).to_owned())
+            .await
+    }
+}
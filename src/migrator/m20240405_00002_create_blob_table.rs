@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum Blob {
+    Table,
+    Hash,
+    HasThumbnail,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per content in the blob store, locked while attachments are added or
+        // removed so that content is not deleted while a new attachment starts using it
+        manager
+            .create_table(
+                Table::create()
+                    .table(Blob::Table)
+                    .col(ColumnDef::new(Blob::Hash).string().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(Blob::HasThumbnail)
+                            .boolean()
+                            .default(false)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Blob::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240410_00001_create_inspection_template_table::InspectionTemplate;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum InspectionItem {
+    Table,
+    Id,
+    TemplateId,
+    Position,
+    Section,
+    Name,
+    Unit,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(InspectionItem::Table)
+                    .col(
+                        ColumnDef::new(InspectionItem::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InspectionItem::TemplateId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-inspection_item-template_id")
+                            .from(InspectionItem::Table, InspectionItem::TemplateId)
+                            .to(InspectionTemplate::Table, InspectionTemplate::Id),
+                    )
+                    // Order of the item on the checklist
+                    .col(
+                        ColumnDef::new(InspectionItem::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(InspectionItem::Section).string().not_null())
+                    .col(ColumnDef::new(InspectionItem::Name).string().not_null())
+                    // Empty for items that are only looked at, e.g. mm for tread depth
+                    .col(ColumnDef::new(InspectionItem::Unit).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(InspectionItem::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,121 @@
+use sea_orm::{EnumIter, Iterable};
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+use super::m20240410_00002_create_inspection_item_table::InspectionItem;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum InspectionResult {
+    Table,
+    Id,
+    OrderId,
+    ItemId,
+    Status,
+    Measurement,
+    Comment,
+    MechanicId,
+    RecordedAt,
+}
+
+#[derive(Iden, EnumIter)]
+pub enum InspectionStatus {
+    Table,
+    #[iden = "Pass"]
+    Pass,
+    #[iden = "Advisory"]
+    Advisory,
+    #[iden = "Fail"]
+    Fail,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(InspectionResult::Table)
+                    .col(
+                        ColumnDef::new(InspectionResult::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InspectionResult::OrderId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-inspection_result-order_id")
+                            .from(InspectionResult::Table, InspectionResult::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    .col(
+                        ColumnDef::new(InspectionResult::ItemId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-inspection_result-item_id")
+                            .from(InspectionResult::Table, InspectionResult::ItemId)
+                            .to(InspectionItem::Table, InspectionItem::Id),
+                    )
+                    .col(
+                        ColumnDef::new(InspectionResult::Status)
+                            .enumeration(InspectionStatus::Table, InspectionStatus::iter().skip(1))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InspectionResult::Measurement)
+                            .double()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(InspectionResult::Comment).text().null())
+                    .col(
+                        ColumnDef::new(InspectionResult::MechanicId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-inspection_result-mechanic_id")
+                            .from(InspectionResult::Table, InspectionResult::MechanicId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(
+                        ColumnDef::new(InspectionResult::RecordedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An item is checked once per order, checking it again replaces the result
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-inspection_result-order_id-item_id")
+                    .table(InspectionResult::Table)
+                    .col(InspectionResult::OrderId)
+                    .col(InspectionResult::ItemId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(InspectionResult::Table).to_owned())
+            .await
+    }
+}
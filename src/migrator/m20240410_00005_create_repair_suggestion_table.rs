@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_order_table::Order;
+use super::m20240410_00002_create_inspection_item_table::InspectionItem;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum RepairSuggestion {
+    Table,
+    Id,
+    OrderId,
+    ItemId,
+    Description,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RepairSuggestion::Table)
+                    .col(
+                        ColumnDef::new(RepairSuggestion::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RepairSuggestion::OrderId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-repair_suggestion-order_id")
+                            .from(RepairSuggestion::Table, RepairSuggestion::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    // Failed checklist item the repair is suggested for
+                    .col(
+                        ColumnDef::new(RepairSuggestion::ItemId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-repair_suggestion-item_id")
+                            .from(RepairSuggestion::Table, RepairSuggestion::ItemId)
+                            .to(InspectionItem::Table, InspectionItem::Id),
+                    )
+                    .col(
+                        ColumnDef::new(RepairSuggestion::Description)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RepairSuggestion::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-repair_suggestion-order_id")
+                    .table(RepairSuggestion::Table)
+                    .col(RepairSuggestion::OrderId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RepairSuggestion::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240111_00001_create_employee_table::Employee;
+use super::m20240111_00001_create_order_table::Order;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+pub enum TroubleCode {
+    Table,
+    Id,
+    OrderId,
+    Code,
+    FreezeFrame,
+    Cleared,
+    MechanicId,
+    RecordedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TroubleCode::Table)
+                    .col(
+                        ColumnDef::new(TroubleCode::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TroubleCode::OrderId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-trouble_code-order_id")
+                            .from(TroubleCode::Table, TroubleCode::OrderId)
+                            .to(Order::Table, Order::Id),
+                    )
+                    // Five characters, e.g. P0420
+                    .col(ColumnDef::new(TroubleCode::Code).string_len(5).not_null())
+                    // Sensor values the car stored when it set the code
+                    .col(ColumnDef::new(TroubleCode::FreezeFrame).json())
+                    .col(
+                        ColumnDef::new(TroubleCode::Cleared)
+                            .boolean()
+                            .default(false)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TroubleCode::MechanicId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-trouble_code-mechanic_id")
+                            .from(TroubleCode::Table, TroubleCode::MechanicId)
+                            .to(Employee::Table, Employee::Id),
+                    )
+                    .col(
+                        ColumnDef::new(TroubleCode::RecordedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, column) in [
+            ("idx-trouble_code-order_id", TroubleCode::OrderId),
+            ("idx-trouble_code-code", TroubleCode::Code),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(name)
+                        .table(TroubleCode::Table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TroubleCode::Table).to_owned())
+            .await
+    }
+}
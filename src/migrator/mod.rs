@@ -2,6 +2,46 @@ mod m20240111_00001_create_client_table;
 mod m20240111_00001_create_employee_table;
 mod m20240111_00001_create_order_table;
 mod m20240111_00001_create_report_table;
+mod m20240120_00001_add_employee_email;
+mod m20240120_00002_create_password_reset_token_table;
+mod m20240125_00001_add_manager_role;
+mod m20240125_00002_add_employee_totp;
+mod m20240125_00003_create_recovery_code_table;
+mod m20240125_00004_create_setting_table;
+mod m20240130_00001_add_client_profile;
+mod m20240201_00001_add_client_verification_sent_at;
+mod m20240205_00001_create_payment_table;
+mod m20240205_00002_create_notification_table;
+mod m20240205_00003_create_notification_outbox_table;
+mod m20240210_00001_create_webhook_subscription_table;
+mod m20240210_00002_create_webhook_delivery_table;
+mod m20240215_00001_add_order_and_report_version;
+mod m20240220_00001_add_report_revisions;
+mod m20240225_00001_add_report_created_at;
+mod m20240301_00001_create_location_table;
+mod m20240301_00002_add_locations;
+mod m20240301_00003_create_bay_table;
+mod m20240301_00004_create_stock_item_table;
+mod m20240310_00001_add_order_tracking;
+mod m20240315_00001_create_labor_entry_table;
+mod m20240315_00002_add_order_labor_estimate;
+mod m20240315_00003_create_labor_line_table;
+mod m20240320_00001_create_mileage_reading_table;
+mod m20240320_00002_create_order_part_table;
+mod m20240325_00001_create_maintenance_schedule_table;
+mod m20240325_00002_create_maintenance_reminder_table;
+mod m20240325_00003_create_maintenance_done_table;
+mod m20240401_00001_add_order_complaint;
+mod m20240401_00002_create_order_note_table;
+mod m20240401_00003_create_order_message_table;
+mod m20240405_00001_create_attachment_table;
+mod m20240405_00002_create_blob_table;
+mod m20240410_00001_create_inspection_template_table;
+mod m20240410_00002_create_inspection_item_table;
+mod m20240410_00003_add_order_inspection_template;
+mod m20240410_00004_create_inspection_result_table;
+mod m20240410_00005_create_repair_suggestion_table;
+mod m20240415_00001_create_trouble_code_table;
 
 use sea_orm_migration::prelude::*;
 
@@ -15,6 +55,46 @@ impl MigratorTrait for Migrator {
             Box::new(m20240111_00001_create_report_table::Migration),
             Box::new(m20240111_00001_create_order_table::Migration),
             Box::new(m20240111_00001_create_employee_table::Migration),
+            Box::new(m20240120_00001_add_employee_email::Migration),
+            Box::new(m20240120_00002_create_password_reset_token_table::Migration),
+            Box::new(m20240125_00001_add_manager_role::Migration),
+            Box::new(m20240125_00002_add_employee_totp::Migration),
+            Box::new(m20240125_00003_create_recovery_code_table::Migration),
+            Box::new(m20240125_00004_create_setting_table::Migration),
+            Box::new(m20240130_00001_add_client_profile::Migration),
+            Box::new(m20240201_00001_add_client_verification_sent_at::Migration),
+            Box::new(m20240205_00001_create_payment_table::Migration),
+            Box::new(m20240205_00002_create_notification_table::Migration),
+            Box::new(m20240205_00003_create_notification_outbox_table::Migration),
+            Box::new(m20240210_00001_create_webhook_subscription_table::Migration),
+            Box::new(m20240210_00002_create_webhook_delivery_table::Migration),
+            Box::new(m20240215_00001_add_order_and_report_version::Migration),
+            Box::new(m20240220_00001_add_report_revisions::Migration),
+            Box::new(m20240225_00001_add_report_created_at::Migration),
+            Box::new(m20240301_00001_create_location_table::Migration),
+            Box::new(m20240301_00002_add_locations::Migration),
+            Box::new(m20240301_00003_create_bay_table::Migration),
+            Box::new(m20240301_00004_create_stock_item_table::Migration),
+            Box::new(m20240310_00001_add_order_tracking::Migration),
+            Box::new(m20240315_00001_create_labor_entry_table::Migration),
+            Box::new(m20240315_00002_add_order_labor_estimate::Migration),
+            Box::new(m20240315_00003_create_labor_line_table::Migration),
+            Box::new(m20240320_00001_create_mileage_reading_table::Migration),
+            Box::new(m20240320_00002_create_order_part_table::Migration),
+            Box::new(m20240325_00001_create_maintenance_schedule_table::Migration),
+            Box::new(m20240325_00002_create_maintenance_reminder_table::Migration),
+            Box::new(m20240325_00003_create_maintenance_done_table::Migration),
+            Box::new(m20240401_00001_add_order_complaint::Migration),
+            Box::new(m20240401_00002_create_order_note_table::Migration),
+            Box::new(m20240401_00003_create_order_message_table::Migration),
+            Box::new(m20240405_00001_create_attachment_table::Migration),
+            Box::new(m20240405_00002_create_blob_table::Migration),
+            Box::new(m20240410_00001_create_inspection_template_table::Migration),
+            Box::new(m20240410_00002_create_inspection_item_table::Migration),
+            Box::new(m20240410_00003_add_order_inspection_template::Migration),
+            Box::new(m20240410_00004_create_inspection_result_table::Migration),
+            Box::new(m20240410_00005_create_repair_suggestion_table::Migration),
+            Box::new(m20240415_00001_create_trouble_code_table::Migration),
         ]
     }
 }
@@ -0,0 +1,127 @@
+use crate::Service;
+
+use chrono::NaiveDate;
+
+/// Something a client is told about, rendered with a fixed template
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotificationEvent {
+    OrderReceived {
+        order_id: u32,
+        service: Service,
+    },
+    OrderStatusChanged {
+        order_id: u32,
+        status: String,
+    },
+    EstimateReady {
+        order_id: u32,
+    },
+    ReportIssued {
+        report_id: u32,
+        order_id: u32,
+        cost: u32,
+    },
+    ReportCorrected {
+        report_id: u32,
+        superseded_report_id: u32,
+        order_id: u32,
+        cost: u32,
+    },
+    PaymentReceived {
+        report_id: u32,
+        amount: u32,
+    },
+    MessageReceived {
+        order_id: u32,
+    },
+    MaintenanceDue {
+        name: String,
+        car: String,
+        due_on: Option<NaiveDate>,
+        due_mileage: Option<u32>,
+    },
+}
+
+fn format_money(amount: u32) -> String {
+    format!("${}.{:02}", amount / 100, amount % 100)
+}
+
+impl NotificationEvent {
+    /// Subject and body of the message
+    pub fn render(&self) -> (String, String) {
+        match self {
+            NotificationEvent::OrderReceived { order_id, service } => (
+                format!("Order {order_id} received"),
+                format!("We have received your order {order_id} for {service}."),
+            ),
+            NotificationEvent::OrderStatusChanged { order_id, status } => (
+                format!("Order {order_id} updated"),
+                format!("Your order {order_id} is now {status}."),
+            ),
+            NotificationEvent::EstimateReady { order_id } => (
+                format!("Inspection results for order {order_id}"),
+                format!(
+                    "The inspection in order {order_id} is done and your car needs a repair. \
+                     The order has been changed to a repair."
+                ),
+            ),
+            NotificationEvent::ReportIssued {
+                report_id,
+                order_id,
+                cost,
+            } => (
+                format!("Report {report_id} issued"),
+                format!(
+                    "Report {report_id} for order {order_id} has been issued, the total cost is {}.",
+                    format_money(*cost)
+                ),
+            ),
+            NotificationEvent::ReportCorrected {
+                report_id,
+                superseded_report_id,
+                order_id,
+                cost,
+            } => (
+                format!("Report {superseded_report_id} corrected"),
+                format!(
+                    "Report {superseded_report_id} for order {order_id} has been replaced by \
+                     report {report_id}, the total cost is now {}.",
+                    format_money(*cost)
+                ),
+            ),
+            NotificationEvent::PaymentReceived { report_id, amount } => (
+                format!("Payment for report {report_id} received"),
+                format!(
+                    "We have received your payment of {} for report {report_id}. Thank you!",
+                    format_money(*amount)
+                ),
+            ),
+            NotificationEvent::MessageReceived { order_id } => (
+                format!("New message about order {order_id}"),
+                format!("The shop has sent you a message about your order {order_id}."),
+            ),
+            NotificationEvent::MaintenanceDue {
+                name,
+                car,
+                due_on,
+                due_mileage,
+            } => {
+                let when = match (due_on, due_mileage) {
+                    (Some(date), Some(mileage)) => {
+                        format!(
+                            " on {} or at {mileage} km, whichever comes first",
+                            date.format("%Y-%m-%d")
+                        )
+                    }
+                    (Some(date), None) => format!(" on {}", date.format("%Y-%m-%d")),
+                    (None, Some(mileage)) => format!(" at {mileage} km"),
+                    (None, None) => String::new(),
+                };
+                (
+                    format!("{name} due soon"),
+                    format!("{name} of your {car} is due{when}. Register an order to book it."),
+                )
+            }
+        }
+    }
+}
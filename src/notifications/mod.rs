@@ -0,0 +1,51 @@
+mod event;
+mod sms;
+mod smtp;
+
+use async_trait::async_trait;
+
+use crate::NotifyError;
+
+use std::io::Write;
+use std::sync::Mutex;
+
+pub use event::NotificationEvent;
+pub use sms::{FileSmsGateway, SmsGateway, SmsNotifier};
+pub use smtp::SmtpNotifier;
+
+/// Delivers messages such as password reset tokens to users.
+///
+/// The backend does not know how messages reach users, so frontends plug in their own
+/// implementation for each channel with [`ShopBackend::set_notifier`](crate::ShopBackend::set_notifier)
+/// and [`ShopBackend::set_sms_notifier`](crate::ShopBackend::set_sms_notifier).
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, recipient: &str, subject: &str, body: &str) -> Result<(), NotifyError>;
+}
+
+/// Writes messages to `out` instead of sending them, for development and demos.
+///
+/// Messages contain secrets such as password reset tokens, so the backend never uses this
+/// notifier unless it is set explicitly.
+#[derive(Debug)]
+pub struct ConsoleNotifier<W> {
+    out: Mutex<W>,
+}
+
+impl<W: Write + Send> ConsoleNotifier<W> {
+    pub fn new(out: W) -> Self {
+        ConsoleNotifier {
+            out: Mutex::new(out),
+        }
+    }
+}
+
+#[async_trait]
+impl<W: Write + Send> Notifier for ConsoleNotifier<W> {
+    async fn notify(&self, recipient: &str, subject: &str, body: &str) -> Result<(), NotifyError> {
+        let mut out = self.out.lock().unwrap();
+        writeln!(out, "To: {recipient}\nSubject: {subject}\n\n{body}\n")
+            .and_then(|_| out.flush())
+            .map_err(|e| NotifyError(e.to_string()))
+    }
+}
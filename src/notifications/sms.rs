@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+use super::Notifier;
+use crate::NotifyError;
+
+use std::path::PathBuf;
+
+/// Provider-specific part of sending text messages
+#[async_trait]
+pub trait SmsGateway: Send + Sync {
+    async fn send_sms(&self, phone: &str, text: &str) -> Result<(), NotifyError>;
+}
+
+/// Appends text messages to a file instead of sending them, for development and demos
+#[derive(Clone, Debug)]
+pub struct FileSmsGateway {
+    path: PathBuf,
+}
+
+impl FileSmsGateway {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileSmsGateway { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl SmsGateway for FileSmsGateway {
+    async fn send_sms(&self, phone: &str, text: &str) -> Result<(), NotifyError> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| NotifyError(e.to_string()))?;
+        file.write_all(format!("{phone}: {text}\n").as_bytes())
+            .await
+            .map_err(|e| NotifyError(e.to_string()))
+    }
+}
+
+/// Sends notifications as text messages through any [`SmsGateway`]
+pub struct SmsNotifier {
+    gateway: Box<dyn SmsGateway>,
+}
+
+impl SmsNotifier {
+    pub fn new(gateway: impl SmsGateway + 'static) -> Self {
+        SmsNotifier {
+            gateway: Box::new(gateway),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SmsNotifier {
+    async fn notify(&self, recipient: &str, subject: &str, body: &str) -> Result<(), NotifyError> {
+        self.gateway
+            .send_sms(recipient, &format!("{subject}: {body}"))
+            .await
+    }
+}
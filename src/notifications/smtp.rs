@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use super::Notifier;
+use crate::NotifyError;
+
+use std::env;
+
+/// Sends email over plain SMTP, meant for a local relay or a fake SMTP server
+#[derive(Clone, Debug)]
+pub struct SmtpNotifier {
+    host: String,
+    port: u16,
+    from: String,
+}
+
+impl SmtpNotifier {
+    pub fn new(host: &str, port: u16, from: &str) -> Self {
+        SmtpNotifier {
+            host: host.to_owned(),
+            port,
+            from: from.to_owned(),
+        }
+    }
+
+    /// Reads SHOP_SMTP_HOST, SHOP_SMTP_PORT (25 by default) and SHOP_SMTP_FROM,
+    /// returns `None` if the host is not set
+    pub fn from_env() -> Option<Self> {
+        let host = env::var("SHOP_SMTP_HOST").ok()?;
+        let port = env::var("SHOP_SMTP_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(25);
+        let from = env::var("SHOP_SMTP_FROM").unwrap_or(String::from("shop@localhost"));
+        Some(SmtpNotifier::new(&host, port, &from))
+    }
+}
+
+struct SmtpConnection {
+    reader: BufReader<TcpStream>,
+}
+
+impl SmtpConnection {
+    /// Reads a possibly multiline reply and checks its code
+    async fn expect(&mut self, code: u16) -> Result<(), NotifyError> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).await.map_err(io_error)? == 0 {
+                return Err(NotifyError(String::from(
+                    "SMTP server closed the connection",
+                )));
+            }
+
+            let reply_code = line.get(..3).and_then(|c| c.parse::<u16>().ok());
+            if reply_code != Some(code) {
+                return Err(NotifyError(format!(
+                    "unexpected SMTP reply: {}",
+                    line.trim_end()
+                )));
+            }
+
+            // "250-" continues a multiline reply, "250 " ends it
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn command(&mut self, command: &str, code: u16) -> Result<(), NotifyError> {
+        self.reader
+            .get_mut()
+            .write_all(format!("{command}\r\n").as_bytes())
+            .await
+            .map_err(io_error)?;
+        self.expect(code).await
+    }
+}
+
+fn io_error(e: std::io::Error) -> NotifyError {
+    NotifyError(format!("SMTP connection error: {e}"))
+}
+
+/// Normalizes line endings to CRLF and escapes lines starting with a dot
+fn dot_stuff(body: &str) -> String {
+    body.lines()
+        .map(|line| {
+            if line.starts_with('.') {
+                format!(".{line}\r\n")
+            } else {
+                format!("{line}\r\n")
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn notify(&self, recipient: &str, subject: &str, body: &str) -> Result<(), NotifyError> {
+        if [recipient, subject, &self.from]
+            .iter()
+            .any(|field| field.contains(['\r', '\n']))
+        {
+            return Err(NotifyError(String::from("line break in email header")));
+        }
+
+        let stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(io_error)?;
+        let mut smtp = SmtpConnection {
+            reader: BufReader::new(stream),
+        };
+
+        smtp.expect(220).await?;
+        smtp.command("EHLO localhost", 250).await?;
+        smtp.command(&format!("MAIL FROM:<{}>", self.from), 250)
+            .await?;
+        smtp.command(&format!("RCPT TO:<{recipient}>"), 250).await?;
+        smtp.command("DATA", 354).await?;
+
+        let message = format!(
+            "From: <{}>\r\nTo: <{recipient}>\r\nSubject: {subject}\r\nDate: {}\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\r\n{}.",
+            self.from,
+            Utc::now().to_rfc2822(),
+            dot_stuff(body)
+        );
+        smtp.command(&message, 250).await?;
+        smtp.command("QUIT", 221).await
+    }
+}
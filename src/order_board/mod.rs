@@ -0,0 +1,342 @@
+//! Live view of all orders for a screen at the reception desk
+//!
+//! The board keeps the current state of every order and numbers each change, so that
+//! a subscriber that reconnects with the ID of the last event it saw only receives
+//! what it missed. Subscribers that are too far behind get a new snapshot instead.
+
+mod server;
+
+use crate::{Order, Service, ShopEvent};
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+use tokio::sync::broadcast;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Number of changes kept for subscribers that reconnect
+const HISTORY_CAPACITY: usize = 1000;
+/// Number of changes queued for a live subscriber before it is sent a snapshot instead
+const SUBSCRIBER_CAPACITY: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderChange {
+    Created,
+    Updated,
+    Closed,
+    /// Moved to a location the board does not show
+    Removed,
+}
+
+/// Orders are serialized without their client, as the board is shown to anyone
+/// at the reception desk
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BoardUpdate {
+    /// All orders, replacing whatever the subscriber knew before
+    Snapshot {
+        #[serde(serialize_with = "serialize_board_orders")]
+        orders: Vec<Order>,
+    },
+    Order {
+        change: OrderChange,
+        #[serde(serialize_with = "serialize_board_order")]
+        order: Order,
+    },
+}
+
+/// What the board shows of an order
+#[derive(Serialize)]
+struct BoardOrder {
+    id: u32,
+    location_id: u32,
+    service: Service,
+    finished: bool,
+    version: u32,
+    created_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+    mechanic_id: Option<u32>,
+    converted_to_repair: bool,
+}
+
+impl From<&Order> for BoardOrder {
+    fn from(order: &Order) -> Self {
+        BoardOrder {
+            id: order.id(),
+            location_id: order.location_id(),
+            service: order.service(),
+            finished: order.finished(),
+            version: order.version(),
+            created_at: order.created_at(),
+            finished_at: order.finished_at(),
+            mechanic_id: order.mechanic_id(),
+            converted_to_repair: order.converted_to_repair(),
+        }
+    }
+}
+
+fn serialize_board_order<S: Serializer>(order: &Order, serializer: S) -> Result<S::Ok, S::Error> {
+    BoardOrder::from(order).serialize(serializer)
+}
+
+fn serialize_board_orders<S: Serializer>(
+    orders: &[Order],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(orders.iter().map(BoardOrder::from))
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BoardEvent {
+    id: u64,
+    #[serde(flatten)]
+    update: BoardUpdate,
+}
+
+impl BoardEvent {
+    /// Increases with every change, a snapshot has the ID of the last change it includes
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn update(&self) -> &BoardUpdate {
+        &self.update
+    }
+}
+
+struct BoardState {
+    /// Orders at every location, so that orders moved to the board's location can be shown
+    orders: BTreeMap<u32, Order>,
+    /// Only orders at this location are shown, orders at all locations if `None`
+    location_id: Option<u32>,
+    history: VecDeque<BoardEvent>,
+    last_id: u64,
+}
+
+impl BoardState {
+    fn shows(&self, order: &Order) -> bool {
+        self.location_id.is_none_or(|id| id == order.location_id())
+    }
+
+    fn shown_orders(&self) -> Vec<Order> {
+        self.orders
+            .values()
+            .filter(|order| self.shows(order))
+            .copied()
+            .collect()
+    }
+
+    fn snapshot(&self) -> BoardEvent {
+        BoardEvent {
+            id: self.last_id,
+            update: BoardUpdate::Snapshot {
+                orders: self.shown_orders(),
+            },
+        }
+    }
+
+    /// Changes after `last_event_id`, or `None` if some of them are no longer kept
+    fn changes_since(&self, last_event_id: u64) -> Option<VecDeque<BoardEvent>> {
+        if last_event_id > self.last_id {
+            return None;
+        }
+
+        let oldest = self.history.front().map_or(self.last_id + 1, |e| e.id);
+        if last_event_id + 1 < oldest {
+            return None;
+        }
+
+        Some(
+            self.history
+                .iter()
+                .filter(|e| e.id > last_event_id)
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+struct Inner {
+    state: Mutex<BoardState>,
+    sender: broadcast::Sender<BoardEvent>,
+}
+
+/// Current state of all orders, or of the orders at one location, kept up to date from
+/// [`ShopEvent`]s.
+/// Created with [`ShopBackend::order_board`](crate::ShopBackend::order_board), cheap to clone.
+#[derive(Clone)]
+pub struct OrderBoard {
+    inner: Arc<Inner>,
+}
+
+impl OrderBoard {
+    /// Board of `orders` showing only the ones at `location_id`, or all if it is `None`
+    pub(crate) fn new(orders: Vec<Order>, location_id: Option<u32>) -> Self {
+        OrderBoard {
+            inner: Arc::new(Inner {
+                state: Mutex::new(BoardState {
+                    orders: orders
+                        .into_iter()
+                        .map(|order| (order.id(), order))
+                        .collect(),
+                    location_id,
+                    history: VecDeque::new(),
+                    last_id: 0,
+                }),
+                sender: broadcast::channel(SUBSCRIBER_CAPACITY).0,
+            }),
+        }
+    }
+
+    /// Shown orders in the order they were registered
+    pub fn orders(&self) -> Vec<Order> {
+        self.inner.state.lock().unwrap().shown_orders()
+    }
+
+    /// Starts receiving changes. If `last_event_id` is the ID of an event received earlier,
+    /// only the changes after it are sent, otherwise a snapshot of all orders comes first.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> BoardSubscription {
+        let state = self.inner.state.lock().unwrap();
+        // Subscribing while holding the lock, so that no change falls between the backlog and the receiver
+        let receiver = self.inner.sender.subscribe();
+        let backlog = last_event_id
+            .and_then(|id| state.changes_since(id))
+            .unwrap_or_else(|| VecDeque::from([state.snapshot()]));
+
+        BoardSubscription {
+            board: self.clone(),
+            backlog,
+            receiver,
+            last_id: state.last_id,
+        }
+    }
+
+    pub(crate) fn apply(&self, event: &ShopEvent) {
+        let mut state = self.inner.state.lock().unwrap();
+        let (change, order) = match *event {
+            ShopEvent::OrderRegistered {
+                order_id,
+                client_id,
+                location_id,
+                service,
+            } => (
+                OrderChange::Created,
+                Order::registered(order_id, client_id, location_id, service, None),
+            ),
+            ShopEvent::ServiceChanged {
+                order_id,
+                service,
+                version,
+                ..
+            } => match state.orders.get(&order_id) {
+                Some(order) => (
+                    OrderChange::Updated,
+                    match service {
+                        Service::Repair => order.into_repair(),
+                        Service::Inspection => *order,
+                    }
+                    .at_version(version),
+                ),
+                None => return,
+            },
+            ShopEvent::OrderClosed {
+                order_id,
+                mechanic_id,
+                version,
+                ..
+            } => match state.orders.get(&order_id) {
+                Some(order) => (
+                    OrderChange::Closed,
+                    order.close(mechanic_id, None).at_version(version),
+                ),
+                None => return,
+            },
+            ShopEvent::OrderTransferred {
+                order_id,
+                location_id,
+                version,
+                ..
+            } => match state.orders.get(&order_id) {
+                Some(order) => (
+                    OrderChange::Updated,
+                    order.move_to(location_id).at_version(version),
+                ),
+                None => return,
+            },
+            _ => return,
+        };
+
+        let was_shown = state
+            .orders
+            .get(&order.id())
+            .is_some_and(|order| state.shows(order));
+        let change = match (was_shown, state.shows(&order)) {
+            (_, true) => change,
+            (true, false) => OrderChange::Removed,
+            (false, false) => {
+                state.orders.insert(order.id(), order);
+                return;
+            }
+        };
+
+        state.last_id += 1;
+        let event = BoardEvent {
+            id: state.last_id,
+            update: BoardUpdate::Order { change, order },
+        };
+        state.orders.insert(order.id(), order);
+        if state.history.len() == HISTORY_CAPACITY {
+            state.history.pop_front();
+        }
+        state.history.push_back(event.clone());
+        let _ = self.inner.sender.send(event);
+    }
+
+    /// Replaces all orders after changes were missed, everyone gets a new snapshot
+    pub(crate) fn reset(&self, orders: Vec<Order>) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.orders = orders
+            .into_iter()
+            .map(|order| (order.id(), order))
+            .collect();
+        state.history.clear();
+        state.last_id += 1;
+        let _ = self.inner.sender.send(state.snapshot());
+    }
+}
+
+/// Stream of [`BoardEvent`]s for one subscriber
+pub struct BoardSubscription {
+    board: OrderBoard,
+    backlog: VecDeque<BoardEvent>,
+    receiver: broadcast::Receiver<BoardEvent>,
+    last_id: u64,
+}
+
+impl BoardSubscription {
+    /// Waits for the next event
+    pub async fn next(&mut self) -> BoardEvent {
+        if let Some(event) = self.backlog.pop_front() {
+            return event;
+        }
+
+        loop {
+            match self.receiver.recv().await {
+                // Already part of the backlog
+                Ok(event) if event.id <= self.last_id => continue,
+                Ok(event) => return event,
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let state = self.board.inner.state.lock().unwrap();
+                    self.receiver = self.receiver.resubscribe();
+                    self.last_id = state.last_id;
+                    return state.snapshot();
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    unreachable!("the subscription keeps the sender alive")
+                }
+            }
+        }
+    }
+}
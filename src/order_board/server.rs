@@ -0,0 +1,190 @@
+use super::{BoardEvent, BoardUpdate, OrderBoard};
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+const BOARD_PAGE: &str = include_str!("board.html");
+/// Comment sent when nothing happened for a while, so that proxies keep the stream open
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// How long browsers wait before reconnecting a dropped stream
+const RECONNECT_DELAY_MILLISECONDS: u32 = 2000;
+
+impl OrderBoard {
+    /// Serves the board page at `/` and a Server-Sent Events stream of [`BoardEvent`]s
+    /// at `/events`. The stream resumes from the `Last-Event-ID` header, or from the
+    /// `last_event_id` query parameter for clients that cannot set headers.
+    ///
+    /// Every request has to carry `token`, either as `Authorization: Bearer <token>` or as
+    /// the URL-encoded `token` query parameter, which the board page passes on to the stream,
+    /// so the board is opened at `/?token=<token>`. Use a long random token,
+    /// and bind `listener` to a loopback address unless the board is behind a proxy
+    /// adding TLS. Has to be run inside a Tokio runtime.
+    pub async fn serve(self, listener: TcpListener, token: &str) -> io::Result<()> {
+        if token.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the order board token is empty",
+            ));
+        }
+
+        let token: Arc<str> = token.into();
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let board = self.clone();
+            let token = token.clone();
+            tokio::spawn(async move {
+                // Errors only mean that the viewer went away
+                let _ = board.handle_connection(stream, &token).await;
+            });
+        }
+    }
+
+    async fn handle_connection(&self, stream: TcpStream, token: &str) -> io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await?;
+
+        let mut last_event_id = None;
+        let mut bearer = None;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 {
+                break;
+            }
+            let Some((name, value)) = line.trim_end().split_once(':') else {
+                break;
+            };
+            if name.eq_ignore_ascii_case("last-event-id") {
+                last_event_id = value.trim().parse().ok();
+            } else if name.eq_ignore_ascii_case("authorization") {
+                bearer = value.trim().strip_prefix("Bearer ").map(str::to_owned);
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let target = parts.next().unwrap_or_default();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let last_event_id = last_event_id
+            .or_else(|| query_parameter(query, "last_event_id").and_then(|id| id.parse().ok()));
+        let query_token =
+            query_parameter(query, "token").and_then(|given| urlencoding::decode(given).ok());
+        let authorized = bearer
+            .as_deref()
+            .or(query_token.as_deref())
+            .is_some_and(|given| same_token(given, token));
+
+        let mut stream = reader.into_inner();
+        match (method, path) {
+            (_, _) if !authorized => {
+                respond(
+                    &mut stream,
+                    "401 Unauthorized",
+                    "text/plain",
+                    "unauthorized",
+                )
+                .await
+            }
+            ("GET", "/") => {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "text/html; charset=utf-8",
+                    BOARD_PAGE,
+                )
+                .await
+            }
+            ("GET", "/events") => self.stream_events(&mut stream, last_event_id).await,
+            ("GET", _) => respond(&mut stream, "404 Not Found", "text/plain", "not found").await,
+            _ => {
+                respond(
+                    &mut stream,
+                    "405 Method Not Allowed",
+                    "text/plain",
+                    "method not allowed",
+                )
+                .await
+            }
+        }
+    }
+
+    async fn stream_events(
+        &self,
+        stream: &mut TcpStream,
+        last_event_id: Option<u64>,
+    ) -> io::Result<()> {
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\n\
+                     Content-Type: text/event-stream\r\n\
+                     Cache-Control: no-cache\r\n\
+                     Connection: keep-alive\r\n\r\n\
+                     retry: {RECONNECT_DELAY_MILLISECONDS}\n\n"
+                )
+                .as_bytes(),
+            )
+            .await?;
+
+        let mut subscription = self.subscribe(last_event_id);
+        loop {
+            match tokio::time::timeout(KEEP_ALIVE_INTERVAL, subscription.next()).await {
+                Ok(event) => stream.write_all(sse_message(&event).as_bytes()).await?,
+                Err(_) => stream.write_all(b": keep-alive\n\n").await?,
+            }
+        }
+    }
+}
+
+/// Value of the parameter `name` in `query`, still URL-encoded
+fn query_parameter<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query.split('&').find_map(|pair| {
+        pair.strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+    })
+}
+
+/// Compares tokens in time independent of where they differ
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn sse_message(event: &BoardEvent) -> String {
+    let name = match event.update {
+        BoardUpdate::Snapshot { .. } => "snapshot",
+        BoardUpdate::Order { .. } => "order",
+    };
+    let data = serde_json::to_string(event).expect("board events are always serializable");
+    format!("id: {}\nevent: {name}\ndata: {data}\n\n", event.id)
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\n\
+                 Content-Type: {content_type}\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n\
+                 {body}",
+                body.len()
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.shutdown().await
+}
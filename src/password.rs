@@ -0,0 +1,49 @@
+use crate::PasswordPolicyError;
+
+/// Rules a new password has to follow
+#[derive(Clone, Debug)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn validate(&self, password: &str) -> Result<(), PasswordPolicyError> {
+        if password.chars().count() < self.min_length {
+            return Err(PasswordPolicyError::TooShort(self.min_length));
+        }
+
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            return Err(PasswordPolicyError::MissingUppercase);
+        }
+
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            return Err(PasswordPolicyError::MissingLowercase);
+        }
+
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err(PasswordPolicyError::MissingDigit);
+        }
+
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            return Err(PasswordPolicyError::MissingSymbol);
+        }
+
+        Ok(())
+    }
+}
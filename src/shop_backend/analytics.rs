@@ -0,0 +1,561 @@
+use crate::analytics::{
+    AnalyticsFilter, MechanicRevenue, OperationsSummary, Period, PeriodRevenue, Receivable,
+    ServiceRevenue,
+};
+use crate::db_entities::{employee, order, payment, report};
+use crate::{DbError, Service, ShopBackend, UserType};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use function_name::named;
+use sea_orm::sea_query::{
+    Alias, Cond, Expr, Func, IntoCondition, JoinType, Order, Query, SelectStatement, SimpleExpr,
+};
+use sea_orm::{
+    ActiveEnum, ColumnTrait, ConnectionTrait, DbBackend, DbErr, FromQueryResult, Statement,
+};
+
+#[derive(FromQueryResult)]
+struct PeriodRow {
+    period: String,
+    reports: i64,
+    revenue: i64,
+}
+
+#[derive(FromQueryResult)]
+struct ServiceRow {
+    service: String,
+    reports: i64,
+    revenue: i64,
+}
+
+#[derive(FromQueryResult)]
+struct MechanicRow {
+    mechanic_id: i32,
+    name: String,
+    reports: i64,
+    revenue: i64,
+}
+
+#[derive(FromQueryResult)]
+struct ReceivableRow {
+    report_id: i32,
+    order_id: i32,
+    client_id: i32,
+    location_id: i32,
+    cost: i32,
+    paid: i64,
+    issued_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromQueryResult)]
+struct ReportTotals {
+    reports: i64,
+    revenue: i64,
+    average_ticket: Option<f64>,
+}
+
+#[derive(FromQueryResult)]
+struct TurnaroundTotals {
+    finished_orders: i64,
+    average_turnaround_hours: Option<f64>,
+}
+
+#[derive(FromQueryResult)]
+struct ConversionTotals {
+    inspections: i64,
+    converted_to_repair: i64,
+}
+
+/// SQL `template` of `backend` with `$1`, `$2`, ... standing for `exprs`
+fn custom(backend: DbBackend, template: &str, exprs: &[SimpleExpr]) -> SimpleExpr {
+    if backend == DbBackend::Postgres {
+        return Expr::cust_with_exprs(template, exprs.to_vec());
+    }
+
+    // The other databases have unnumbered placeholders, which are filled in order
+    let mut sql = String::new();
+    let mut values = Vec::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut number = String::new();
+        while c == '$' && chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+            number.push(chars.next().unwrap());
+        }
+        match number.parse::<usize>() {
+            Ok(n) => {
+                sql.push('?');
+                values.push(exprs[n - 1].clone());
+            }
+            Err(_) => sql.push(c),
+        }
+    }
+    Expr::cust_with_exprs(sql, values)
+}
+
+/// `expr` as a 64 bit integer, MySQL sums are decimals otherwise
+fn integer(backend: DbBackend, expr: SimpleExpr) -> SimpleExpr {
+    match backend {
+        DbBackend::MySql => custom(backend, "CAST($1 AS SIGNED)", &[expr]),
+        DbBackend::Postgres => custom(backend, "CAST($1 AS BIGINT)", &[expr]),
+        DbBackend::Sqlite => expr,
+    }
+}
+
+/// Average of `expr` as a floating point number, `NULL` without rows
+fn average(backend: DbBackend, expr: SimpleExpr) -> SimpleExpr {
+    match backend {
+        DbBackend::MySql => custom(backend, "CAST(AVG($1) AS DOUBLE)", &[expr]),
+        DbBackend::Postgres => custom(backend, "CAST(AVG($1) AS DOUBLE PRECISION)", &[expr]),
+        DbBackend::Sqlite => custom(backend, "AVG($1 * 1.0)", &[expr]),
+    }
+}
+
+/// Sum of `expr` that is 0 without rows
+fn total(backend: DbBackend, expr: SimpleExpr) -> SimpleExpr {
+    integer(
+        backend,
+        Func::coalesce([Expr::expr(expr).sum(), Expr::val(0).into()]).into(),
+    )
+}
+
+/// First day of the period containing the timestamp `at`, as `YYYY-MM-DD`
+fn period_start(backend: DbBackend, period: Period, at: SimpleExpr) -> SimpleExpr {
+    let template = match (backend, period) {
+        (DbBackend::Sqlite, Period::Day) => "date($1)",
+        (DbBackend::Sqlite, Period::Week) => "date($1, 'weekday 0', '-6 days')",
+        (DbBackend::Sqlite, Period::Month) => "strftime('%Y-%m-01', $1)",
+        (DbBackend::MySql, Period::Day) => "DATE_FORMAT($1, '%Y-%m-%d')",
+        (DbBackend::MySql, Period::Week) => {
+            "DATE_FORMAT(DATE_SUB($1, INTERVAL WEEKDAY($1) DAY), '%Y-%m-%d')"
+        }
+        (DbBackend::MySql, Period::Month) => "DATE_FORMAT($1, '%Y-%m-01')",
+        (DbBackend::Postgres, Period::Day) => "to_char($1, 'YYYY-MM-DD')",
+        (DbBackend::Postgres, Period::Week) => "to_char(date_trunc('week', $1), 'YYYY-MM-DD')",
+        (DbBackend::Postgres, Period::Month) => "to_char($1, 'YYYY-MM-01')",
+    };
+    custom(backend, template, &[at])
+}
+
+/// Hours between the timestamps `from` and `until`
+fn hours_between(backend: DbBackend, from: SimpleExpr, until: SimpleExpr) -> SimpleExpr {
+    let template = match backend {
+        DbBackend::Sqlite => "(julianday($2) - julianday($1)) * 24.0",
+        DbBackend::MySql => "TIMESTAMPDIFF(SECOND, $1, $2) / 3600.0",
+        DbBackend::Postgres => "EXTRACT(EPOCH FROM ($2 - $1)) / 3600.0",
+    };
+    custom(backend, template, &[from, until])
+}
+
+/// Current reports issued in the range of `filter`
+fn issued_reports(filter: &AnalyticsFilter, location_id: Option<u32>) -> Cond {
+    let issued_at = || Expr::col((report::Entity, report::Column::CreatedAt));
+    let mut condition = Cond::all()
+        .add(Expr::col((report::Entity, report::Column::SupersededBy)).is_null())
+        .add(issued_at().gte(filter.from))
+        .add(issued_at().lt(filter.until));
+    if let Some(location_id) = location_id {
+        condition = condition
+            .add(Expr::col((report::Entity, report::Column::LocationId)).eq(location_id as i32));
+    }
+    condition
+}
+
+/// Orders whose timestamp `column` is in the range of `filter`
+fn orders_between(
+    column: order::Column,
+    filter: &AnalyticsFilter,
+    location_id: Option<u32>,
+) -> Cond {
+    let mut condition = Cond::all()
+        .add(Expr::col((order::Entity, column)).gte(filter.from))
+        .add(Expr::col((order::Entity, column)).lt(filter.until));
+    if let Some(location_id) = location_id {
+        condition = condition
+            .add(Expr::col((order::Entity, order::Column::LocationId)).eq(location_id as i32));
+    }
+    condition
+}
+
+impl ShopBackend {
+    /// Location the manager may see the numbers of, `None` for all locations
+    fn analytics_location(&self, location_id: Option<u32>) -> Result<Option<u32>, DbError> {
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        match (self.user.location_id(), location_id) {
+            (Some(own), Some(location_id)) if own != location_id => Err(DbError::Permission),
+            (own, location_id) => Ok(location_id.or(own)),
+        }
+    }
+
+    async fn analytics_rows<T: FromQueryResult>(
+        &self,
+        query: &SelectStatement,
+    ) -> Result<Vec<T>, DbErr> {
+        let statement: Statement = self.db().get_database_backend().build(query);
+        T::find_by_statement(statement).all(self.db()).await
+    }
+
+    async fn analytics_row<T: FromQueryResult>(&self, query: &SelectStatement) -> Result<T, DbErr> {
+        let statement: Statement = self.db().get_database_backend().build(query);
+        T::find_by_statement(statement)
+            .one(self.db())
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(String::from("analytics")))
+    }
+
+    /// Revenue of the reports issued in every day, week or month of the range, for managers.
+    /// Periods without reports are left out.
+    #[named]
+    pub async fn revenue_by_period(
+        &self,
+        filter: &AnalyticsFilter,
+        period: Period,
+    ) -> Result<Vec<PeriodRevenue>, DbError> {
+        self.login_check(function_name!())?;
+        let location_id = self.analytics_location(filter.location_id)?;
+        let backend = self.db().get_database_backend();
+
+        let period = period_start(
+            backend,
+            period,
+            Expr::col((report::Entity, report::Column::CreatedAt)).into(),
+        );
+        let query = Query::select()
+            .expr_as(period.clone(), Alias::new("period"))
+            .expr_as(
+                integer(
+                    backend,
+                    Expr::col((report::Entity, report::Column::Id)).count(),
+                ),
+                Alias::new("reports"),
+            )
+            .expr_as(
+                total(
+                    backend,
+                    Expr::col((report::Entity, report::Column::Cost)).into(),
+                ),
+                Alias::new("revenue"),
+            )
+            .from(report::Entity)
+            .cond_where(issued_reports(filter, location_id))
+            .add_group_by([period.clone()])
+            .order_by_expr(period, Order::Asc)
+            .to_owned();
+
+        self.analytics_rows::<PeriodRow>(&query)
+            .await?
+            .into_iter()
+            .map(|row| {
+                Ok(PeriodRevenue {
+                    period_start: NaiveDate::parse_from_str(&row.period, "%Y-%m-%d")
+                        .map_err(|e| DbErr::Type(e.to_string()))?,
+                    reports: row.reports as u64,
+                    revenue: row.revenue as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Revenue of the reports issued in the range for each kind of service, for managers
+    #[named]
+    pub async fn revenue_by_service(
+        &self,
+        filter: &AnalyticsFilter,
+    ) -> Result<Vec<ServiceRevenue>, DbError> {
+        self.login_check(function_name!())?;
+        let location_id = self.analytics_location(filter.location_id)?;
+        let backend = self.db().get_database_backend();
+
+        let service = custom(
+            backend,
+            match backend {
+                DbBackend::Postgres => "CAST($1 AS TEXT)",
+                _ => "$1",
+            },
+            &[Expr::col((order::Entity, order::Column::Service)).into()],
+        );
+        let query = Query::select()
+            .expr_as(service.clone(), Alias::new("service"))
+            .expr_as(
+                integer(
+                    backend,
+                    Expr::col((report::Entity, report::Column::Id)).count(),
+                ),
+                Alias::new("reports"),
+            )
+            .expr_as(
+                total(
+                    backend,
+                    Expr::col((report::Entity, report::Column::Cost)).into(),
+                ),
+                Alias::new("revenue"),
+            )
+            .from(report::Entity)
+            .join(
+                JoinType::InnerJoin,
+                order::Entity,
+                Expr::col((order::Entity, order::Column::Id))
+                    .equals((report::Entity, report::Column::OrderId)),
+            )
+            .cond_where(issued_reports(filter, location_id))
+            .add_group_by([service.clone()])
+            .order_by_expr(service, Order::Asc)
+            .to_owned();
+
+        self.analytics_rows::<ServiceRow>(&query)
+            .await?
+            .into_iter()
+            .map(|row| {
+                Ok(ServiceRevenue {
+                    service: Service::try_from_value(&row.service)?,
+                    reports: row.reports as u64,
+                    revenue: row.revenue as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Revenue of the reports issued in the range for each mechanic who finished the
+    /// orders, for managers. Orders finished before mechanics were recorded are left out.
+    #[named]
+    pub async fn revenue_by_mechanic(
+        &self,
+        filter: &AnalyticsFilter,
+    ) -> Result<Vec<MechanicRevenue>, DbError> {
+        self.login_check(function_name!())?;
+        let location_id = self.analytics_location(filter.location_id)?;
+        let backend = self.db().get_database_backend();
+
+        let query = Query::select()
+            .expr_as(
+                Expr::col((employee::Entity, employee::Column::Id)),
+                Alias::new("mechanic_id"),
+            )
+            .column((employee::Entity, employee::Column::Name))
+            .expr_as(
+                integer(
+                    backend,
+                    Expr::col((report::Entity, report::Column::Id)).count(),
+                ),
+                Alias::new("reports"),
+            )
+            .expr_as(
+                total(
+                    backend,
+                    Expr::col((report::Entity, report::Column::Cost)).into(),
+                ),
+                Alias::new("revenue"),
+            )
+            .from(report::Entity)
+            .join(
+                JoinType::InnerJoin,
+                order::Entity,
+                Expr::col((order::Entity, order::Column::Id))
+                    .equals((report::Entity, report::Column::OrderId)),
+            )
+            .join(
+                JoinType::InnerJoin,
+                employee::Entity,
+                Expr::col((employee::Entity, employee::Column::Id))
+                    .equals((order::Entity, order::Column::MechanicId)),
+            )
+            .cond_where(issued_reports(filter, location_id))
+            .group_by_col((employee::Entity, employee::Column::Id))
+            .group_by_col((employee::Entity, employee::Column::Name))
+            .order_by((employee::Entity, employee::Column::Id), Order::Asc)
+            .to_owned();
+
+        Ok(self
+            .analytics_rows::<MechanicRow>(&query)
+            .await?
+            .into_iter()
+            .map(|row| MechanicRevenue {
+                mechanic_id: row.mechanic_id as u32,
+                name: row.name,
+                reports: row.reports as u64,
+                revenue: row.revenue as u64,
+            })
+            .collect())
+    }
+
+    /// Current reports whose payments do not cover their cost, oldest first, for managers
+    #[named]
+    pub async fn outstanding_receivables(
+        &self,
+        location_id: Option<u32>,
+    ) -> Result<Vec<Receivable>, DbError> {
+        self.login_check(function_name!())?;
+        let location_id = self.analytics_location(location_id)?;
+        let backend = self.db().get_database_backend();
+
+        let paid = total(
+            backend,
+            Expr::col((payment::Entity, payment::Column::Amount)).into(),
+        );
+        let mut condition =
+            Cond::all().add(Expr::col((report::Entity, report::Column::SupersededBy)).is_null());
+        if let Some(location_id) = location_id {
+            condition = condition.add(
+                Expr::col((report::Entity, report::Column::LocationId)).eq(location_id as i32),
+            );
+        }
+        let query = Query::select()
+            .expr_as(
+                Expr::col((report::Entity, report::Column::Id)),
+                Alias::new("report_id"),
+            )
+            .columns([
+                (report::Entity, report::Column::OrderId),
+                (report::Entity, report::Column::ClientId),
+                (report::Entity, report::Column::LocationId),
+                (report::Entity, report::Column::Cost),
+            ])
+            .expr_as(paid.clone(), Alias::new("paid"))
+            .expr_as(
+                Expr::col((report::Entity, report::Column::CreatedAt)),
+                Alias::new("issued_at"),
+            )
+            .from(report::Entity)
+            .join(
+                JoinType::LeftJoin,
+                payment::Entity,
+                Expr::col((payment::Entity, payment::Column::ReportId))
+                    .equals((report::Entity, report::Column::Id)),
+            )
+            .cond_where(condition)
+            .group_by_columns([
+                (report::Entity, report::Column::Id),
+                (report::Entity, report::Column::OrderId),
+                (report::Entity, report::Column::ClientId),
+                (report::Entity, report::Column::LocationId),
+                (report::Entity, report::Column::Cost),
+                (report::Entity, report::Column::CreatedAt),
+            ])
+            .cond_having(Expr::col((report::Entity, report::Column::Cost)).gt(paid))
+            .order_by((report::Entity, report::Column::Id), Order::Asc)
+            .to_owned();
+
+        Ok(self
+            .analytics_rows::<ReceivableRow>(&query)
+            .await?
+            .into_iter()
+            .map(|row| Receivable {
+                report_id: row.report_id as u32,
+                order_id: row.order_id as u32,
+                client_id: row.client_id as u32,
+                location_id: row.location_id as u32,
+                cost: row.cost as u64,
+                paid: row.paid as u64,
+                issued_at: row.issued_at,
+            })
+            .collect())
+    }
+
+    /// Revenue, average ticket size, turnaround time and inspection conversion in the
+    /// range, for managers. Orders registered before their dates were recorded are left out.
+    #[named]
+    pub async fn operations_summary(
+        &self,
+        filter: &AnalyticsFilter,
+    ) -> Result<OperationsSummary, DbError> {
+        self.login_check(function_name!())?;
+        let location_id = self.analytics_location(filter.location_id)?;
+        let backend = self.db().get_database_backend();
+
+        let cost = || SimpleExpr::from(Expr::col((report::Entity, report::Column::Cost)));
+        let reports = self
+            .analytics_row::<ReportTotals>(
+                Query::select()
+                    .expr_as(
+                        integer(
+                            backend,
+                            Expr::col((report::Entity, report::Column::Id)).count(),
+                        ),
+                        Alias::new("reports"),
+                    )
+                    .expr_as(total(backend, cost()), Alias::new("revenue"))
+                    .expr_as(average(backend, cost()), Alias::new("average_ticket"))
+                    .from(report::Entity)
+                    .cond_where(issued_reports(filter, location_id)),
+            )
+            .await?;
+
+        let turnaround = self
+            .analytics_row::<TurnaroundTotals>(
+                Query::select()
+                    .expr_as(
+                        integer(
+                            backend,
+                            Expr::col((order::Entity, order::Column::Id)).count(),
+                        ),
+                        Alias::new("finished_orders"),
+                    )
+                    .expr_as(
+                        average(
+                            backend,
+                            hours_between(
+                                backend,
+                                Expr::col((order::Entity, order::Column::CreatedAt)).into(),
+                                Expr::col((order::Entity, order::Column::FinishedAt)).into(),
+                            ),
+                        ),
+                        Alias::new("average_turnaround_hours"),
+                    )
+                    .from(order::Entity)
+                    .cond_where(orders_between(
+                        order::Column::FinishedAt,
+                        filter,
+                        location_id,
+                    )),
+            )
+            .await?;
+
+        let converted = || {
+            Expr::col((order::Entity, order::Column::ConvertedToRepair))
+                .eq(true)
+                .into_condition()
+        };
+        let inspection = Cond::any()
+            .add(order::Column::Service.eq(Service::Inspection))
+            .add(converted());
+        let conversion = self
+            .analytics_row::<ConversionTotals>(
+                Query::select()
+                    .expr_as(
+                        total(backend, Expr::case(inspection, 1).finally(0).into()),
+                        Alias::new("inspections"),
+                    )
+                    .expr_as(
+                        total(backend, Expr::case(converted(), 1).finally(0).into()),
+                        Alias::new("converted_to_repair"),
+                    )
+                    .from(order::Entity)
+                    .cond_where(orders_between(
+                        order::Column::CreatedAt,
+                        filter,
+                        location_id,
+                    )),
+            )
+            .await?;
+
+        let outstanding_receivables = self
+            .outstanding_receivables(location_id)
+            .await?
+            .iter()
+            .map(Receivable::outstanding)
+            .sum();
+
+        Ok(OperationsSummary {
+            reports: reports.reports as u64,
+            revenue: reports.revenue as u64,
+            average_ticket: reports.average_ticket,
+            finished_orders: turnaround.finished_orders as u64,
+            average_turnaround_hours: turnaround.average_turnaround_hours,
+            inspections: conversion.inspections as u64,
+            converted_to_repair: conversion.converted_to_repair as u64,
+            outstanding_receivables,
+        })
+    }
+}
@@ -0,0 +1,287 @@
+use crate::attachments::{content_type, thumbnail};
+use crate::db_entities::{attachment, blob, order};
+use crate::storage::{Storage, Transaction};
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use sha2::{Digest, Sha256};
+
+/// Largest attachment in bytes
+pub const MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Key of the thumbnail of the content stored under `hash`
+fn thumbnail_key(hash: &str) -> String {
+    format!("{hash}-thumbnail")
+}
+
+/// Locks the row of the content stored under `hash` until the transaction ends, creating it
+/// if the content is new. Returns `None` for new content, which still has to be stored.
+async fn lock_blob(db: &impl ConnectionTrait, hash: &str) -> Result<Option<blob::Model>, DbErr> {
+    // Writing first makes SQLite take its write lock before the row is read
+    let inserted = blob::Entity::insert(blob::ActiveModel {
+        hash: Set(hash.to_owned()),
+        has_thumbnail: Set(false),
+    })
+    .on_conflict(
+        OnConflict::column(blob::Column::Hash)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    let blob = blob::Entity::find_by_id(hash)
+        .lock_exclusive()
+        .one(db)
+        .await?;
+    Ok(blob.filter(|_| inserted == 0))
+}
+
+/// Removes the row of the content stored under `hash` once no attachment uses it. Runs in the
+/// transaction that removed attachments, the hash stays locked until it commits. Returns
+/// whether the content is unused, it is deleted with [`ShopBackend::delete_blob_content`]
+/// after the commit so that a rolled back transaction does not lose it.
+pub(super) async fn release_blob(db: &impl ConnectionTrait, hash: &str) -> Result<bool, DbErr> {
+    lock_blob(db, hash).await?;
+    let still_used = attachment::Entity::find()
+        .filter(attachment::Column::Hash.eq(hash))
+        .count(db)
+        .await?
+        > 0;
+    if !still_used {
+        blob::Entity::delete_by_id(hash).exec(db).await?;
+    }
+    Ok(!still_used)
+}
+
+impl ShopBackend {
+    /// Attaches a photo or document to an order, for the order's client and employees of its
+    /// location. Attachments of clients are always visible to them, employees choose.
+    ///
+    /// Only JPEG, PNG and PDF files of at most [`MAX_ATTACHMENT_SIZE`] bytes are accepted.
+    /// Content already attached to any order is not stored again.
+    #[named]
+    pub async fn add_attachment(
+        &self,
+        order_id: u32,
+        file_name: &str,
+        content: &[u8],
+        visibility: Visibility,
+    ) -> Result<Attachment, AttachmentError> {
+        self.login_check(function_name!())?;
+        self.attachment_order_check(order_id).await?;
+
+        let file_name = file_name.trim();
+        if file_name.is_empty() {
+            return Err(AttachmentError::EmptyFileName);
+        }
+        if content.is_empty() {
+            return Err(AttachmentError::Empty);
+        }
+        let size = content.len() as u64;
+        if size > MAX_ATTACHMENT_SIZE {
+            return Err(AttachmentError::TooLarge {
+                size,
+                max_size: MAX_ATTACHMENT_SIZE,
+            });
+        }
+        let Some(content_type) = content_type(content) else {
+            return Err(AttachmentError::UnsupportedType);
+        };
+        let (employee_id, visibility) = match self.user.user_type() {
+            UserType::Client => (None, Visibility::Client),
+            _ => (Some(self.user.id()), visibility),
+        };
+        let hash = hex::encode(Sha256::digest(content));
+
+        let txn = self.storage.begin().await?;
+        let has_thumbnail = match lock_blob(txn.connection(), &hash).await? {
+            Some(blob) => blob.has_thumbnail,
+            None => {
+                // Content goes first, a row never points to a missing blob
+                self.blob_store.put(&hash, content).await?;
+                let has_thumbnail = match thumbnail(content) {
+                    Some(thumbnail) => {
+                        self.blob_store
+                            .put(&thumbnail_key(&hash), &thumbnail)
+                            .await?;
+                        true
+                    }
+                    None => false,
+                };
+                blob::Entity::update_many()
+                    .col_expr(blob::Column::HasThumbnail, Expr::value(has_thumbnail))
+                    .filter(blob::Column::Hash.eq(&hash))
+                    .exec(txn.connection())
+                    .await?;
+                has_thumbnail
+            }
+        };
+
+        let attachment = attachment::ActiveModel {
+            order_id: Set(order_id as i32),
+            employee_id: Set(employee_id.map(|id| id as i32)),
+            file_name: Set(file_name.to_owned()),
+            content_type: Set(content_type.to_owned()),
+            size: Set(size as i64),
+            hash: Set(hash),
+            has_thumbnail: Set(has_thumbnail),
+            visibility: Set(visibility),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(txn.connection())
+        .await?;
+        txn.commit().await?;
+        Ok(attachment.into())
+    }
+
+    /// Attachments of an order, oldest first. Clients only get those visible to them.
+    #[named]
+    pub async fn get_attachments(&self, order_id: u32) -> Result<Vec<Attachment>, AttachmentError> {
+        self.login_check(function_name!())?;
+        self.attachment_order_check(order_id).await?;
+
+        let mut query = db_entities::prelude::Attachment::find()
+            .filter(attachment::Column::OrderId.eq(order_id as i32));
+        if matches!(self.user.user_type(), UserType::Client) {
+            query = query.filter(attachment::Column::Visibility.eq(Visibility::Client));
+        }
+        Ok(query
+            .order_by_asc(attachment::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    /// Content of an attachment, for whoever may see it
+    #[named]
+    pub async fn get_attachment_content(&self, id: u32) -> Result<Vec<u8>, AttachmentError> {
+        self.login_check(function_name!())?;
+        let attachment = self.visible_attachment(id).await?;
+
+        Ok(self.blob_store.get(&attachment.hash).await?)
+    }
+
+    /// PNG thumbnail of an image attachment, `None` for documents
+    #[named]
+    pub async fn get_attachment_thumbnail(
+        &self,
+        id: u32,
+    ) -> Result<Option<Vec<u8>>, AttachmentError> {
+        self.login_check(function_name!())?;
+        let attachment = self.visible_attachment(id).await?;
+        if !attachment.has_thumbnail {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            self.blob_store
+                .get(&thumbnail_key(&attachment.hash))
+                .await?,
+        ))
+    }
+
+    /// Shows an attachment to the client or hides it again, for employees of the order's location
+    #[named]
+    pub async fn set_attachment_visibility(
+        &self,
+        id: u32,
+        visibility: Visibility,
+    ) -> Result<(), AttachmentError> {
+        self.login_check(function_name!())?;
+        if matches!(self.user.user_type(), UserType::Client) {
+            return Err(AttachmentError::Permission);
+        }
+        self.visible_attachment(id).await?;
+
+        attachment::Entity::update_many()
+            .col_expr(attachment::Column::Visibility, Expr::value(visibility))
+            .filter(attachment::Column::Id.eq(id as i32))
+            .exec(self.db())
+            .await?;
+        Ok(())
+    }
+
+    /// Removes an attachment, for its uploader and employees of the order's location.
+    /// The content is deleted from the blob store once no attachment uses it.
+    #[named]
+    pub async fn delete_attachment(&self, id: u32) -> Result<(), AttachmentError> {
+        self.login_check(function_name!())?;
+        let attachment = self.visible_attachment(id).await?;
+        if matches!(self.user.user_type(), UserType::Client) && attachment.employee_id.is_some() {
+            return Err(AttachmentError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        attachment::Entity::delete_by_id(attachment.id)
+            .exec(txn.connection())
+            .await?;
+        let unused = release_blob(txn.connection(), &attachment.hash).await?;
+        txn.commit().await?;
+        if unused {
+            self.delete_blob_content::<AttachmentError>(&attachment.hash)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the content released by [`release_blob`] from the blob store, after the
+    /// transaction releasing it was committed. Content stored again in the meantime stays.
+    pub(super) async fn delete_blob_content<E: From<DbErr> + From<BlobError>>(
+        &self,
+        hash: &str,
+    ) -> Result<(), E> {
+        let txn = self.storage.begin().await?;
+        if lock_blob(txn.connection(), hash).await?.is_some() {
+            return Ok(());
+        }
+        self.blob_store.delete(hash).await?;
+        self.blob_store.delete(&thumbnail_key(hash)).await?;
+        blob::Entity::delete_by_id(hash)
+            .exec(txn.connection())
+            .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Fails unless the order exists and the logged in user may see it
+    async fn attachment_order_check(&self, order_id: u32) -> Result<(), AttachmentError> {
+        let Some(order) = order::Entity::find_by_id(order_id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(AttachmentError::Order(order_id));
+        };
+        if !self.can_see_order(&order.into()) {
+            return Err(AttachmentError::Permission);
+        }
+        Ok(())
+    }
+
+    /// Returns the attachment if the logged in user may see it, clients only see those
+    /// visible to them
+    async fn visible_attachment(&self, id: u32) -> Result<attachment::Model, AttachmentError> {
+        let Some(attachment) = attachment::Entity::find_by_id(id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(AttachmentError::Attachment(id));
+        };
+        self.attachment_order_check(attachment.order_id as u32)
+            .await?;
+        if matches!(self.user.user_type(), UserType::Client)
+            && attachment.visibility == Visibility::Internal
+        {
+            return Err(AttachmentError::Attachment(id));
+        }
+        Ok(attachment)
+    }
+}
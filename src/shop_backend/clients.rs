@@ -1,43 +1,42 @@
+use super::attachments::release_blob;
 use super::*;
-use crate::{db_entities::client, UserType, *};
+use crate::storage::{ClientRecord, Storage};
+use crate::{
+    db_entities::{
+        attachment, client, maintenance_reminder, mileage_reading, notification,
+        notification_outbox, order, order_message, order_note, password_reset_token, trouble_code,
+    },
+    UserType, *,
+};
 
+use chrono::Utc;
 use function_name::named;
+use sea_orm::sea_query::Expr;
 use sea_orm::ColumnTrait;
 use sea_orm::QueryFilter;
-use sea_orm::{ActiveModelTrait, EntityTrait, ModelTrait, Set};
-use sea_orm_migration::prelude::*;
+use sea_orm::{ActiveModelTrait, EntityTrait, QuerySelect, Set, TransactionTrait};
 
-impl ShopBackend {
-    pub async fn client_login(
-        &mut self,
-        email: &str,
-        password_hash: &str,
-    ) -> Result<User, LoginError> {
+use std::collections::BTreeSet;
+
+impl<S: Storage> ShopBackend<S> {
+    /// Logs in a client with the plain text password, which is checked against the stored hash
+    pub async fn client_login(&mut self, email: &str, password: &str) -> Result<User, LoginError> {
         if !matches!(self.user.user_type(), UserType::NotLoggedIn) {
             return Err(LoginError::AlreadyLoggedIn);
         };
 
-        match db_entities::prelude::Client::find()
-            .filter(client::Column::Email.eq(email))
-            .one(&self.db)
-            .await
-        {
+        match self.storage.client_by_email(email).await {
             Ok(res) => match res {
                 Some(client) => {
                     if !EMAIL_REGEX.is_match(email) {
                         return Err(LoginError::EmailIncorrectFormat(email.to_owned()));
                     }
 
-                    if !HASH_REGEX.is_match(password_hash) {
-                        return Err(LoginError::PasswordNotHashed);
-                    }
-
-                    if client.password_hash != *password_hash {
+                    if !bcrypt::verify(password, &client.password_hash).unwrap_or(false) {
                         return Err(LoginError::ClientIncorrectPassword(email.to_string()));
                     }
 
-                    self.user = User::logged_in(client.id as u32, &client.name, UserType::Client);
-                    Ok(self.user.clone())
+                    Ok(self.log_in(User::logged_in(client.id, &client.name, UserType::Client)))
                 }
                 None => Err(LoginError::EmailNotRegistered(email.to_string())),
             },
@@ -45,6 +44,8 @@ impl ShopBackend {
         }
     }
 
+    /// Registers a client and logs them in. The password is given as a bcrypt hash.
+    /// Registration succeeds even if the verification email cannot be sent.
     pub async fn register_client(
         &mut self,
         name: &str,
@@ -59,24 +60,37 @@ impl ShopBackend {
             return Err(RegisterClientError::EmailIncorrectFormat(email.to_owned()));
         }
 
-        match db_entities::prelude::Client::find()
-            .filter(client::Column::Email.eq(email))
-            .one(&self.db)
-            .await?
-        {
+        if !HASH_REGEX.is_match(password_hash) {
+            return Err(RegisterClientError::PasswordNotHashed);
+        }
+
+        match self.storage.client_by_email(email).await? {
             Some(_) => Err(RegisterClientError::EmailAlreadyRegistered(
                 email.to_owned(),
             )),
             None => {
-                let client = client::ActiveModel {
-                    name: Set(name.to_owned()),
-                    email: Set(email.to_owned()),
-                    password_hash: Set(password_hash.to_string()),
-                    ..Default::default()
+                let client = ClientRecord {
+                    id: 0,
+                    name: name.to_owned(),
+                    email: email.to_owned(),
+                    password_hash: password_hash.to_string(),
+                    car: None,
+                    phone: None,
+                    address: None,
+                    communication_preferences: None,
+                    email_verified: false,
+                    deleted: false,
+                    verification_sent_at: None,
                 };
-                let res = client.insert(&self.db).await?;
-                self.user = User::logged_in(res.id as u32, name, UserType::Client);
-                Ok(self.user.clone())
+                let mut res = self.storage.insert_client(client).await?;
+                // The account exists even if the email cannot be sent,
+                // the client asks for it again with resend_verification_email
+                if self.send_verification_email(res.id, email).await.is_ok() {
+                    res.verification_sent_at = Some(Utc::now());
+                    self.storage.update_client(&res).await?;
+                }
+                self.publish(ShopEvent::ClientRegistered { client_id: res.id });
+                Ok(self.log_in(User::logged_in(res.id, name, UserType::Client)))
             }
         }
     }
@@ -85,11 +99,8 @@ impl ShopBackend {
     pub async fn get_car(&self, client_id: u32) -> Result<Option<Car>, DbError> {
         self.login_check(function_name!())?;
 
-        match db_entities::prelude::Client::find_by_id(client_id as i32)
-            .one(&self.db)
-            .await?
-        {
-            Some(client) => Ok(client.car.clone()),
+        match self.storage.client(client_id).await? {
+            Some(client) => Ok(client.car),
             None => Err(DbError::Client(client_id)),
         }
     }
@@ -98,17 +109,7 @@ impl ShopBackend {
     pub async fn get_client_orders(&self) -> Result<Vec<crate::Order>, DbError> {
         self.login_check(function_name!())?;
         match self.user.user_type() {
-            UserType::Client => {
-                let client = db_entities::prelude::Client::find_by_id(self.user.id() as i32)
-                    .one(&self.db)
-                    .await?
-                    .unwrap();
-                let orders = client
-                    .find_related(db_entities::prelude::Order)
-                    .all(&self.db)
-                    .await?;
-                Ok(orders.into_iter().map(|m| m.into()).collect())
-            }
+            UserType::Client => Ok(self.storage.client_orders(self.user.id()).await?),
             _ => Err(DbError::Permission),
         }
     }
@@ -117,18 +118,235 @@ impl ShopBackend {
     pub async fn get_client_reports(&self) -> Result<Vec<Report>, DbError> {
         self.login_check(function_name!())?;
         match self.user.user_type() {
-            UserType::Client => {
-                let client = db_entities::prelude::Client::find_by_id(self.user.id() as i32)
-                    .one(&self.db)
-                    .await?
-                    .unwrap();
-                let reps = client
-                    .find_related(db_entities::prelude::Report)
-                    .all(&self.db)
-                    .await?;
-                Ok(reps.into_iter().map(|m| m.into()).collect())
-            }
+            UserType::Client => Ok(self.storage.client_reports(self.user.id()).await?),
             _ => Err(DbError::Permission),
         }
     }
+
+    /// Whether the logged in user may view and edit the profile of `client_id`
+    fn can_manage_client(&self, client_id: u32) -> bool {
+        match self.user.user_type() {
+            UserType::Client => self.user.id() == client_id,
+            UserType::Technician | UserType::Manager => true,
+            _ => false,
+        }
+    }
+
+    #[named]
+    pub async fn get_client(&self, client_id: u32) -> Result<crate::Client, DbError> {
+        self.login_check(function_name!())?;
+        if !self.can_manage_client(client_id) {
+            return Err(DbError::Permission);
+        }
+
+        match self.storage.client(client_id).await? {
+            Some(client) if !client.deleted => Ok(client.into()),
+            _ => Err(DbError::Client(client_id)),
+        }
+    }
+
+    /// Replaces the contact details of a client. Changing the email address
+    /// marks it as unverified until the client verifies it again.
+    #[named]
+    pub async fn update_profile(
+        &mut self,
+        client_id: u32,
+        name: &str,
+        email: &str,
+        phone: Option<&str>,
+        address: Option<&str>,
+    ) -> Result<crate::Client, ProfileError> {
+        self.login_check(function_name!())?;
+        if !self.can_manage_client(client_id) {
+            return Err(ProfileError::Permission);
+        }
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ProfileError::EmptyName);
+        }
+
+        if !EMAIL_REGEX.is_match(email) {
+            return Err(ProfileError::EmailIncorrectFormat(email.to_owned()));
+        }
+
+        if let Some(phone) = phone {
+            if !PHONE_REGEX.is_match(phone) {
+                return Err(ProfileError::PhoneIncorrectFormat(phone.to_owned()));
+            }
+        }
+
+        let mut client = match self.storage.client(client_id).await? {
+            Some(client) if !client.deleted => client,
+            _ => return Err(ProfileError::Client(client_id)),
+        };
+
+        let email_changed = client.email != email;
+        client.name = name.to_owned();
+        client.phone = phone.map(str::to_owned);
+        client.address = address.map(str::to_owned);
+        if email_changed {
+            client.email = email.to_owned();
+            client.email_verified = false;
+            client.verification_sent_at = None;
+        }
+        // The unique email index decides between clients changing to the same address
+        if !self.storage.update_client(&client).await? {
+            return Err(ProfileError::EmailAlreadyRegistered(email.to_owned()));
+        }
+
+        if matches!(self.user.user_type(), UserType::Client) {
+            self.user = User::logged_in(client_id, name, UserType::Client);
+        }
+
+        // Like at registration the change is kept if the email cannot be sent,
+        // the client asks for it again with resend_verification_email
+        if email_changed && self.send_verification_email(client_id, email).await.is_ok() {
+            client.verification_sent_at = Some(Utc::now());
+            self.storage.update_client(&client).await?;
+        }
+
+        Ok(client.into())
+    }
+
+    #[named]
+    pub async fn set_communication_preferences(
+        &self,
+        client_id: u32,
+        preferences: CommunicationPreferences,
+    ) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !self.can_manage_client(client_id) {
+            return Err(DbError::Permission);
+        }
+
+        match self.storage.client(client_id).await? {
+            Some(mut client) if !client.deleted => {
+                client.communication_preferences = Some(preferences);
+                self.storage.update_client(&client).await?;
+                Ok(())
+            }
+            _ => Err(DbError::Client(client_id)),
+        }
+    }
+}
+
+impl ShopBackend {
+    /// Deletes a client account by anonymizing its personal data. Orders and reports
+    /// are kept for accounting, everything else linked to the client is deleted: notifications,
+    /// mileage readings, complaints, messages, notes, trouble codes and attachments.
+    /// Clients can delete their own account, which logs them out, managers can delete
+    /// any account.
+    #[named]
+    pub async fn delete_client_account(&mut self, client_id: u32) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        let own_account =
+            matches!(self.user.user_type(), UserType::Client) && self.user.id() == client_id;
+        if !own_account && !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        let client = match db_entities::prelude::Client::find_by_id(client_id as i32)
+            .one(self.db())
+            .await?
+        {
+            Some(client) if !client.deleted => client,
+            _ => return Err(DbError::Client(client_id)),
+        };
+
+        let txn = self.db().begin().await?;
+        db_entities::prelude::PasswordResetToken::delete_many()
+            .filter(password_reset_token::Column::ClientId.eq(client_id as i32))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::Notification::delete_many()
+            .filter(notification::Column::ClientId.eq(client_id as i32))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::NotificationOutbox::delete_many()
+            .filter(notification_outbox::Column::ClientId.eq(client_id as i32))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::MaintenanceReminder::delete_many()
+            .filter(maintenance_reminder::Column::ClientId.eq(client_id as i32))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::MileageReading::delete_many()
+            .filter(mileage_reading::Column::ClientId.eq(client_id as i32))
+            .exec(&txn)
+            .await?;
+
+        // Orders stay, what was written about them and the files attached to them go
+        let order_ids = db_entities::prelude::Order::find()
+            .select_only()
+            .column(order::Column::Id)
+            .filter(order::Column::ClientId.eq(client_id as i32))
+            .into_tuple::<i32>()
+            .all(&txn)
+            .await?;
+        db_entities::prelude::Order::update_many()
+            .col_expr(
+                order::Column::Complaint,
+                Expr::value(Option::<String>::None),
+            )
+            .col_expr(
+                order::Column::Version,
+                Expr::col(order::Column::Version).add(1),
+            )
+            .filter(order::Column::ClientId.eq(client_id as i32))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::OrderMessage::delete_many()
+            .filter(order_message::Column::OrderId.is_in(order_ids.clone()))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::OrderNote::delete_many()
+            .filter(order_note::Column::OrderId.is_in(order_ids.clone()))
+            .exec(&txn)
+            .await?;
+        db_entities::prelude::TroubleCode::delete_many()
+            .filter(trouble_code::Column::OrderId.is_in(order_ids.clone()))
+            .exec(&txn)
+            .await?;
+        let attachments = db_entities::prelude::Attachment::find()
+            .filter(attachment::Column::OrderId.is_in(order_ids))
+            .all(&txn)
+            .await?;
+        for attachment in &attachments {
+            attachment::Entity::delete_by_id(attachment.id)
+                .exec(&txn)
+                .await?;
+        }
+        let hashes = attachments
+            .into_iter()
+            .map(|attachment| attachment.hash)
+            .collect::<BTreeSet<_>>();
+        let mut unused = Vec::new();
+        for hash in hashes {
+            if release_blob(&txn, &hash).await? {
+                unused.push(hash);
+            }
+        }
+
+        let mut client: client::ActiveModel = client.into();
+        client.name = Set(String::from("Deleted client"));
+        client.email = Set(format!("deleted-{client_id}@deleted.invalid"));
+        client.password_hash = Set(String::new());
+        client.phone = Set(None);
+        client.address = Set(None);
+        client.communication_preferences = Set(None);
+        client.email_verified = Set(false);
+        client.deleted = Set(true);
+        client.update(&txn).await?;
+        txn.commit().await?;
+        for hash in unused {
+            self.delete_blob_content::<DbError>(&hash).await?;
+        }
+
+        if own_account {
+            self.user = User::not_logged_in();
+        }
+
+        Ok(())
+    }
 }
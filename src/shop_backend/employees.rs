@@ -1,46 +1,88 @@
-use super::HASH_REGEX;
+use super::settings::TWO_FACTOR_REQUIRED;
 
-use crate::db_entities::employee;
+use crate::storage::Storage;
 use crate::UserType;
 use crate::*;
 
 use sea_orm::EntityTrait;
 
-impl ShopBackend {
-    pub async fn employee_login(
-        &mut self,
-        id: u32,
-        password_hash: &str,
-    ) -> Result<User, LoginError> {
+/// Incorrect codes after which a pending login is abandoned and has to start over
+const MAX_SECOND_FACTOR_ATTEMPTS: u32 = 5;
+
+impl<S: Storage> ShopBackend<S> {
+    /// Logs in an employee with the plain text password, which is checked against the stored
+    /// hash. If the employee has two-factor authentication enabled this returns
+    /// [`LoginError::SecondFactorRequired`] and the login has to be finished with
+    /// [`ShopBackend::employee_login_second_factor`]. If two-factor authentication is required
+    /// but the employee has not enrolled, this returns [`LoginError::TwoFactorEnrollmentRequired`]
+    /// and the login is finished by [`ShopBackend::confirm_totp_enrollment`].
+    pub async fn employee_login(&mut self, id: u32, password: &str) -> Result<User, LoginError> {
         if !matches!(self.user.user_type(), UserType::NotLoggedIn) {
             return Err(LoginError::AlreadyLoggedIn);
         };
 
-        if !HASH_REGEX.is_match(password_hash) {
-            return Err(LoginError::PasswordNotHashed);
-        }
+        self.pending_employee = None;
+        self.second_factor_failures = 0;
 
-        match db_entities::prelude::Employee::find_by_id(id as i32)
-            .one(&self.db)
-            .await?
-        {
+        match self.storage.employee(id).await? {
             Some(employee) => {
-                if employee.password_hash != *password_hash {
+                if !bcrypt::verify(password, &employee.password_hash).unwrap_or(false) {
                     return Err(LoginError::EmployeeIncorrectPassword(id));
                 }
 
-                match employee.role {
-                    employee::Role::Technician => {
-                        self.user = User::logged_in(id, &employee.name, UserType::Technician)
-                    }
-                    employee::Role::Mechanic => {
-                        self.user = User::logged_in(id, &employee.name, UserType::Mechanic)
-                    }
+                if employee.totp_enabled {
+                    self.pending_employee = Some(id);
+                    return Err(LoginError::SecondFactorRequired(id));
+                }
+
+                if self.setting(TWO_FACTOR_REQUIRED).await?.unwrap_or(false) {
+                    self.pending_employee = Some(id);
+                    return Err(LoginError::TwoFactorEnrollmentRequired(id));
                 }
 
-                Ok(self.user.clone())
+                Ok(self.log_in(
+                    User::logged_in(id, &employee.name, employee.role.into())
+                        .at_location(employee.location_id),
+                ))
             }
             None => Err(LoginError::EmployeeNotRegistered(id)),
         }
     }
 }
+
+impl ShopBackend {
+    /// Finishes a login started by [`ShopBackend::employee_login`] with a TOTP or recovery code.
+    /// After a few incorrect codes the login is abandoned and has to start over with the password.
+    pub async fn employee_login_second_factor(&mut self, code: &str) -> Result<User, LoginError> {
+        let Some(id) = self.pending_employee else {
+            return Err(LoginError::NoPendingLogin);
+        };
+
+        let Some(employee) = db_entities::prelude::Employee::find_by_id(id as i32)
+            .one(self.db())
+            .await?
+        else {
+            self.pending_employee = None;
+            return Err(LoginError::EmployeeNotRegistered(id));
+        };
+
+        if !employee.totp_enabled {
+            return Err(LoginError::TwoFactorEnrollmentRequired(id));
+        }
+
+        if !self.check_second_factor(&employee, code).await? {
+            self.second_factor_failures += 1;
+            if self.second_factor_failures >= MAX_SECOND_FACTOR_ATTEMPTS {
+                self.pending_employee = None;
+                return Err(LoginError::TooManySecondFactorAttempts);
+            }
+            return Err(LoginError::InvalidSecondFactor);
+        }
+
+        self.pending_employee = None;
+        let location_id = employee.location_id.map(|id| id as u32);
+        Ok(self.log_in(
+            User::logged_in(id, &employee.name, employee.role.into()).at_location(location_id),
+        ))
+    }
+}
@@ -0,0 +1,96 @@
+use crate::db_entities::{attachment, mileage_reading, order_message, trouble_code};
+use crate::{UserType, *};
+
+use function_name::named;
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+
+impl ShopBackend {
+    /// Gathers all data about a client. Clients can export their own data,
+    /// managers can export it on behalf of any client, also after the account was deleted
+    /// to check what is left of it. Internal notes and attachments are not exported.
+    #[named]
+    pub async fn export_client_data(&self, client_id: u32) -> Result<ClientDataExport, DbError> {
+        self.login_check(function_name!())?;
+        let allowed = match self.user.user_type() {
+            UserType::Client => self.user.id() == client_id,
+            UserType::Manager => true,
+            _ => false,
+        };
+        if !allowed {
+            return Err(DbError::Permission);
+        }
+
+        let client = match db_entities::prelude::Client::find_by_id(client_id as i32)
+            .one(self.db())
+            .await?
+        {
+            Some(client) => client,
+            None => return Err(DbError::Client(client_id)),
+        };
+
+        let orders = client
+            .find_related(db_entities::prelude::Order)
+            .all(self.db())
+            .await?;
+        let reports = client
+            .find_related(db_entities::prelude::Report)
+            .all(self.db())
+            .await?;
+        let payments = client
+            .find_related(db_entities::prelude::Payment)
+            .all(self.db())
+            .await?;
+        let notifications = client
+            .find_related(db_entities::prelude::Notification)
+            .all(self.db())
+            .await?;
+
+        let order_ids = orders.iter().map(|order| order.id).collect::<Vec<_>>();
+        let complaints = orders
+            .iter()
+            .filter_map(|order| {
+                let complaint = order.complaint.as_deref()?;
+                Some(OrderComplaint::new(order.id as u32, complaint))
+            })
+            .collect();
+        let messages = db_entities::prelude::OrderMessage::find()
+            .filter(order_message::Column::OrderId.is_in(order_ids.clone()))
+            .order_by_asc(order_message::Column::Id)
+            .all(self.db())
+            .await?;
+        let mileage_readings = db_entities::prelude::MileageReading::find()
+            .filter(mileage_reading::Column::ClientId.eq(client_id as i32))
+            .order_by_asc(mileage_reading::Column::Id)
+            .all(self.db())
+            .await?;
+        let trouble_codes = db_entities::prelude::TroubleCode::find()
+            .filter(trouble_code::Column::OrderId.is_in(order_ids.clone()))
+            .order_by_asc(trouble_code::Column::Id)
+            .all(self.db())
+            .await?;
+        let mut attachments = Vec::new();
+        for attachment in db_entities::prelude::Attachment::find()
+            .filter(attachment::Column::OrderId.is_in(order_ids))
+            .filter(attachment::Column::Visibility.eq(Visibility::Client))
+            .order_by_asc(attachment::Column::Id)
+            .all(self.db())
+            .await?
+        {
+            let content = self.blob_store.get(&attachment.hash).await?;
+            attachments.push((attachment.into(), content));
+        }
+
+        Ok(ClientDataExport::new(
+            client.into(),
+            orders.into_iter().map(|m| m.into()).collect(),
+            reports.into_iter().map(|m| m.into()).collect(),
+            payments.into_iter().map(|m| m.into()).collect(),
+            notifications.into_iter().map(|m| m.into()).collect(),
+            complaints,
+            messages.into_iter().map(|m| m.into()).collect(),
+            mileage_readings.into_iter().map(|m| m.into()).collect(),
+            trouble_codes.into_iter().map(|m| m.into()).collect(),
+            attachments,
+        ))
+    }
+}
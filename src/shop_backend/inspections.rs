@@ -0,0 +1,300 @@
+use super::notes::checked_text;
+use crate::db_entities::{inspection_item, inspection_result, inspection_template, order};
+use crate::storage::{Storage, Transaction};
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+/// Item of a template being added with [`ShopBackend::add_inspection_template`]
+#[derive(Clone, Debug, Default)]
+pub struct NewInspectionItem {
+    pub section: String,
+    pub name: String,
+    /// Unit the item is measured in, e.g. mm, `None` for items that are only looked at
+    pub unit: Option<String>,
+}
+
+impl ShopBackend {
+    /// Adds a checklist for inspections, for managers. Items are checked in the given order.
+    #[named]
+    pub async fn add_inspection_template(
+        &self,
+        name: &str,
+        items: &[NewInspectionItem],
+    ) -> Result<InspectionTemplate, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        let name = name.trim();
+        let incomplete = items
+            .iter()
+            .any(|item| item.section.trim().is_empty() || item.name.trim().is_empty());
+        if name.is_empty() || items.is_empty() || incomplete {
+            return Err(DbError::EmptyInspectionTemplate);
+        }
+
+        let txn = self.storage.begin().await?;
+        let template = inspection_template::ActiveModel {
+            name: Set(name.to_owned()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(txn.connection())
+        .await?;
+        let mut inserted = Vec::with_capacity(items.len());
+        for (position, item) in items.iter().enumerate() {
+            let unit = item
+                .unit
+                .as_deref()
+                .map(str::trim)
+                .filter(|unit| !unit.is_empty());
+            let item = inspection_item::ActiveModel {
+                template_id: Set(template.id),
+                position: Set(position as i32),
+                section: Set(item.section.trim().to_owned()),
+                name: Set(item.name.trim().to_owned()),
+                unit: Set(unit.map(str::to_owned)),
+                ..Default::default()
+            }
+            .insert(txn.connection())
+            .await?;
+            inserted.push(item.into());
+        }
+        txn.commit().await?;
+
+        Ok(InspectionTemplate::new(
+            template.id as u32,
+            &template.name,
+            inserted,
+        ))
+    }
+
+    #[named]
+    pub async fn get_inspection_templates(&self) -> Result<Vec<InspectionTemplate>, DbError> {
+        self.login_check(function_name!())?;
+
+        let templates = db_entities::prelude::InspectionTemplate::find()
+            .find_with_related(inspection_item::Entity)
+            .order_by_asc(inspection_template::Column::Id)
+            .order_by_asc(inspection_item::Column::Position)
+            .all(self.db())
+            .await?;
+        Ok(templates
+            .into_iter()
+            .map(|(template, items)| {
+                InspectionTemplate::new(
+                    template.id as u32,
+                    &template.name,
+                    items.into_iter().map(|item| item.into()).collect(),
+                )
+            })
+            .collect())
+    }
+
+    /// Makes `template_id` the checklist of an unfinished inspection, for mechanics of the
+    /// order's location. Starting another checklist discards the results of the previous one.
+    /// Raises the [`Order::version`] like other changes of the order.
+    #[named]
+    pub async fn start_inspection(
+        &self,
+        order_id: u32,
+        template_id: u32,
+    ) -> Result<InspectionChecklist, DbError> {
+        self.login_check(function_name!())?;
+        self.inspection_check(order_id).await?;
+        let template = self.inspection_template(template_id).await?;
+
+        let txn = self.storage.begin().await?;
+        inspection_result::Entity::delete_many()
+            .filter(inspection_result::Column::OrderId.eq(order_id as i32))
+            .exec(txn.connection())
+            .await?;
+        order::Entity::update_many()
+            .col_expr(
+                order::Column::InspectionTemplateId,
+                Expr::value(template_id as i32),
+            )
+            .col_expr(
+                order::Column::Version,
+                Expr::col(order::Column::Version).add(1),
+            )
+            .filter(order::Column::Id.eq(order_id as i32))
+            .exec(txn.connection())
+            .await?;
+        txn.commit().await?;
+
+        Ok(InspectionChecklist::new(order_id, template, Vec::new()))
+    }
+
+    /// Records what the mechanic found when checking an item of the order's checklist,
+    /// replacing an earlier result for the item. Items with a unit need a `measurement`,
+    /// it is not stored for other items.
+    #[named]
+    pub async fn record_inspection_result(
+        &self,
+        order_id: u32,
+        item_id: u32,
+        status: InspectionStatus,
+        measurement: Option<f64>,
+        comment: Option<&str>,
+    ) -> Result<InspectionResult, DbError> {
+        self.login_check(function_name!())?;
+        let order = self.inspection_check(order_id).await?;
+        let Some(template_id) = order.inspection_template_id else {
+            return Err(DbError::NoChecklist(order_id));
+        };
+
+        let Some(item) = inspection_item::Entity::find_by_id(item_id as i32)
+            .filter(inspection_item::Column::TemplateId.eq(template_id))
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::InspectionItem(item_id));
+        };
+        let measurement = match (&item.unit, measurement.filter(|m| m.is_finite())) {
+            (Some(_), None) => return Err(DbError::MissingMeasurement(item_id)),
+            (Some(_), measurement) => measurement,
+            (None, _) => None,
+        };
+        let comment = match comment.map(str::trim).filter(|c| !c.is_empty()) {
+            Some(comment) => Some(checked_text(comment)?.to_owned()),
+            None => None,
+        };
+
+        let result = inspection_result::ActiveModel {
+            order_id: Set(order_id as i32),
+            item_id: Set(item.id),
+            status: Set(status),
+            measurement: Set(measurement),
+            comment: Set(comment),
+            mechanic_id: Set(self.user.id() as i32),
+            recorded_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        inspection_result::Entity::insert(result)
+            .on_conflict(
+                OnConflict::columns([
+                    inspection_result::Column::OrderId,
+                    inspection_result::Column::ItemId,
+                ])
+                .update_columns([
+                    inspection_result::Column::Status,
+                    inspection_result::Column::Measurement,
+                    inspection_result::Column::Comment,
+                    inspection_result::Column::MechanicId,
+                    inspection_result::Column::RecordedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(self.db())
+            .await?;
+
+        let Some(result) = inspection_result::Entity::find()
+            .filter(inspection_result::Column::OrderId.eq(order_id as i32))
+            .filter(inspection_result::Column::ItemId.eq(item.id))
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::InspectionItem(item_id));
+        };
+        Ok(result.into())
+    }
+
+    /// Checklist of an order with the results recorded so far, for whoever may see the order
+    #[named]
+    pub async fn get_inspection_checklist(
+        &self,
+        order_id: u32,
+    ) -> Result<InspectionChecklist, DbError> {
+        self.login_check(function_name!())?;
+        let order = self.checklist_order(order_id).await?;
+        let Some(template_id) = order.inspection_template_id else {
+            return Err(DbError::NoChecklist(order_id));
+        };
+        let template = self.inspection_template(template_id as u32).await?;
+
+        let results = db_entities::prelude::InspectionResult::find()
+            .filter(inspection_result::Column::OrderId.eq(order_id as i32))
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect();
+        Ok(InspectionChecklist::new(order_id, template, results))
+    }
+
+    /// Repairs suggested when the inspection was changed to a repair, in checklist order,
+    /// for whoever may see the order
+    #[named]
+    pub async fn get_repair_suggestions(
+        &self,
+        order_id: u32,
+    ) -> Result<Vec<RepairSuggestion>, DbError> {
+        self.login_check(function_name!())?;
+        self.checklist_order(order_id).await?;
+
+        Ok(db_entities::prelude::RepairSuggestion::find()
+            .filter(db_entities::repair_suggestion::Column::OrderId.eq(order_id as i32))
+            .order_by_asc(db_entities::repair_suggestion::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    async fn inspection_template(&self, template_id: u32) -> Result<InspectionTemplate, DbError> {
+        let Some(template) =
+            db_entities::prelude::InspectionTemplate::find_by_id(template_id as i32)
+                .one(self.db())
+                .await?
+        else {
+            return Err(DbError::InspectionTemplate(template_id));
+        };
+        let items = db_entities::prelude::InspectionItem::find()
+            .filter(inspection_item::Column::TemplateId.eq(template.id))
+            .order_by_asc(inspection_item::Column::Position)
+            .all(self.db())
+            .await?;
+        Ok(InspectionTemplate::new(
+            template.id as u32,
+            &template.name,
+            items.into_iter().map(|item| item.into()).collect(),
+        ))
+    }
+
+    /// Returns the order if the logged in user may see it
+    async fn checklist_order(&self, order_id: u32) -> Result<order::Model, DbError> {
+        let Some(order) = order::Entity::find_by_id(order_id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.can_see_order(&order.clone().into()) {
+            return Err(DbError::Permission);
+        }
+        Ok(order)
+    }
+
+    /// Returns the order if the logged in user is a mechanic of its location and it is
+    /// an unfinished inspection
+    async fn inspection_check(&self, order_id: u32) -> Result<order::Model, DbError> {
+        if !matches!(self.user.user_type(), UserType::Mechanic) {
+            return Err(DbError::Permission);
+        }
+        let order = self.checklist_order(order_id).await?;
+        if order.finished {
+            return Err(DbError::OrderFinished(order_id));
+        }
+        if order.service != Service::Inspection {
+            return Err(DbError::NotInspection(order_id));
+        }
+        Ok(order)
+    }
+}
@@ -0,0 +1,329 @@
+use super::settings::LABOR_RATE;
+use crate::db_entities::{labor_entry, labor_line, order};
+use crate::storage::{OrderRepository, ReportRepository, Storage};
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, QuerySelect, Set, SqlErr,
+};
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+
+/// Time tracked for an order compared with its estimate
+#[derive(Clone, Debug, Serialize)]
+pub struct LaborSummary {
+    pub order_id: u32,
+    pub entries: Vec<LaborEntry>,
+    /// Tracked so far, including mechanics who are still clocked on
+    pub actual_minutes: u32,
+    pub estimated_minutes: Option<u32>,
+}
+
+impl LaborSummary {
+    pub fn actual_hours(&self) -> f64 {
+        self.actual_minutes as f64 / 60.0
+    }
+
+    pub fn estimated_hours(&self) -> Option<f64> {
+        self.estimated_minutes.map(|minutes| minutes as f64 / 60.0)
+    }
+
+    /// Minutes worked beyond the estimate, negative when the order took less
+    pub fn overrun_minutes(&self) -> Option<i64> {
+        self.estimated_minutes
+            .map(|estimate| self.actual_minutes as i64 - estimate as i64)
+    }
+}
+
+/// Whole minutes in `seconds`, rounded up
+fn minutes(seconds: i64) -> u32 {
+    (seconds.max(0) as u64).div_ceil(60) as u32
+}
+
+/// Cost in cents of `minutes` at `rate` cents per hour, rounded to the nearest cent,
+/// `None` if it is too high
+fn labor_cost(minutes: u32, rate: u32) -> Option<u32> {
+    u32::try_from((minutes as u64 * rate as u64 + 30) / 60).ok()
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// Cents per hour of labor, `None` until a manager sets it
+    #[named]
+    pub async fn labor_rate(&self) -> Result<Option<u32>, DbError> {
+        self.login_check(function_name!())?;
+        Ok(self.setting(LABOR_RATE).await?)
+    }
+
+    /// Sets the cents per hour billed for labor on future reports, for managers
+    #[named]
+    pub async fn set_labor_rate(&self, rate: u32) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        self.set_setting(LABOR_RATE, &rate).await?;
+        Ok(())
+    }
+}
+
+impl ShopBackend {
+    /// Starts tracking the mechanic's time on an unfinished order. Mechanics work on one
+    /// order at a time, pausing is clocking off and on again.
+    #[named]
+    pub async fn clock_on(&self, order_id: u32) -> Result<LaborEntry, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Mechanic) {
+            return Err(DbError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        let Some(order) = txn.order_for_update(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id()) {
+            return Err(DbError::Permission);
+        }
+        if order.finished() {
+            return Err(DbError::OrderFinished(order_id));
+        }
+
+        if let Some(active) = self.open_labor_entry(txn.connection(), true).await? {
+            return Err(DbError::AlreadyClockedOn(active.order_id as u32));
+        }
+
+        let entry = match (labor_entry::ActiveModel {
+            order_id: Set(order_id as i32),
+            mechanic_id: Set(self.user.id() as i32),
+            started_at: Set(Utc::now()),
+            ..Default::default()
+        })
+        .insert(txn.connection())
+        .await
+        {
+            Ok(entry) => entry,
+            // Clocked on to another order at the same time, which the unique index on
+            // open entries rejects
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                drop(txn);
+                let active = self.open_labor_entry(self.db(), false).await?;
+                return Err(DbError::AlreadyClockedOn(
+                    active.map_or(order_id, |active| active.order_id as u32),
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        txn.commit().await?;
+        Ok(entry.into())
+    }
+
+    /// The entry the logged in mechanic is clocked on to, locked if `for_update`
+    async fn open_labor_entry(
+        &self,
+        db: &impl ConnectionTrait,
+        for_update: bool,
+    ) -> Result<Option<labor_entry::Model>, DbErr> {
+        let mut query = db_entities::prelude::LaborEntry::find()
+            .filter(labor_entry::Column::MechanicId.eq(self.user.id() as i32))
+            .filter(labor_entry::Column::EndedAt.is_null());
+        if for_update {
+            query = query.lock_exclusive();
+        }
+        query.one(db).await
+    }
+
+    /// Stops tracking the mechanic's time on an order, returns the finished entry
+    #[named]
+    pub async fn clock_off(&self, order_id: u32) -> Result<LaborEntry, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Mechanic) {
+            return Err(DbError::Permission);
+        }
+
+        let Some(entry) = db_entities::prelude::LaborEntry::find()
+            .filter(labor_entry::Column::OrderId.eq(order_id as i32))
+            .filter(labor_entry::Column::MechanicId.eq(self.user.id() as i32))
+            .filter(labor_entry::Column::EndedAt.is_null())
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::NotClockedOn(order_id));
+        };
+
+        let mut entry = entry.into_active_model();
+        entry.ended_at = Set(Some(Utc::now()));
+        Ok(entry.update(self.db()).await?.into())
+    }
+
+    /// Sets how long an order should take, for mechanics and managers.
+    /// Raises the [`Order::version`] like other changes of the order.
+    #[named]
+    pub async fn set_labor_estimate(
+        &self,
+        order_id: u32,
+        estimated_minutes: Option<u32>,
+    ) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(
+            self.user.user_type(),
+            UserType::Mechanic | UserType::Manager
+        ) {
+            return Err(DbError::Permission);
+        }
+
+        let Some(order) = self.storage.order(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id()) {
+            return Err(DbError::Permission);
+        }
+
+        order::Entity::update_many()
+            .col_expr(
+                order::Column::EstimatedMinutes,
+                Expr::value(estimated_minutes.map(|minutes| minutes as i32)),
+            )
+            // Whoever saw the order before the estimate changed has to look again
+            .col_expr(
+                order::Column::Version,
+                Expr::col(order::Column::Version).add(1),
+            )
+            .filter(order::Column::Id.eq(order_id as i32))
+            .exec(self.db())
+            .await?;
+        Ok(())
+    }
+
+    /// Time tracked for an order and its estimate, for employees of its location
+    #[named]
+    pub async fn get_labor(&self, order_id: u32) -> Result<LaborSummary, DbError> {
+        self.login_check(function_name!())?;
+        if matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+
+        let Some(order) = order::Entity::find_by_id(order_id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id as u32) {
+            return Err(DbError::Permission);
+        }
+
+        let entries: Vec<LaborEntry> = db_entities::prelude::LaborEntry::find()
+            .filter(labor_entry::Column::OrderId.eq(order_id as i32))
+            .order_by_asc(labor_entry::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect();
+        let now = Utc::now();
+        let seconds = entries
+            .iter()
+            .map(|entry| entry.duration(now).num_seconds())
+            .sum();
+
+        Ok(LaborSummary {
+            order_id,
+            entries,
+            actual_minutes: minutes(seconds),
+            estimated_minutes: order.estimated_minutes.map(|minutes| minutes as u32),
+        })
+    }
+
+    /// Issues the report of a finished order like [`ShopBackend::register_report`], adding
+    /// a labor line for every mechanic who tracked time on it at the current labor rate.
+    /// `parts_cost` is everything but labor, the report costs both.
+    #[named]
+    pub async fn register_report_with_labor(
+        &self,
+        order_id: u32,
+        parts_cost: u32,
+    ) -> Result<Report, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Technician) {
+            return Err(DbError::Permission);
+        }
+        let rate = self.labor_rate().await?.ok_or(DbError::LaborRateNotSet)?;
+
+        let txn = self.storage.begin().await?;
+        let order = self.reportable_order(&txn, order_id).await?;
+
+        let now = Utc::now();
+        let mut seconds = BTreeMap::<i32, i64>::new();
+        for entry in db_entities::prelude::LaborEntry::find()
+            .filter(labor_entry::Column::OrderId.eq(order_id as i32))
+            .all(txn.connection())
+            .await?
+        {
+            let entry = LaborEntry::from(entry);
+            *seconds.entry(entry.mechanic_id() as i32).or_default() +=
+                entry.duration(now).num_seconds();
+        }
+        let lines = seconds
+            .into_iter()
+            .map(|(mechanic_id, seconds)| {
+                let minutes = minutes(seconds);
+                let amount = labor_cost(minutes, rate).ok_or(DbError::CostTooHigh)?;
+                Ok((mechanic_id, minutes, amount))
+            })
+            .collect::<Result<Vec<(i32, u32, u32)>, DbError>>()?;
+        let cost = lines
+            .iter()
+            .try_fold(parts_cost, |cost, (_, _, amount)| cost.checked_add(*amount))
+            .ok_or(DbError::CostTooHigh)?;
+
+        let Some(report) = txn
+            .insert_report(order.client_id(), order_id, order.location_id(), cost, 1)
+            .await?
+        else {
+            return Err(DbError::Conflict("order", order_id));
+        };
+        for (mechanic_id, minutes, amount) in lines {
+            labor_line::ActiveModel {
+                report_id: Set(report.id() as i32),
+                mechanic_id: Set(mechanic_id),
+                minutes: Set(minutes as i32),
+                rate: Set(rate as i32),
+                amount: Set(amount as i32),
+                ..Default::default()
+            }
+            .insert(txn.connection())
+            .await?;
+        }
+
+        self.commit_event(
+            txn,
+            ShopEvent::ReportRegistered {
+                report_id: report.id(),
+                order_id,
+                client_id: order.client_id(),
+                cost,
+            },
+        )
+        .await?;
+        Ok(report)
+    }
+
+    /// Labor billed on a report, for whoever may see the report
+    pub async fn get_labor_lines(&self, report_id: u32) -> Result<Vec<LaborLine>, DbError> {
+        self.get_report(report_id).await?;
+
+        Ok(db_entities::prelude::LaborLine::find()
+            .filter(labor_line::Column::ReportId.eq(report_id as i32))
+            .order_by_asc(labor_line::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+}
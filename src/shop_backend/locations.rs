@@ -0,0 +1,285 @@
+use crate::db_entities::{bay, employee, stock_item};
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use function_name::named;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use serde::Serialize;
+
+/// Work at one location, for the cross-location view of managers
+#[derive(Clone, Debug, Serialize)]
+pub struct LocationSummary {
+    pub location: Location,
+    pub unfinished_orders: u32,
+    pub finished_orders: u32,
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// All workshops, clients pick one of them when booking an order
+    #[named]
+    pub async fn get_locations(&self) -> Result<Vec<Location>, DbError> {
+        self.login_check(function_name!())?;
+        Ok(self.storage.locations().await?)
+    }
+
+    /// Adds a workshop, for managers
+    #[named]
+    pub async fn create_location(
+        &self,
+        name: &str,
+        address: Option<&str>,
+    ) -> Result<Location, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        Ok(self.storage.insert_location(name, address).await?)
+    }
+
+    /// Number of orders at every location, for managers
+    #[named]
+    pub async fn get_location_summaries(&self) -> Result<Vec<LocationSummary>, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        let orders = self.storage.orders(None, None).await?;
+        Ok(self
+            .storage
+            .locations()
+            .await?
+            .into_iter()
+            .map(|location| {
+                let at_location = orders
+                    .iter()
+                    .filter(|order| order.location_id() == location.id());
+                let total = at_location.clone().count();
+                let finished = at_location.filter(|order| order.finished()).count();
+                LocationSummary {
+                    location,
+                    unfinished_orders: (total - finished) as u32,
+                    finished_orders: finished as u32,
+                }
+            })
+            .collect())
+    }
+
+    /// Checks that `location_id` exists and the logged in employee works there
+    async fn location_check(&self, location_id: u32) -> Result<(), DbError> {
+        if matches!(
+            self.user.user_type(),
+            UserType::Client | UserType::NotLoggedIn
+        ) || !self.works_at(location_id)
+        {
+            return Err(DbError::Permission);
+        }
+
+        match self.storage.location(location_id).await? {
+            Some(_) => Ok(()),
+            None => Err(DbError::Location(location_id)),
+        }
+    }
+}
+
+impl ShopBackend {
+    /// Assigns an employee to a workshop, or lets them work at all of them with `None`.
+    /// For managers, takes effect the next time the employee logs in.
+    #[named]
+    pub async fn set_employee_location(
+        &self,
+        employee_id: u32,
+        location_id: Option<u32>,
+    ) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        if let Some(location_id) = location_id {
+            if self.storage.location(location_id).await?.is_none() {
+                return Err(DbError::Location(location_id));
+            }
+        }
+        let Some(employee) = db_entities::prelude::Employee::find_by_id(employee_id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::Employee(employee_id));
+        };
+
+        let mut employee: employee::ActiveModel = employee.into();
+        employee.location_id = Set(location_id.map(|id| id as i32));
+        employee.update(self.db()).await?;
+        Ok(())
+    }
+
+    /// Adds a bay to a workshop, for managers
+    #[named]
+    pub async fn create_bay(&self, location_id: u32, name: &str) -> Result<Bay, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+        self.location_check(location_id).await?;
+
+        Ok(bay::ActiveModel {
+            location_id: Set(location_id as i32),
+            name: Set(name.to_owned()),
+            ..Default::default()
+        }
+        .insert(self.db())
+        .await?
+        .into())
+    }
+
+    /// Bays of a workshop, for employees working there
+    #[named]
+    pub async fn get_bays(&self, location_id: u32) -> Result<Vec<Bay>, DbError> {
+        self.login_check(function_name!())?;
+        self.location_check(location_id).await?;
+
+        Ok(db_entities::prelude::Bay::find()
+            .filter(bay::Column::LocationId.eq(location_id as i32))
+            .order_by_asc(bay::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    /// Parts kept at a workshop, for employees working there
+    #[named]
+    pub async fn get_stock(&self, location_id: u32) -> Result<Vec<StockItem>, DbError> {
+        self.login_check(function_name!())?;
+        self.location_check(location_id).await?;
+
+        Ok(db_entities::prelude::StockItem::find()
+            .filter(stock_item::Column::LocationId.eq(location_id as i32))
+            .order_by_asc(stock_item::Column::PartNumber)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    /// Records parts delivered to a workshop, for employees working there.
+    /// Returns the stock of the part after the delivery.
+    #[named]
+    pub async fn add_stock(
+        &self,
+        location_id: u32,
+        part_number: &str,
+        name: &str,
+        quantity: u32,
+    ) -> Result<StockItem, DbError> {
+        self.login_check(function_name!())?;
+        self.location_check(location_id).await?;
+
+        let txn = self.storage.begin().await?;
+        let item = add_stock(txn.connection(), location_id, part_number, name, quantity).await?;
+        txn.commit().await?;
+        Ok(item)
+    }
+
+    /// Moves parts from one workshop to another, for managers.
+    /// Returns the stock of the part at `to_location_id` after the transfer.
+    #[named]
+    pub async fn transfer_stock(
+        &self,
+        part_number: &str,
+        from_location_id: u32,
+        to_location_id: u32,
+        quantity: u32,
+    ) -> Result<StockItem, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+        self.location_check(from_location_id).await?;
+        self.location_check(to_location_id).await?;
+
+        let txn = self.storage.begin().await?;
+        let source = take_stock(txn.connection(), from_location_id, part_number, quantity).await?;
+        let item = add_stock(
+            txn.connection(),
+            to_location_id,
+            part_number,
+            source.name(),
+            quantity,
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(item)
+    }
+}
+
+/// Removes `quantity` from the stock of a part at a location, returns the remaining stock
+pub(super) async fn take_stock(
+    db: &impl ConnectionTrait,
+    location_id: u32,
+    part_number: &str,
+    quantity: u32,
+) -> Result<StockItem, DbError> {
+    let item = db_entities::prelude::StockItem::find()
+        .filter(stock_item::Column::LocationId.eq(location_id as i32))
+        .filter(stock_item::Column::PartNumber.eq(part_number))
+        .lock_exclusive()
+        .one(db)
+        .await?;
+    let available = item.as_ref().map_or(0, |item| item.quantity as u32);
+    let Some(item) = item.filter(|_| available >= quantity) else {
+        return Err(DbError::InsufficientStock {
+            location_id,
+            part_number: part_number.to_owned(),
+            available,
+        });
+    };
+
+    let mut item = item.into_active_model();
+    item.quantity = Set((available - quantity) as i32);
+    Ok(item.update(db).await?.into())
+}
+
+/// Adds `quantity` to the stock of a part at a location, creating it if there is none
+async fn add_stock(
+    db: &impl ConnectionTrait,
+    location_id: u32,
+    part_number: &str,
+    name: &str,
+    quantity: u32,
+) -> Result<StockItem, DbErr> {
+    let existing = db_entities::prelude::StockItem::find()
+        .filter(stock_item::Column::LocationId.eq(location_id as i32))
+        .filter(stock_item::Column::PartNumber.eq(part_number))
+        .lock_exclusive()
+        .one(db)
+        .await?;
+
+    let item = match existing {
+        Some(item) => {
+            let stocked = item.quantity;
+            let mut item = item.into_active_model();
+            item.quantity = Set(stocked + quantity as i32);
+            item.update(db).await?
+        }
+        None => {
+            stock_item::ActiveModel {
+                location_id: Set(location_id as i32),
+                part_number: Set(part_number.to_owned()),
+                name: Set(name.to_owned()),
+                quantity: Set(quantity as i32),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?
+        }
+    };
+    Ok(item.into())
+}
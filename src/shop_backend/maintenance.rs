@@ -0,0 +1,257 @@
+use crate::db_entities::{client, maintenance_done, maintenance_reminder, maintenance_schedule};
+use crate::maintenance::MaintenanceDue;
+use crate::storage::{Storage, Transaction};
+use crate::vehicle_history::VehicleHistory;
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+/// Maintenance of the car in `history` for every schedule that applies to its make.
+/// `done` are the schedule and order IDs of the maintenance recorded for the car.
+fn due_maintenance(
+    history: &VehicleHistory,
+    schedules: &[MaintenanceSchedule],
+    done: &[(i32, i32)],
+) -> Vec<MaintenanceDue> {
+    schedules
+        .iter()
+        .filter(|schedule| schedule.applies_to(history.car()))
+        .map(|schedule| {
+            let orders = done
+                .iter()
+                .filter(|(schedule_id, _)| *schedule_id as u32 == schedule.id())
+                .map(|(_, order_id)| *order_id as u32)
+                .collect::<Vec<_>>();
+            MaintenanceDue::new(schedule.clone(), history, &orders)
+        })
+        .collect()
+}
+
+impl ShopBackend {
+    /// Adds maintenance that cars need regularly, for managers. `make` limits the schedule
+    /// to cars of one make. Maintenance is due after `interval_km` kilometers or
+    /// `interval_months` months, whichever comes first, and at least one has to be given.
+    #[named]
+    pub async fn add_maintenance_schedule(
+        &self,
+        name: &str,
+        service: &Service,
+        make: Option<&str>,
+        interval_km: Option<u32>,
+        interval_months: Option<u32>,
+    ) -> Result<MaintenanceSchedule, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DbError::EmptyScheduleName);
+        }
+        let interval_km = interval_km.filter(|&km| km > 0);
+        let interval_months = interval_months.filter(|&months| months > 0);
+        if interval_km.is_none() && interval_months.is_none() {
+            return Err(DbError::NoMaintenanceInterval);
+        }
+        let make = make.map(str::trim).filter(|make| !make.is_empty());
+
+        let schedule = maintenance_schedule::ActiveModel {
+            name: Set(name.to_owned()),
+            service: Set(*service),
+            make: Set(make.map(str::to_owned)),
+            interval_km: Set(interval_km.map(|km| km as i32)),
+            interval_months: Set(interval_months.map(|months| months as i32)),
+            ..Default::default()
+        }
+        .insert(self.db())
+        .await?;
+        Ok(schedule.into())
+    }
+
+    #[named]
+    pub async fn get_maintenance_schedules(&self) -> Result<Vec<MaintenanceSchedule>, DbError> {
+        self.login_check(function_name!())?;
+        self.maintenance_schedules().await
+    }
+
+    /// Removes a schedule together with the record of reminders sent for it, for managers
+    #[named]
+    pub async fn delete_maintenance_schedule(&self, schedule_id: u32) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        let deleted = db_entities::prelude::MaintenanceSchedule::delete_by_id(schedule_id as i32)
+            .exec(self.db())
+            .await?;
+        if deleted.rows_affected == 0 {
+            return Err(DbError::MaintenanceSchedule(schedule_id));
+        }
+        Ok(())
+    }
+
+    /// Records that `order_id` does the maintenance of `schedule_id`, so that the schedule's
+    /// intervals start again once the order is finished. Recording it twice changes nothing.
+    /// For mechanics and managers working at the order's location.
+    #[named]
+    pub async fn record_maintenance_done(
+        &self,
+        order_id: u32,
+        schedule_id: u32,
+    ) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(
+            self.user.user_type(),
+            UserType::Mechanic | UserType::Manager
+        ) {
+            return Err(DbError::Permission);
+        }
+
+        let Some(order) = self.storage.order(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id()) {
+            return Err(DbError::Permission);
+        }
+        if db_entities::prelude::MaintenanceSchedule::find_by_id(schedule_id as i32)
+            .one(self.db())
+            .await?
+            .is_none()
+        {
+            return Err(DbError::MaintenanceSchedule(schedule_id));
+        }
+
+        db_entities::prelude::MaintenanceDone::insert(maintenance_done::ActiveModel {
+            schedule_id: Set(schedule_id as i32),
+            order_id: Set(order_id as i32),
+            recorded_at: Set(Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                maintenance_done::Column::OrderId,
+                maintenance_done::Column::ScheduleId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(self.db())
+        .await?;
+        Ok(())
+    }
+
+    /// When a client's car next needs each maintenance, computed from its history. Clients
+    /// see their own car, employees every car.
+    pub async fn get_maintenance_due(
+        &self,
+        client_id: u32,
+    ) -> Result<Vec<MaintenanceDue>, DbError> {
+        let history = self.get_vehicle_history(client_id).await?;
+        let schedules = self.maintenance_schedules().await?;
+        let done = self.maintenance_done(&history).await?;
+        Ok(due_maintenance(&history, &schedules, &done))
+    }
+
+    /// Reminds clients of maintenance due within `days_ahead` days or overdue, through the
+    /// channels they agreed to, unless they opted out of maintenance reminders. Every due
+    /// maintenance is reminded of once, until it is done again. Returns how many reminders
+    /// were added to the outbox. Should be called periodically, e.g. daily, by one process.
+    pub async fn send_maintenance_reminders(&self, days_ahead: u32) -> Result<usize, DbError> {
+        let schedules = self.maintenance_schedules().await?;
+        if schedules.is_empty() {
+            return Ok(0);
+        }
+
+        let clients = db_entities::prelude::Client::find()
+            .filter(client::Column::Deleted.eq(false))
+            .filter(client::Column::Car.is_not_null())
+            .order_by_asc(client::Column::Id)
+            .all(self.db())
+            .await?;
+        let today = Utc::now().date_naive();
+        let mut reminded = 0;
+        for client in clients {
+            let preferences = client.communication_preferences.unwrap_or_default();
+            if !preferences.maintenance_reminders {
+                continue;
+            }
+
+            let history = self.vehicle_history(client.id as u32).await?;
+            let sent = db_entities::prelude::MaintenanceReminder::find()
+                .filter(maintenance_reminder::Column::ClientId.eq(client.id))
+                .all(self.db())
+                .await?;
+            let done = self.maintenance_done(&history).await?;
+            for due in due_maintenance(&history, &schedules, &done) {
+                let schedule_id = due.schedule.id() as i32;
+                let last_order_id = due.last_order_id.map(|id| id as i32);
+                if !due.due_within(today, days_ahead)
+                    || sent.iter().any(|reminder| {
+                        reminder.schedule_id == schedule_id
+                            && reminder.last_order_id == last_order_id
+                    })
+                {
+                    continue;
+                }
+
+                let txn = self.storage.begin().await?;
+                maintenance_reminder::ActiveModel {
+                    client_id: Set(client.id),
+                    schedule_id: Set(schedule_id),
+                    last_order_id: Set(last_order_id),
+                    sent_at: Set(Utc::now()),
+                    ..Default::default()
+                }
+                .insert(txn.connection())
+                .await?;
+                txn.enqueue_client_notification(
+                    client.id as u32,
+                    &NotificationEvent::MaintenanceDue {
+                        name: due.schedule.name().to_owned(),
+                        car: format!("{} {}", history.car().make, history.car().model),
+                        due_on: due.due_on,
+                        due_mileage: due.due_mileage,
+                    },
+                )
+                .await?;
+                txn.commit().await?;
+                reminded += 1;
+            }
+        }
+        Ok(reminded)
+    }
+
+    /// Schedule and order IDs of the maintenance recorded for the orders in `history`
+    async fn maintenance_done(&self, history: &VehicleHistory) -> Result<Vec<(i32, i32)>, DbErr> {
+        let order_ids = history
+            .services()
+            .iter()
+            .map(|record| record.order().id() as i32)
+            .collect::<Vec<_>>();
+        db_entities::prelude::MaintenanceDone::find()
+            .select_only()
+            .column(maintenance_done::Column::ScheduleId)
+            .column(maintenance_done::Column::OrderId)
+            .filter(maintenance_done::Column::OrderId.is_in(order_ids))
+            .into_tuple()
+            .all(self.db())
+            .await
+    }
+
+    async fn maintenance_schedules(&self) -> Result<Vec<MaintenanceSchedule>, DbError> {
+        Ok(db_entities::prelude::MaintenanceSchedule::find()
+            .order_by_asc(maintenance_schedule::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+}
@@ -0,0 +1,79 @@
+use super::notes::checked_text;
+use crate::db_entities::order_message;
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+impl ShopBackend {
+    /// Writes to the thread of an order, clients about their own orders and employees for
+    /// the shop about orders at their location. Clients are notified of messages from the shop.
+    #[named]
+    pub async fn send_order_message(
+        &self,
+        order_id: u32,
+        body: &str,
+    ) -> Result<OrderMessage, DbError> {
+        self.login_check(function_name!())?;
+        let order = self.visible_order(order_id).await?;
+        let body = checked_text(body)?;
+        let employee_id = match self.user.user_type() {
+            UserType::Client => None,
+            _ => Some(self.user.id()),
+        };
+
+        let txn = self.storage.begin().await?;
+        let message = order_message::ActiveModel {
+            order_id: Set(order_id as i32),
+            employee_id: Set(employee_id.map(|id| id as i32)),
+            body: Set(body.to_owned()),
+            sent_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(txn.connection())
+        .await?;
+        self.commit_event(
+            txn,
+            ShopEvent::OrderMessageSent {
+                message_id: message.id as u32,
+                order_id,
+                client_id: order.client_id(),
+                employee_id,
+            },
+        )
+        .await?;
+        Ok(message.into())
+    }
+
+    /// Thread of an order, oldest first, for whoever may write to it. Messages from the
+    /// other side are marked as read, which their senders see in [`OrderMessage::read_at`].
+    #[named]
+    pub async fn get_order_messages(&self, order_id: u32) -> Result<Vec<OrderMessage>, DbError> {
+        self.login_check(function_name!())?;
+        self.visible_order(order_id).await?;
+
+        let from_other_side = match self.user.user_type() {
+            UserType::Client => order_message::Column::EmployeeId.is_not_null(),
+            _ => order_message::Column::EmployeeId.is_null(),
+        };
+        order_message::Entity::update_many()
+            .col_expr(order_message::Column::ReadAt, Expr::value(Utc::now()))
+            .filter(order_message::Column::OrderId.eq(order_id as i32))
+            .filter(order_message::Column::ReadAt.is_null())
+            .filter(from_other_side)
+            .exec(self.db())
+            .await?;
+
+        Ok(db_entities::prelude::OrderMessage::find()
+            .filter(order_message::Column::OrderId.eq(order_id as i32))
+            .order_by_asc(order_message::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+}
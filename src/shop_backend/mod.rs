@@ -1,31 +1,75 @@
+mod analytics;
+mod attachments;
 mod clients;
 mod employees;
+mod export;
+mod inspections;
+mod labor;
+mod locations;
+mod maintenance;
+mod messages;
+mod notes;
+mod notifications;
+mod order_board;
 mod orders;
+mod passwords;
+mod payments;
 mod reports;
+mod settings;
+mod transactions;
+mod trouble_codes;
+mod two_factor;
+mod vehicles;
+mod verification;
+mod webhooks;
 
-use super::db_entities::client::{self, Car};
+use super::db_entities::client::Car;
 use super::migrator::Migrator;
+use super::storage::{SeaOrmStorage, Storage, Transaction};
 use super::{user::*, *};
 
 use function_name::named;
 use regex::Regex;
-use sea_orm::{
-    ActiveModelTrait, Database, DatabaseConnection, DbBackend, EntityTrait, Set, Statement,
-};
+use sea_orm::{Database, DatabaseConnection, DbBackend, Statement};
 use sea_orm_migration::prelude::*;
 
+pub use attachments::MAX_ATTACHMENT_SIZE;
+pub use inspections::NewInspectionItem;
+pub use labor::LaborSummary;
+pub use locations::LocationSummary;
+pub use reports::{ReportPage, ReportSearch};
+pub use verification::UnverifiedClientRestrictions;
+
 use std::env;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, Mutex};
 
 pub static EMAIL_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[\w\-\.]+@([\w-]+\.)+[\w-]{2,}$").unwrap());
 
+pub static PHONE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\+?[0-9][0-9 \-]{5,18}[0-9]$").unwrap());
+
 pub static HASH_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\$2[aby]?\$\d{1,2}\$[./A-Za-z0-9]{53}$").unwrap());
 
-pub struct ShopBackend {
-    db: DatabaseConnection,
+pub struct ShopBackend<S: Storage = SeaOrmStorage> {
+    storage: S,
     user: User,
+    notifier: Option<Arc<dyn Notifier>>,
+    sms_notifier: Option<Arc<dyn Notifier>>,
+    password_policy: PasswordPolicy,
+    /// Employee who gave a correct password but still has to pass two-factor authentication
+    pending_employee: Option<u32>,
+    /// Incorrect codes given for the pending login
+    second_factor_failures: u32,
+    signing_key: Vec<u8>,
+    http_client: reqwest::Client,
+    events: EventBus,
+    /// Events of changes made inside [`ShopBackend::transaction`], published after it commits
+    deferred_events: Option<Arc<Mutex<Vec<ShopEvent>>>>,
+    /// Shop this backend works for when hosted by a [`TenantDirectory`]
+    tenant: Option<String>,
+    blob_store: Arc<dyn BlobStore>,
 }
 
 impl ShopBackend {
@@ -40,10 +84,12 @@ impl ShopBackend {
 
         let db = Self::connect().await?;
 
-        Ok(ShopBackend {
-            db,
-            user: User::not_logged_in(),
-        })
+        Ok(Self::with_storage(SeaOrmStorage::new(db)).await?)
+    }
+
+    /// Connection to the database, for what only the default storage supports
+    fn db(&self) -> &DatabaseConnection {
+        self.storage.connection()
     }
 
     async fn connect() -> Result<DatabaseConnection, DbErr> {
@@ -84,14 +130,142 @@ impl ShopBackend {
 
         Ok(db)
     }
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// Backend keeping its data in `storage`, which has to be ready for use.
+    /// [`ShopBackend::init`] creates one with the default storage, other storages only
+    /// support part of the backend as listed at [`Storage`].
+    pub async fn with_storage(storage: S) -> Result<Self, DbErr> {
+        let mut backend = ShopBackend {
+            storage,
+            user: User::not_logged_in(),
+            notifier: SmtpNotifier::from_env().map(|smtp| Arc::new(smtp) as Arc<dyn Notifier>),
+            sms_notifier: None,
+            password_policy: PasswordPolicy::default(),
+            pending_employee: None,
+            second_factor_failures: 0,
+            signing_key: Vec::new(),
+            http_client: reqwest::Client::new(),
+            events: EventBus::new(),
+            deferred_events: None,
+            tenant: None,
+            blob_store: Arc::new(FileBlobStore::from_env()),
+        };
+        backend.signing_key = backend.load_signing_key().await?;
+
+        Ok(backend)
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Name of the shop when hosted by a [`TenantDirectory`], otherwise `None`
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub(crate) fn set_tenant(&mut self, tenant: &str) {
+        self.tenant = Some(tenant.to_owned());
+    }
+
+    /// Replaces the notifier used to send email, [`SmtpNotifier`] if SHOP_SMTP_HOST
+    /// environment variable exists. Without one, sending email fails.
+    pub fn set_notifier(&mut self, notifier: impl Notifier + 'static) {
+        self.notifier = Some(Arc::new(notifier));
+    }
+
+    fn email_notifier(&self) -> Result<&dyn Notifier, NotifyError> {
+        self.notifier
+            .as_deref()
+            .ok_or_else(|| NotifyError(String::from("no email notifier set")))
+    }
+
+    /// Replaces where the content of attachments is kept, a [`FileBlobStore`] in the
+    /// SHOP_BLOB_DIR directory by default
+    pub fn set_blob_store(&mut self, store: impl BlobStore + 'static) {
+        self.blob_store = Arc::new(store);
+    }
+
+    /// Events about changes made through this backend
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<ShopEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn event_bus(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Makes this backend publish to `bus`, so that one subscriber sees changes from many backends
+    pub fn set_event_bus(&mut self, bus: EventBus) {
+        self.events = bus;
+    }
+
+    /// Logs `user` in and tells subscribers about it
+    fn log_in(&mut self, user: User) -> User {
+        self.user = user;
+        self.publish(ShopEvent::LoggedIn {
+            user: self.user.clone(),
+        });
+        self.user.clone()
+    }
+
+    /// Tells subscribers about `event`, or keeps it until the enclosing transaction commits
+    fn publish(&self, event: ShopEvent) {
+        match &self.deferred_events {
+            Some(deferred) => deferred.lock().unwrap().push(event),
+            None => self.events.publish(event),
+        }
+    }
+
+    /// Adds the messages about `event` to the transaction and commits it, then tells
+    /// subscribers about the change. The messages are sent by [`ShopBackend::deliver_pending`].
+    async fn commit_event(&self, txn: S::Transaction, event: ShopEvent) -> Result<(), DbErr> {
+        txn.enqueue_messages(&event).await?;
+        txn.commit().await?;
+
+        self.publish(event);
+        Ok(())
+    }
 
     #[named]
     pub async fn log_out(&mut self) -> Result<User, NotLoggedInError> {
         self.login_check(function_name!())?;
         self.user = User::not_logged_in();
+        self.pending_employee = None;
         Ok(self.user.clone())
     }
 
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    /// Whether the logged in user may work on things at `location_id`.
+    /// Only employees assigned to a location are limited to it.
+    fn works_at(&self, location_id: u32) -> bool {
+        self.user.location_id().is_none_or(|id| id == location_id)
+    }
+
+    /// Whether the logged in user may see `order` and talk about it, which clients may
+    /// for their own orders and employees for the orders at their location
+    fn can_see_order(&self, order: &crate::Order) -> bool {
+        match self.user.user_type() {
+            UserType::Client => order.client_id() == self.user.id(),
+            UserType::NotLoggedIn => false,
+            _ => self.works_at(order.location_id()),
+        }
+    }
+
+    /// Returns the order if the logged in user may see it
+    async fn visible_order(&self, order_id: u32) -> Result<crate::Order, DbError> {
+        match self.storage.order(order_id).await? {
+            Some(order) if self.can_see_order(&order) => Ok(order),
+            Some(_) => Err(DbError::Permission),
+            None => Err(DbError::Order(order_id)),
+        }
+    }
+
     pub fn login_check(&self, func_name: &str) -> Result<(), NotLoggedInError> {
         if matches!(self.user.user_type(), UserType::NotLoggedIn) {
             Err(NotLoggedInError(func_name.to_string()))
@@ -109,25 +283,24 @@ impl ShopBackend {
     ) -> Result<(), DbError> {
         self.login_check(function_name!())?;
 
-        if matches!(self.user.user_type(), UserType::Mechanic { .. }) {
+        if matches!(self.user.user_type(), UserType::Mechanic) {
             return Err(DbError::Permission);
         }
 
-        match db_entities::prelude::Client::find_by_id(client_id as i32)
-            .one(&self.db)
-            .await?
-        {
+        self.unverified_check(|r| r.block_car_registration).await?;
+
+        let txn = self.storage.begin().await?;
+        match txn.client_for_update(client_id).await? {
             Some(client) => match &client.car {
-                Some(_) => Err(DbError::Other(String::from(
-                    "client already has a car registered",
-                ))),
+                Some(_) => Err(DbError::CarAlreadyRegistered(client_id)),
                 None => {
-                    let mut client_active: client::ActiveModel = client.into();
-                    client_active.car = Set(Some(Car {
+                    let car = Car {
                         make: make.to_owned(),
                         model: model.to_owned(),
-                    }));
-                    client_active.update(&self.db).await?;
+                    };
+                    txn.set_car(client_id, &car).await?;
+                    self.commit_event(txn, ShopEvent::CarRegistered { client_id, car })
+                        .await?;
                     Ok(())
                 }
             },
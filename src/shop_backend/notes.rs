@@ -0,0 +1,84 @@
+use crate::db_entities::order_note;
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+/// Longest complaint, note or message in characters
+const MAX_TEXT_LENGTH: usize = 4000;
+
+/// `text` without surrounding whitespace, fails if that is empty or too long
+pub(super) fn checked_text(text: &str) -> Result<&str, DbError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(DbError::EmptyText);
+    }
+    if text.chars().count() > MAX_TEXT_LENGTH {
+        return Err(DbError::TextTooLong(MAX_TEXT_LENGTH));
+    }
+    Ok(text)
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// The client's description of the problem, for whoever may see the order
+    #[named]
+    pub async fn get_complaint(&self, order_id: u32) -> Result<Option<String>, DbError> {
+        self.login_check(function_name!())?;
+
+        let Some(order) = self.storage.order(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.can_see_order(&order) {
+            return Err(DbError::Permission);
+        }
+        Ok(self.storage.complaint(order_id).await?)
+    }
+}
+
+impl ShopBackend {
+    /// Records a finding or remark about an order that the client does not see, for
+    /// employees of the order's location
+    #[named]
+    pub async fn add_order_note(&self, order_id: u32, body: &str) -> Result<OrderNote, DbError> {
+        self.login_check(function_name!())?;
+        self.note_check(order_id).await?;
+        let body = checked_text(body)?;
+
+        let note = order_note::ActiveModel {
+            order_id: Set(order_id as i32),
+            employee_id: Set(self.user.id() as i32),
+            body: Set(body.to_owned()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(self.db())
+        .await?;
+        Ok(note.into())
+    }
+
+    /// Internal notes about an order, oldest first, for employees of the order's location
+    #[named]
+    pub async fn get_order_notes(&self, order_id: u32) -> Result<Vec<OrderNote>, DbError> {
+        self.login_check(function_name!())?;
+        self.note_check(order_id).await?;
+
+        Ok(db_entities::prelude::OrderNote::find()
+            .filter(order_note::Column::OrderId.eq(order_id as i32))
+            .order_by_asc(order_note::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    /// Fails unless the logged in user is an employee who may see the order
+    async fn note_check(&self, order_id: u32) -> Result<(), DbError> {
+        if matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+        self.visible_order(order_id).await?;
+        Ok(())
+    }
+}
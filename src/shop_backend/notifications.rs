@@ -0,0 +1,185 @@
+use crate::db_entities::{notification, notification_outbox, notification_outbox::Channel};
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use chrono::{Duration, Utc};
+use function_name::named;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+
+use std::sync::Arc;
+
+/// Undelivered notifications are given up on after this many attempts
+const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+/// Delay before the first retry, doubled after every failed attempt
+const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+/// How long a notification being sent is hidden from other dispatchers
+const DELIVERY_LEASE_SECONDS: i64 = 300;
+
+/// Stores an in-app notification and marks its outbox message sent in one transaction,
+/// so that the client neither misses it nor gets it twice
+async fn deliver_in_app(
+    db: &DatabaseConnection,
+    message: &notification_outbox::Model,
+    attempts: i32,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    notification::ActiveModel {
+        client_id: Set(message.client_id),
+        subject: Set(message.subject.clone()),
+        body: Set(message.body.clone()),
+        created_at: Set(message.created_at),
+        read: Set(false),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    let mut sent: notification_outbox::ActiveModel = message.clone().into();
+    sent.attempts = Set(attempts);
+    sent.sent_at = Set(Some(Utc::now()));
+    sent.last_error = Set(None);
+    sent.update(&txn).await?;
+    txn.commit().await
+}
+
+impl ShopBackend {
+    /// Replaces the notifier used to send text messages, by default no text messages are sent
+    pub fn set_sms_notifier(&mut self, notifier: impl Notifier + 'static) {
+        self.sms_notifier = Some(Arc::new(notifier));
+        self.storage.set_sms_enabled(true);
+    }
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// Sends the notifications and webhooks that are due, returns how many were delivered.
+    ///
+    /// Changes only queue messages, so that no request waits for a mail server or a webhook
+    /// endpoint. Frontends call this from a background task every few seconds, failed
+    /// deliveries are retried with growing delays.
+    pub async fn deliver_pending(&self) -> Result<usize, DbError> {
+        Ok(self.dispatch_notifications().await? + self.dispatch_webhooks().await?)
+    }
+
+    /// Sends all notifications from the outbox that are due, returns how many were delivered.
+    /// Called by [`ShopBackend::deliver_pending`].
+    pub async fn dispatch_notifications(&self) -> Result<usize, DbError> {
+        let Some(db) = self.storage.outbox() else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        let due = db_entities::prelude::NotificationOutbox::find()
+            .filter(notification_outbox::Column::SentAt.is_null())
+            .filter(notification_outbox::Column::Attempts.lt(MAX_DELIVERY_ATTEMPTS))
+            .filter(notification_outbox::Column::NextAttemptAt.lte(now))
+            .order_by_asc(notification_outbox::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut delivered = 0;
+        for message in due {
+            // Claim the message by counting the attempt, so that other dispatchers skip it
+            let attempts = message.attempts + 1;
+            let claimed = db_entities::prelude::NotificationOutbox::update_many()
+                .col_expr(notification_outbox::Column::Attempts, attempts.into())
+                .col_expr(
+                    notification_outbox::Column::NextAttemptAt,
+                    (now + Duration::seconds(DELIVERY_LEASE_SECONDS)).into(),
+                )
+                .filter(notification_outbox::Column::Id.eq(message.id))
+                .filter(notification_outbox::Column::Attempts.eq(message.attempts))
+                .filter(notification_outbox::Column::SentAt.is_null())
+                .exec(db)
+                .await?;
+            if claimed.rows_affected != 1 {
+                continue;
+            }
+
+            let result = match message.channel {
+                Channel::Email => match self.email_notifier() {
+                    Ok(email) => {
+                        email
+                            .notify(&message.recipient, &message.subject, &message.body)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                },
+                Channel::Sms => match &self.sms_notifier {
+                    Some(sms) => {
+                        sms.notify(&message.recipient, &message.subject, &message.body)
+                            .await
+                    }
+                    None => Err(NotifyError(String::from("no SMS notifier set"))),
+                },
+                Channel::InApp => match deliver_in_app(db, &message, attempts).await {
+                    Ok(()) => {
+                        delivered += 1;
+                        continue;
+                    }
+                    Err(e) => Err(NotifyError(e.to_string())),
+                },
+            };
+
+            let mut message: notification_outbox::ActiveModel = message.into();
+            message.attempts = Set(attempts);
+            match result {
+                Ok(_) => {
+                    message.sent_at = Set(Some(Utc::now()));
+                    message.last_error = Set(None);
+                    delivered += 1;
+                }
+                Err(e) => {
+                    message.last_error = Set(Some(e.to_string()));
+                    message.next_attempt_at =
+                        Set(Utc::now()
+                            + Duration::seconds(RETRY_BASE_DELAY_SECONDS << (attempts - 1)));
+                }
+            }
+            message.update(db).await?;
+        }
+
+        Ok(delivered)
+    }
+}
+
+impl ShopBackend {
+    /// In-app notifications of the logged in client, newest first
+    #[named]
+    pub async fn get_notifications(&self) -> Result<Vec<Notification>, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+
+        let notifications = db_entities::prelude::Notification::find()
+            .filter(notification::Column::ClientId.eq(self.user.id() as i32))
+            .order_by_desc(notification::Column::CreatedAt)
+            .all(self.db())
+            .await?;
+        Ok(notifications.into_iter().map(|m| m.into()).collect())
+    }
+
+    #[named]
+    pub async fn mark_notification_read(&self, notification_id: u32) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+
+        match db_entities::prelude::Notification::find_by_id(notification_id as i32)
+            .one(self.db())
+            .await?
+        {
+            Some(notification) if notification.client_id == self.user.id() as i32 => {
+                let mut notification: notification::ActiveModel = notification.into();
+                notification.read = Set(true);
+                notification.update(self.db()).await?;
+                Ok(())
+            }
+            _ => Err(DbError::Notification(notification_id)),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::storage::Storage;
+use crate::{order_board::OrderBoard, UserType, *};
+
+use function_name::named;
+use tokio::sync::broadcast::error::RecvError;
+
+impl<S: Storage + Clone> ShopBackend<S> {
+    /// Board showing the orders that follows changes published on this backend's
+    /// [`EventBus`], for employees. Employees assigned to a location only see the orders
+    /// there. Has to be called inside a Tokio runtime.
+    #[named]
+    pub async fn order_board(&self) -> Result<OrderBoard, DbError> {
+        self.login_check(function_name!())?;
+        if matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+
+        // Subscribing first, so that no change made while loading is missed
+        let mut events = self.events.subscribe();
+        // All orders, as orders moved to the employee's location appear on the board
+        let board = OrderBoard::new(
+            self.storage.orders(None, None).await?,
+            self.user.location_id(),
+        );
+
+        let storage = self.storage.clone();
+        let updated = board.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => updated.apply(&event),
+                    Err(RecvError::Lagged(_)) => {
+                        if let Ok(orders) = storage.orders(None, None).await {
+                            updated.reset(orders);
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Ok(board)
+    }
+}
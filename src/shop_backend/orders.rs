@@ -1,86 +1,123 @@
-use crate::{db_entities::order, UserType, *};
+use super::notes::checked_text;
+use crate::storage::Storage;
+use crate::{UserType, *};
 
+use chrono::Utc;
 use function_name::named;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 
-impl ShopBackend {
+impl<S: Storage> ShopBackend<S> {
+    /// Books `service` at the workshop `location_id`, see [`ShopBackend::get_locations`].
+    /// `complaint` is the client's description of the problem, returns the order.
     #[named]
-    pub async fn register_order(&self, client_id: u32, service: &Service) -> Result<(), DbError> {
+    pub async fn register_order(
+        &self,
+        client_id: u32,
+        location_id: u32,
+        service: &Service,
+        complaint: Option<&str>,
+    ) -> Result<Order, DbError> {
         self.login_check(function_name!())?;
-        if matches!(self.user.user_type(), UserType::Technician) {
+        if matches!(self.user.user_type(), UserType::Technician) || !self.works_at(location_id) {
             return Err(DbError::Permission);
         }
+        let complaint = complaint.map(checked_text).transpose()?;
 
-        let Some(client) = db_entities::prelude::Client::find_by_id(client_id as i32)
-            .one(&self.db)
-            .await?
-        else {
+        self.unverified_check(|r| r.block_orders).await?;
+
+        // Locking the client, so that its car cannot be changed while the order is placed
+        let txn = self.storage.begin().await?;
+        let Some(client) = txn.client_for_update(client_id).await? else {
             return Err(DbError::Client(client_id));
         };
+        if txn.location(location_id).await?.is_none() {
+            return Err(DbError::Location(location_id));
+        }
 
         match client.car {
             Some(_) => {
-                let order = order::ActiveModel {
-                    client_id: Set(client_id as i32),
-                    service: Set(service.to_owned()),
-                    ..Default::default()
-                };
-                order.insert(&self.db).await?;
-                Ok(())
+                let order = txn
+                    .insert_order(client_id, location_id, *service, complaint)
+                    .await?;
+                self.commit_event(
+                    txn,
+                    ShopEvent::OrderRegistered {
+                        order_id: order.id(),
+                        client_id,
+                        location_id,
+                        service: *service,
+                    },
+                )
+                .await?;
+                Ok(order)
             }
-            None => Err(DbError::Other(String::from(
-                "client {client_id} has no car registered",
-            ))),
+            None => Err(DbError::NoCarRegistered(client_id)),
         }
     }
 
+    /// Unfinished orders at the mechanic's location
     pub async fn get_unfinished_orders(&self) -> Result<Vec<String>, DbError> {
         match self.user.user_type() {
             UserType::Mechanic => {
-                let orders = db_entities::prelude::Order::find()
-                    .filter(order::Column::Finished.eq(false))
-                    .all(&self.db)
+                let orders = self
+                    .storage
+                    .orders(Some(false), self.user.location_id())
                     .await?;
                 Ok(orders
                     .iter()
-                    .map(|m: &order::Model| serde_json::to_string(m).unwrap())
+                    .map(|order| serde_json::to_string(order).unwrap())
                     .collect::<Vec<_>>())
             }
             _ => Err(DbError::Permission),
         }
     }
 
+    /// Finished orders at the technician's location
     pub async fn get_finished_orders(&self) -> Result<Vec<Order>, DbError> {
         match self.user.user_type() {
-            UserType::Technician => {
-                let orders = db_entities::prelude::Order::find()
-                    .filter(order::Column::Finished.eq(true))
-                    .all(&self.db)
-                    .await?;
-                Ok(orders.into_iter().map(|m| m.into()).collect::<Vec<Order>>())
-            }
+            UserType::Technician => Ok(self
+                .storage
+                .orders(Some(true), self.user.location_id())
+                .await?),
             _ => Err(DbError::Permission),
         }
     }
 
+    /// `version` is the [`Order::version`] the mechanic saw, returns the changed order.
+    /// Failed items of the inspection checklist become repair suggestions, see
+    /// [`ShopBackend::get_repair_suggestions`].
     #[named]
-    pub async fn change_inspection_to_repair(&self, order_id: u32) -> Result<(), DbError> {
+    pub async fn change_inspection_to_repair(
+        &self,
+        order_id: u32,
+        version: u32,
+    ) -> Result<Order, DbError> {
         self.login_check(function_name!())?;
         if let UserType::Mechanic = self.user.user_type() {
-            match db_entities::prelude::Order::find_by_id(order_id as i32)
-                .one(&self.db)
-                .await?
-            {
-                Some(order) => match &order.service {
-                    order::Service::Inspection => {
-                        let mut order: order::ActiveModel = order.into();
-                        order.service = Set(order::Service::Repair);
-                        order.update(&self.db).await?;
-                        Ok(())
+            let txn = self.storage.begin().await?;
+            match txn.order_for_update(order_id).await? {
+                Some(order) if !self.works_at(order.location_id()) => Err(DbError::Permission),
+                Some(order) if order.version() != version => {
+                    Err(DbError::Conflict("order", order_id))
+                }
+                Some(order) => match order.service() {
+                    Service::Inspection => {
+                        let Some(order) = txn.update_order(&order.into_repair()).await? else {
+                            return Err(DbError::Conflict("order", order_id));
+                        };
+                        txn.suggest_repairs(order_id).await?;
+                        self.commit_event(
+                            txn,
+                            ShopEvent::ServiceChanged {
+                                order_id,
+                                client_id: order.client_id(),
+                                service: Service::Repair,
+                                version: order.version(),
+                            },
+                        )
+                        .await?;
+                        Ok(order)
                     }
-                    _ => Err(DbError::Other(String::from(
-                        "service to be performed was not inspection",
-                    ))),
+                    _ => Err(DbError::NotInspection(order_id)),
                 },
                 None => Err(DbError::Order(order_id)),
             }
@@ -89,24 +126,98 @@ impl ShopBackend {
         }
     }
 
+    /// `version` is the [`Order::version`] the mechanic saw, returns the closed order.
+    /// Mechanics still clocked on to the order are clocked off. Finished orders stay
+    /// as they were closed.
     #[named]
-    pub async fn close_order(&self, order_id: u32) -> Result<(), DbError> {
+    pub async fn close_order(&self, order_id: u32, version: u32) -> Result<Order, DbError> {
         self.login_check(function_name!())?;
         if let UserType::Mechanic = self.user.user_type() {
-            match db_entities::prelude::Order::find_by_id(order_id as i32)
-                .one(&self.db)
-                .await?
-            {
+            let txn = self.storage.begin().await?;
+            match txn.order_for_update(order_id).await? {
+                Some(order) if !self.works_at(order.location_id()) => Err(DbError::Permission),
+                Some(order) if order.version() != version => {
+                    Err(DbError::Conflict("order", order_id))
+                }
+                Some(order) if order.finished() => Err(DbError::OrderFinished(order_id)),
                 Some(order) => {
-                    let mut order: order::ActiveModel = order.into();
-                    order.finished = Set(true);
-                    order.update(&self.db).await?;
+                    let now = Utc::now();
+                    let Some(order) = txn
+                        .update_order(&order.close(self.user.id(), Some(now)))
+                        .await?
+                    else {
+                        return Err(DbError::Conflict("order", order_id));
+                    };
+                    // Nobody works on a finished order, so its labor stops being billed
+                    txn.end_labor(order_id, now).await?;
+                    self.commit_event(
+                        txn,
+                        ShopEvent::OrderClosed {
+                            order_id,
+                            client_id: order.client_id(),
+                            mechanic_id: self.user.id(),
+                            version: order.version(),
+                        },
+                    )
+                    .await?;
+                    Ok(order)
                 }
-                None => return Err(DbError::Order(order_id)),
+                None => Err(DbError::Order(order_id)),
             }
         } else {
+            Err(DbError::Permission)
+        }
+    }
+
+    /// Orders at every location, or only at `location_id`, for managers
+    #[named]
+    pub async fn get_orders(&self, location_id: Option<u32>) -> Result<Vec<Order>, DbError> {
+        self.login_check(function_name!())?;
+        match self.user.user_type() {
+            UserType::Manager => Ok(self.storage.orders(None, location_id).await?),
+            _ => Err(DbError::Permission),
+        }
+    }
+
+    /// Moves an order to the workshop `location_id`, for managers.
+    /// `version` is the [`Order::version`] the manager saw, returns the moved order.
+    #[named]
+    pub async fn transfer_order(
+        &self,
+        order_id: u32,
+        version: u32,
+        location_id: u32,
+    ) -> Result<Order, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
             return Err(DbError::Permission);
         }
-        Ok(())
+
+        let txn = self.storage.begin().await?;
+        let Some(order) = txn.order_for_update(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if order.version() != version {
+            return Err(DbError::Conflict("order", order_id));
+        }
+        if txn.location(location_id).await?.is_none() {
+            return Err(DbError::Location(location_id));
+        }
+
+        let Some(transferred) = txn.update_order(&order.move_to(location_id)).await? else {
+            return Err(DbError::Conflict("order", order_id));
+        };
+        self.commit_event(
+            txn,
+            ShopEvent::OrderTransferred {
+                order_id,
+                client_id: order.client_id(),
+                from_location_id: order.location_id(),
+                location_id,
+                version: transferred.version(),
+            },
+        )
+        .await?;
+        Ok(transferred)
     }
 }
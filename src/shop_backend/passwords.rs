@@ -0,0 +1,218 @@
+use crate::db_entities::{client, employee, password_reset_token};
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use chrono::{Duration, Utc};
+use function_name::named;
+use rand::RngCore;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+
+/// How long a password reset token stays valid
+const RESET_TOKEN_TTL_MINUTES: i64 = 30;
+
+pub(super) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub(super) fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+impl<S: Storage> ShopBackend<S> {
+    pub fn set_password_policy(&mut self, policy: PasswordPolicy) {
+        self.password_policy = policy;
+    }
+
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+}
+
+impl ShopBackend {
+    /// Changes the password of the logged in user after checking the old one.
+    /// Both passwords are given in plain text, the new one is hashed by the backend.
+    #[named]
+    pub async fn change_password(
+        &self,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), PasswordChangeError> {
+        self.login_check(function_name!())?;
+
+        let stored_hash = match self.user.user_type() {
+            UserType::Client => db_entities::prelude::Client::find_by_id(self.user.id() as i32)
+                .one(self.db())
+                .await?
+                .map(|client| client.password_hash),
+            _ => db_entities::prelude::Employee::find_by_id(self.user.id() as i32)
+                .one(self.db())
+                .await?
+                .map(|employee| employee.password_hash),
+        };
+        let Some(stored_hash) = stored_hash else {
+            return Err(PasswordChangeError::AccountNotFound);
+        };
+
+        if !bcrypt::verify(old_password, &stored_hash)? {
+            return Err(PasswordChangeError::IncorrectPassword);
+        }
+
+        if old_password == new_password {
+            return Err(PasswordChangeError::PasswordUnchanged);
+        }
+
+        self.password_policy.validate(new_password)?;
+        let new_hash = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)?;
+
+        match self.user.user_type() {
+            UserType::Client => set_client_password(self.db(), self.user.id(), new_hash).await?,
+            _ => set_employee_password(self.db(), self.user.id(), new_hash).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Sends a single-use reset token to the owner of `email` through the notifier.
+    /// Succeeds without sending anything if the email is not registered, and also if
+    /// sending fails, so that the result does not reveal which addresses have accounts.
+    pub async fn request_password_reset(&self, email: &str) -> Result<(), PasswordChangeError> {
+        let (client_id, employee_id) = match db_entities::prelude::Client::find()
+            .filter(client::Column::Email.eq(email))
+            .one(self.db())
+            .await?
+        {
+            Some(client) => (Some(client.id), None),
+            None => match db_entities::prelude::Employee::find()
+                .filter(employee::Column::Email.eq(email))
+                .one(self.db())
+                .await?
+            {
+                Some(employee) => (None, Some(employee.id)),
+                None => return Ok(()),
+            },
+        };
+
+        let token = generate_token();
+        let reset_token = password_reset_token::ActiveModel {
+            client_id: Set(client_id),
+            employee_id: Set(employee_id),
+            token_hash: Set(hash_token(&token)),
+            expires_at: Set(Utc::now() + Duration::minutes(RESET_TOKEN_TTL_MINUTES)),
+            used: Set(false),
+            ..Default::default()
+        };
+        reset_token.insert(self.db()).await?;
+
+        // Failing to send is not reported either, it would tell that the address is registered.
+        // The user asks for another token if none arrives.
+        if let Ok(notifier) = self.email_notifier() {
+            let _ = notifier
+                .notify(
+                    email,
+                    "Password reset",
+                    &format!(
+                        "Use the following token to reset your password: {token}\n\
+                         It expires in {RESET_TOKEN_TTL_MINUTES} minutes."
+                    ),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Sets a new plain text password for the owner of a token from [`ShopBackend::request_password_reset`].
+    /// All outstanding tokens of that user are invalidated.
+    pub async fn reset_password(
+        &self,
+        token: &str,
+        new_password: &str,
+    ) -> Result<(), PasswordChangeError> {
+        self.password_policy.validate(new_password)?;
+
+        let Some(reset_token) = db_entities::prelude::PasswordResetToken::find()
+            .filter(password_reset_token::Column::TokenHash.eq(hash_token(token)))
+            .one(self.db())
+            .await?
+        else {
+            return Err(PasswordChangeError::InvalidResetToken);
+        };
+
+        if reset_token.used {
+            return Err(PasswordChangeError::InvalidResetToken);
+        }
+
+        if reset_token.expires_at < Utc::now() {
+            return Err(PasswordChangeError::ResetTokenExpired);
+        }
+
+        let new_hash = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)?;
+
+        // Using up the token in the transaction, so that only one of concurrent resets wins
+        let txn = self.db().begin().await?;
+        let used = db_entities::prelude::PasswordResetToken::update_many()
+            .col_expr(password_reset_token::Column::Used, true.into())
+            .filter(password_reset_token::Column::Id.eq(reset_token.id))
+            .filter(password_reset_token::Column::Used.eq(false))
+            .filter(password_reset_token::Column::ExpiresAt.gte(Utc::now()))
+            .exec(&txn)
+            .await?;
+        if used.rows_affected == 0 {
+            return Err(PasswordChangeError::InvalidResetToken);
+        }
+
+        let owner_filter = match (reset_token.client_id, reset_token.employee_id) {
+            (Some(client_id), _) => {
+                set_client_password(&txn, client_id as u32, new_hash).await?;
+                password_reset_token::Column::ClientId.eq(client_id)
+            }
+            (None, Some(employee_id)) => {
+                set_employee_password(&txn, employee_id as u32, new_hash).await?;
+                password_reset_token::Column::EmployeeId.eq(employee_id)
+            }
+            (None, None) => return Err(PasswordChangeError::InvalidResetToken),
+        };
+        db_entities::prelude::PasswordResetToken::update_many()
+            .col_expr(password_reset_token::Column::Used, true.into())
+            .filter(owner_filter)
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn set_client_password(
+    db: &impl ConnectionTrait,
+    client_id: u32,
+    password_hash: String,
+) -> Result<(), DbErr> {
+    let client = client::ActiveModel {
+        id: Set(client_id as i32),
+        password_hash: Set(password_hash),
+        ..Default::default()
+    };
+    client.update(db).await?;
+    Ok(())
+}
+
+async fn set_employee_password(
+    db: &impl ConnectionTrait,
+    employee_id: u32,
+    password_hash: String,
+) -> Result<(), DbErr> {
+    let employee = employee::ActiveModel {
+        id: Set(employee_id as i32),
+        password_hash: Set(password_hash),
+        ..Default::default()
+    };
+    employee.update(db).await?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::db_entities::payment;
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+
+impl ShopBackend {
+    /// Records a payment towards a report, for technicians and managers
+    #[named]
+    pub async fn record_payment(&self, report_id: u32, amount: u32) -> Result<Payment, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(
+            self.user.user_type(),
+            UserType::Technician | UserType::Manager
+        ) {
+            return Err(DbError::Permission);
+        }
+
+        let Some(report) = self.storage.report(report_id).await? else {
+            return Err(DbError::Report(report_id));
+        };
+        if !self.works_at(report.location_id()) {
+            return Err(DbError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        let payment = payment::ActiveModel {
+            report_id: Set(report_id as i32),
+            client_id: Set(report.client_id() as i32),
+            amount: Set(amount as i32),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(txn.connection())
+        .await?;
+        self.commit_event(
+            txn,
+            ShopEvent::PaymentRecorded {
+                payment_id: payment.id as u32,
+                report_id,
+                client_id: report.client_id(),
+                amount,
+            },
+        )
+        .await?;
+        Ok(payment.into())
+    }
+
+    /// Payments of the logged in client
+    #[named]
+    pub async fn get_client_payments(&self) -> Result<Vec<Payment>, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+
+        let payments = db_entities::prelude::Payment::find()
+            .filter(payment::Column::ClientId.eq(self.user.id() as i32))
+            .all(self.db())
+            .await?;
+        Ok(payments.into_iter().map(|m| m.into()).collect())
+    }
+}
@@ -1,48 +1,235 @@
-use crate::db_entities::prelude::Order;
-use crate::db_entities::{self, report};
-use crate::{DbError, ShopBackend};
-use crate::{Report, UserType};
+use crate::storage::{ClientRepository, OrderRepository, ReportRepository, Storage};
+use crate::{DbError, ShopBackend, ShopEvent};
+use crate::{Order, Report, UserType};
 
+use chrono::{DateTime, Utc};
 use function_name::named;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
 
-impl ShopBackend {
+/// Criteria of [`ShopBackend::search_reports`], fields left as `None` match every report
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReportSearch {
+    pub client_id: Option<u32>,
+    pub order_id: Option<u32>,
+    pub location_id: Option<u32>,
+    /// Part of the make or model of the client's car, ignoring case
+    pub vehicle: Option<String>,
+    pub issued_from: Option<DateTime<Utc>>,
+    pub issued_until: Option<DateTime<Utc>>,
+    pub min_cost: Option<u32>,
+    pub max_cost: Option<u32>,
+    /// Whether the payments recorded for the report cover its cost
+    pub paid: Option<bool>,
+    /// Also match reports replaced by corrections
+    pub include_superseded: bool,
+}
+
+/// One page of [`ShopBackend::search_reports`] results
+#[derive(Clone, Debug)]
+pub struct ReportPage {
+    pub reports: Vec<Report>,
+    /// Starts at 0
+    pub page: u64,
+    pub per_page: u64,
+    /// Reports matching the search on all pages
+    pub total: u64,
+}
+
+impl ReportPage {
+    pub fn pages(&self) -> u64 {
+        self.total.div_ceil(self.per_page)
+    }
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// Reports of their location for technicians and managers, clients only get their own reports
     #[named]
     pub async fn get_report(&self, report_id: u32) -> Result<Report, DbError> {
         self.login_check(function_name!())?;
-        match self.user.user_type() {
-            UserType::Client => match db_entities::prelude::Report::find_by_id(report_id as i32)
-                .one(&self.db)
-                .await
-            {
-                Ok(res) => match res {
-                    Some(m) => Ok::<Report, DbError>(m.into()),
-                    None => Err(DbError::Report(report_id)),
-                },
-                Err(e) => Err(e.into()),
+        if !matches!(
+            self.user.user_type(),
+            UserType::Client | UserType::Technician | UserType::Manager
+        ) {
+            return Err(DbError::Permission);
+        }
+
+        let Some(report) = self.storage.report(report_id).await? else {
+            return Err(DbError::Report(report_id));
+        };
+        let allowed = match self.user.user_type() {
+            UserType::Client => report.client_id() == self.user.id(),
+            UserType::Technician | UserType::Manager => self.works_at(report.location_id()),
+            UserType::Mechanic | UserType::NotLoggedIn => false,
+        };
+        if !allowed {
+            return Err(DbError::Permission);
+        }
+        Ok(report)
+    }
+
+    /// Issues the report of a finished order, for technicians. Every order has one report,
+    /// mistakes are fixed with [`ShopBackend::correct_report`].
+    #[named]
+    pub async fn register_report(&self, order_id: u32, cost: u32) -> Result<Report, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Technician) {
+            return Err(DbError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        let order = self.reportable_order(&txn, order_id).await?;
+        let Some(report) = txn
+            .insert_report(order.client_id(), order_id, order.location_id(), cost, 1)
+            .await?
+        else {
+            return Err(DbError::Conflict("order", order_id));
+        };
+        self.commit_event(
+            txn,
+            ShopEvent::ReportRegistered {
+                report_id: report.id(),
+                order_id,
+                client_id: order.client_id(),
+                cost,
             },
-            _ => todo!(),
+        )
+        .await?;
+        Ok(report)
+    }
+
+    /// Finished order of a client that has no report yet, locked so that it is not
+    /// changed while the report is written
+    pub(super) async fn reportable_order(
+        &self,
+        txn: &S::Transaction,
+        order_id: u32,
+    ) -> Result<Order, DbError> {
+        let Some(order) = txn.order_for_update(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id()) {
+            return Err(DbError::Permission);
+        }
+
+        if !order.finished() {
+            return Err(DbError::OrderNotFinished(order_id));
         }
+
+        match txn.client(order.client_id()).await? {
+            Some(client) if !client.deleted => {}
+            _ => return Err(DbError::Client(order.client_id())),
+        }
+
+        if let Some(report) = txn.current_report(order_id).await? {
+            return Err(DbError::ReportAlreadyIssued {
+                order_id,
+                report_id: report.id(),
+            });
+        }
+        Ok(order)
+    }
+
+    /// Issues a report replacing `report_id`, for technicians. The replaced report is kept
+    /// and points to the new one, like a credit note. The labor billed on the replaced report
+    /// is billed on the new one too. `version` is the [`Report::version`] the technician saw,
+    /// returns the new report.
+    #[named]
+    pub async fn correct_report(
+        &self,
+        report_id: u32,
+        version: u32,
+        cost: u32,
+    ) -> Result<Report, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Technician) {
+            return Err(DbError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        let Some(report) = txn.report(report_id).await? else {
+            return Err(DbError::Report(report_id));
+        };
+        if !self.works_at(report.location_id()) {
+            return Err(DbError::Permission);
+        }
+
+        // Superseded reports were corrected by someone else already
+        if report.version() != version || report.superseded_by().is_some() {
+            return Err(DbError::Conflict("report", report_id));
+        }
+
+        let Some(corrected) = txn
+            .insert_report(
+                report.client_id(),
+                report.order_id(),
+                report.location_id(),
+                cost,
+                report.revision() + 1,
+            )
+            .await?
+        else {
+            return Err(DbError::Conflict("report", report_id));
+        };
+        txn.copy_labor_lines(report_id, corrected.id()).await?;
+        if txn
+            .supersede_report(&report, corrected.id())
+            .await?
+            .is_none()
+        {
+            return Err(DbError::Conflict("report", report_id));
+        }
+
+        self.commit_event(
+            txn,
+            ShopEvent::ReportCorrected {
+                report_id: corrected.id(),
+                superseded_report_id: report_id,
+                order_id: report.order_id(),
+                client_id: report.client_id(),
+                cost,
+            },
+        )
+        .await?;
+        Ok(corrected)
     }
 
+    /// Reports matching `search` ordered by ID, `page` counts from 0. Technicians and
+    /// managers search the reports of their location, clients only their own.
     #[named]
-    pub async fn register_report(&self, order_id: u32, cost: u32) -> Result<(), DbError> {
+    pub async fn search_reports(
+        &self,
+        search: &ReportSearch,
+        page: u64,
+        per_page: u64,
+    ) -> Result<ReportPage, DbError> {
         self.login_check(function_name!())?;
-        match self.user.user_type() {
-            UserType::Technician => match Order::find_by_id(order_id as i32).one(&self.db).await? {
-                Some(order) => {
-                    let report = report::ActiveModel {
-                        client_id: Set(order.client_id),
-                        order_id: Set(order_id as i32),
-                        cost: Set(cost as i32),
-                        ..Default::default()
-                    };
-                    report.insert(&self.db).await?;
-                    Ok(())
-                }
-                None => Err(DbError::Order(order_id)),
+        let mut search = search.clone();
+        let visible = match self.user.user_type() {
+            UserType::Client => narrow(&mut search.client_id, self.user.id()),
+            UserType::Technician | UserType::Manager => match self.user.location_id() {
+                Some(location_id) => narrow(&mut search.location_id, location_id),
+                None => true,
             },
-            _ => Err(DbError::Permission),
+            _ => return Err(DbError::Permission),
+        };
+        if per_page == 0 {
+            return Err(DbError::EmptyPage);
         }
+
+        let (reports, total) = if visible {
+            self.storage.search_reports(&search, page, per_page).await?
+        } else {
+            (Vec::new(), 0)
+        };
+        Ok(ReportPage {
+            reports,
+            page,
+            per_page,
+            total,
+        })
     }
 }
+
+/// Restricts a search criterion to `value`, returns `false` if it asked for something else
+fn narrow(criterion: &mut Option<u32>, value: u32) -> bool {
+    *criterion.get_or_insert(value) == value
+}
@@ -0,0 +1,40 @@
+use crate::storage::Storage;
+use crate::*;
+
+use sea_orm::DbErr;
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(crate) const TWO_FACTOR_REQUIRED: &str = "two_factor_required";
+pub(crate) const SIGNING_KEY: &str = "signing_key";
+pub(crate) const UNVERIFIED_CLIENT_RESTRICTIONS: &str = "unverified_client_restrictions";
+pub(crate) const LABOR_RATE: &str = "labor_rate";
+
+impl<S: Storage> ShopBackend<S> {
+    pub(crate) async fn setting<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, DbErr> {
+        match self.storage.setting_value(key).await? {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| DbErr::Json(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) async fn set_setting<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), DbErr> {
+        let value = serde_json::to_value(value).map_err(|e| DbErr::Json(e.to_string()))?;
+        self.storage.set_setting_value(key, value).await
+    }
+
+    /// Stores `value` unless `key` already has one, returns whether it did
+    pub(crate) async fn insert_setting<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+    ) -> Result<bool, DbErr> {
+        let value = serde_json::to_value(value).map_err(|e| DbErr::Json(e.to_string()))?;
+        self.storage.insert_setting_value(key, value).await
+    }
+}
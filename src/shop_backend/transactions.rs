@@ -0,0 +1,63 @@
+use crate::storage::{Storage, Transaction};
+use crate::*;
+
+use sea_orm::DbErr;
+
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+impl<S: Storage> ShopBackend<S> {
+    /// Runs `operations` on a backend acting as the logged in user and commits all of their
+    /// changes together. If `operations` fail nothing is changed. Subscribers and clients only
+    /// hear about the changes after the commit. Logging in or out inside does not affect this
+    /// backend.
+    ///
+    /// ```no_run
+    /// # use car_repair_shop_backend::*;
+    /// # async fn example(backend: &ShopBackend) -> Result<Order, DbError> {
+    /// backend
+    ///     .transaction(|shop| {
+    ///         Box::pin(async move {
+    ///             shop.register_car(1, "VW", "Golf").await?;
+    ///             shop.register_order(1, 1, &Service::Inspection, None).await
+    ///         })
+    ///     })
+    ///     .await
+    /// # }
+    /// ```
+    pub async fn transaction<T, E, F>(&self, operations: F) -> Result<T, E>
+    where
+        F: for<'a> FnOnce(
+            &'a ShopBackend<S::Transaction>,
+        ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'a>>,
+        E: From<DbErr>,
+    {
+        let backend = ShopBackend {
+            storage: self.storage.begin().await?,
+            user: self.user.clone(),
+            notifier: self.notifier.clone(),
+            sms_notifier: self.sms_notifier.clone(),
+            password_policy: self.password_policy.clone(),
+            pending_employee: None,
+            second_factor_failures: 0,
+            signing_key: self.signing_key.clone(),
+            http_client: self.http_client.clone(),
+            events: self.events.clone(),
+            deferred_events: Some(Arc::new(Mutex::new(Vec::new()))),
+            tenant: self.tenant.clone(),
+            blob_store: self.blob_store.clone(),
+        };
+
+        let result = operations(&backend).await?;
+        backend.storage.commit().await?;
+
+        if let Some(deferred) = backend.deferred_events {
+            for event in mem::take(&mut *deferred.lock().unwrap()) {
+                self.publish(event);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,136 @@
+use crate::db_entities::{client, order, trouble_code};
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+impl ShopBackend {
+    /// Records a trouble code read off the car of an unfinished order, for mechanics of the
+    /// order's location. `freeze_frame` holds the sensor values the car stored with the code,
+    /// `cleared` whether the mechanic cleared it from the car's memory.
+    #[named]
+    pub async fn record_trouble_code(
+        &self,
+        order_id: u32,
+        code: &str,
+        freeze_frame: Option<serde_json::Value>,
+        cleared: bool,
+    ) -> Result<TroubleCode, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Mechanic) {
+            return Err(DbError::Permission);
+        }
+        let Some(code) = normalize_trouble_code(code) else {
+            return Err(DbError::InvalidTroubleCode(code.trim().to_owned()));
+        };
+        let order = self.visible_order(order_id).await?;
+        if order.finished() {
+            return Err(DbError::OrderFinished(order_id));
+        }
+
+        let trouble_code = trouble_code::ActiveModel {
+            order_id: Set(order_id as i32),
+            code: Set(code),
+            freeze_frame: Set(freeze_frame),
+            cleared: Set(cleared),
+            mechanic_id: Set(self.user.id() as i32),
+            recorded_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(self.db())
+        .await?;
+        Ok(trouble_code.into())
+    }
+
+    /// Marks a trouble code as cleared from the car's memory, for mechanics of the order's
+    /// location while the order is unfinished
+    #[named]
+    pub async fn clear_trouble_code(&self, id: u32) -> Result<TroubleCode, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Mechanic) {
+            return Err(DbError::Permission);
+        }
+        let Some(trouble_code) = db_entities::prelude::TroubleCode::find_by_id(id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(DbError::TroubleCode(id));
+        };
+        let order = self.visible_order(trouble_code.order_id as u32).await?;
+        if order.finished() {
+            return Err(DbError::OrderFinished(order.id()));
+        }
+
+        let mut trouble_code: trouble_code::ActiveModel = trouble_code.into();
+        trouble_code.cleared = Set(true);
+        Ok(trouble_code.update(self.db()).await?.into())
+    }
+
+    /// Trouble codes read off the car of an order, in the order they were recorded,
+    /// for whoever may see the order
+    #[named]
+    pub async fn get_trouble_codes(&self, order_id: u32) -> Result<Vec<TroubleCode>, DbError> {
+        self.login_check(function_name!())?;
+        self.visible_order(order_id).await?;
+
+        Ok(db_entities::prelude::TroubleCode::find()
+            .filter(trouble_code::Column::OrderId.eq(order_id as i32))
+            .order_by_asc(trouble_code::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    /// Every time `code` was read off a car, newest first, with the car it was read from.
+    /// For employees, who only find codes of orders at their location if they have one.
+    #[named]
+    pub async fn search_trouble_codes(&self, code: &str) -> Result<Vec<TroubleCodeMatch>, DbError> {
+        self.login_check(function_name!())?;
+        if matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+        let Some(code) = normalize_trouble_code(code) else {
+            return Err(DbError::InvalidTroubleCode(code.trim().to_owned()));
+        };
+
+        let mut query = db_entities::prelude::TroubleCode::find()
+            .find_also_related(order::Entity)
+            .filter(trouble_code::Column::Code.eq(code));
+        if let Some(location_id) = self.user.location_id() {
+            query = query.filter(order::Column::LocationId.eq(location_id as i32));
+        }
+        let found = query
+            .order_by_desc(trouble_code::Column::RecordedAt)
+            .order_by_desc(trouble_code::Column::Id)
+            .all(self.db())
+            .await?;
+
+        let mut cars = HashMap::new();
+        let mut matches = Vec::new();
+        for (trouble_code, order) in found {
+            let Some(order) = order else {
+                continue;
+            };
+            let client_id = order.client_id as u32;
+            if let Entry::Vacant(entry) = cars.entry(client_id) {
+                let car = client::Entity::find_by_id(client_id as i32)
+                    .one(self.db())
+                    .await?
+                    .and_then(|client| client.car);
+                entry.insert(car);
+            }
+            matches.push(TroubleCodeMatch {
+                trouble_code: trouble_code.into(),
+                client_id,
+                car: cars[&client_id].clone(),
+            });
+        }
+        Ok(matches)
+    }
+}
@@ -0,0 +1,284 @@
+use super::passwords::hash_token;
+use super::settings::TWO_FACTOR_REQUIRED;
+
+use crate::db_entities::{employee, recovery_code};
+use crate::totp::{self, TotpEnrollment};
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use rand::{distributions::Alphanumeric, Rng};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, ModelTrait,
+    QueryFilter, Set, TransactionTrait,
+};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LEN: usize = 10;
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn generate_recovery_code() -> String {
+    let code: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RECOVERY_CODE_LEN)
+        .map(|c| (c as char).to_ascii_lowercase())
+        .collect();
+    format!(
+        "{}-{}",
+        &code[..RECOVERY_CODE_LEN / 2],
+        &code[RECOVERY_CODE_LEN / 2..]
+    )
+}
+
+impl ShopBackend {
+    /// Employee that two-factor settings apply to: the logged in employee,
+    /// or one halfway through logging in who still has to enroll
+    fn two_factor_subject(&self, func_name: &str) -> Result<u32, TwoFactorError> {
+        match self.user.user_type() {
+            UserType::Client => Err(TwoFactorError::Permission),
+            UserType::NotLoggedIn => self
+                .pending_employee
+                .ok_or_else(|| NotLoggedInError(func_name.to_string()).into()),
+            _ => Ok(self.user.id()),
+        }
+    }
+
+    async fn find_employee(&self, id: u32) -> Result<employee::Model, DbErr> {
+        db_entities::prelude::Employee::find_by_id(id as i32)
+            .one(self.db())
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("employee {id}")))
+    }
+
+    /// Starts TOTP enrollment by generating a new secret. Enrollment is finished by
+    /// confirming the first code from the authenticator app with [`ShopBackend::confirm_totp_enrollment`].
+    #[named]
+    pub async fn begin_totp_enrollment(&self) -> Result<TotpEnrollment, TwoFactorError> {
+        let id = self.two_factor_subject(function_name!())?;
+        let employee = self.find_employee(id).await?;
+
+        if employee.totp_enabled {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        let secret = totp::generate_secret();
+        let account = employee
+            .email
+            .clone()
+            .unwrap_or_else(|| format!("employee {id}"));
+
+        let mut employee: employee::ActiveModel = employee.into();
+        employee.totp_secret = Set(Some(secret.clone()));
+        employee.update(self.db()).await?;
+
+        Ok(TotpEnrollment::new(&secret, &account))
+    }
+
+    /// Enables two-factor authentication if `code` matches the secret from
+    /// [`ShopBackend::begin_totp_enrollment`] and returns single-use recovery codes.
+    /// If the employee was enrolling while logging in, they are logged in afterwards.
+    #[named]
+    pub async fn confirm_totp_enrollment(
+        &mut self,
+        code: &str,
+    ) -> Result<Vec<String>, TwoFactorError> {
+        let id = self.two_factor_subject(function_name!())?;
+        let employee = self.find_employee(id).await?;
+
+        if employee.totp_enabled {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        let Some(secret) = &employee.totp_secret else {
+            return Err(TwoFactorError::NotEnrolled);
+        };
+
+        let Some(step) = totp::verify(secret, code, totp::current_step(Utc::now().timestamp()))
+        else {
+            return Err(TwoFactorError::InvalidCode);
+        };
+
+        let txn = self.db().begin().await?;
+        let name = employee.name.clone();
+        let role = employee.role;
+        let location_id = employee.location_id.map(|id| id as u32);
+        let mut employee: employee::ActiveModel = employee.into();
+        employee.totp_enabled = Set(true);
+        employee.totp_last_step = Set(Some(step));
+        employee.update(&txn).await?;
+        let codes = replace_recovery_codes(&txn, id).await?;
+        txn.commit().await?;
+
+        if self.pending_employee == Some(id) {
+            self.pending_employee = None;
+            self.log_in(User::logged_in(id, &name, role.into()).at_location(location_id));
+        }
+
+        Ok(codes)
+    }
+
+    /// Replaces all recovery codes of the logged in employee, `code` is a current TOTP code
+    #[named]
+    pub async fn regenerate_recovery_codes(
+        &self,
+        code: &str,
+    ) -> Result<Vec<String>, TwoFactorError> {
+        self.login_check(function_name!())?;
+        let id = self.two_factor_subject(function_name!())?;
+        let employee = self.find_employee(id).await?;
+
+        if !employee.totp_enabled {
+            return Err(TwoFactorError::NotEnrolled);
+        }
+
+        if !self.check_totp(&employee, code).await? {
+            return Err(TwoFactorError::InvalidCode);
+        }
+
+        let txn = self.db().begin().await?;
+        let codes = replace_recovery_codes(&txn, id).await?;
+        txn.commit().await?;
+
+        Ok(codes)
+    }
+
+    /// Turns two-factor authentication off for the logged in employee,
+    /// `code` is a TOTP or recovery code
+    #[named]
+    pub async fn disable_totp(&self, code: &str) -> Result<(), TwoFactorError> {
+        self.login_check(function_name!())?;
+        let id = self.two_factor_subject(function_name!())?;
+        let employee = self.find_employee(id).await?;
+
+        if !employee.totp_enabled {
+            return Err(TwoFactorError::NotEnrolled);
+        }
+
+        if self.setting(TWO_FACTOR_REQUIRED).await?.unwrap_or(false) {
+            return Err(TwoFactorError::RequiredByPolicy);
+        }
+
+        if !self.check_second_factor(&employee, code).await? {
+            return Err(TwoFactorError::InvalidCode);
+        }
+
+        let txn = self.db().begin().await?;
+        db_entities::prelude::RecoveryCode::delete_many()
+            .filter(recovery_code::Column::EmployeeId.eq(id as i32))
+            .exec(&txn)
+            .await?;
+        let mut employee: employee::ActiveModel = employee.into();
+        employee.totp_enabled = Set(false);
+        employee.totp_secret = Set(None);
+        employee.totp_last_step = Set(None);
+        employee.update(&txn).await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    #[named]
+    pub async fn two_factor_required(&self) -> Result<bool, DbError> {
+        self.login_check(function_name!())?;
+        Ok(self.setting(TWO_FACTOR_REQUIRED).await?.unwrap_or(false))
+    }
+
+    /// Makes two-factor authentication mandatory for all employees, only for managers
+    #[named]
+    pub async fn set_two_factor_required(&self, required: bool) -> Result<(), TwoFactorError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(TwoFactorError::Permission);
+        }
+
+        self.set_setting(TWO_FACTOR_REQUIRED, &required).await?;
+        Ok(())
+    }
+
+    /// Checks a TOTP code, rejecting codes for time steps that were already used
+    async fn check_totp(&self, employee: &employee::Model, code: &str) -> Result<bool, DbErr> {
+        let Some(secret) = &employee.totp_secret else {
+            return Ok(false);
+        };
+        let Some(step) = totp::verify(secret, code, totp::current_step(Utc::now().timestamp()))
+        else {
+            return Ok(false);
+        };
+
+        // Only one of concurrent logins with the same code moves the last step forward
+        let used = employee::Entity::update_many()
+            .col_expr(employee::Column::TotpLastStep, Expr::value(step))
+            .filter(employee::Column::Id.eq(employee.id))
+            .filter(
+                Condition::any()
+                    .add(employee::Column::TotpLastStep.is_null())
+                    .add(employee::Column::TotpLastStep.lt(step)),
+            )
+            .exec(self.db())
+            .await?;
+        Ok(used.rows_affected == 1)
+    }
+
+    /// Accepts either a TOTP code or an unused recovery code, which is then used up
+    pub(super) async fn check_second_factor(
+        &self,
+        employee: &employee::Model,
+        code: &str,
+    ) -> Result<bool, DbErr> {
+        if self.check_totp(employee, code).await? {
+            return Ok(true);
+        }
+
+        match employee
+            .find_related(db_entities::prelude::RecoveryCode)
+            .filter(recovery_code::Column::CodeHash.eq(hash_token(&normalize_recovery_code(code))))
+            .filter(recovery_code::Column::Used.eq(false))
+            .one(self.db())
+            .await?
+        {
+            Some(recovery_code) => {
+                let used = recovery_code::Entity::update_many()
+                    .col_expr(recovery_code::Column::Used, Expr::value(true))
+                    .filter(recovery_code::Column::Id.eq(recovery_code.id))
+                    .filter(recovery_code::Column::Used.eq(false))
+                    .exec(self.db())
+                    .await?;
+                Ok(used.rows_affected == 1)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+async fn replace_recovery_codes(
+    db: &impl ConnectionTrait,
+    employee_id: u32,
+) -> Result<Vec<String>, DbErr> {
+    db_entities::prelude::RecoveryCode::delete_many()
+        .filter(recovery_code::Column::EmployeeId.eq(employee_id as i32))
+        .exec(db)
+        .await?;
+
+    let codes = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect::<Vec<_>>();
+    db_entities::prelude::RecoveryCode::insert_many(codes.iter().map(|code| {
+        recovery_code::ActiveModel {
+            employee_id: Set(employee_id as i32),
+            code_hash: Set(hash_token(&normalize_recovery_code(code))),
+            used: Set(false),
+            ..Default::default()
+        }
+    }))
+    .exec(db)
+    .await?;
+
+    Ok(codes)
+}
@@ -0,0 +1,163 @@
+use super::locations::take_stock;
+use crate::db_entities::{mileage_reading, order_part};
+use crate::storage::{ClientRepository, OrderRepository, ReportRepository, Storage};
+use crate::vehicle_history::VehicleHistory;
+use crate::{UserType, *};
+
+use chrono::Utc;
+use function_name::named;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+impl ShopBackend {
+    /// Records the odometer of the client's car when it is checked in for an order, for
+    /// employees of the order's location. Mileage lower than the last reading needs a
+    /// `reason`, e.g. a replaced odometer, and is flagged in the history.
+    #[named]
+    pub async fn record_mileage(
+        &self,
+        order_id: u32,
+        mileage: u32,
+        reason: Option<&str>,
+    ) -> Result<MileageReading, DbError> {
+        self.login_check(function_name!())?;
+        if matches!(self.user.user_type(), UserType::Client) {
+            return Err(DbError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        let Some(order) = txn.order_for_update(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id()) {
+            return Err(DbError::Permission);
+        }
+        let client_id = order.client_id();
+        match txn.client(client_id).await? {
+            Some(client) if client.deleted => return Err(DbError::Client(client_id)),
+            Some(client) if client.car.is_none() => {
+                return Err(DbError::NoCarRegistered(client_id))
+            }
+            Some(_) => {}
+            None => return Err(DbError::Client(client_id)),
+        }
+
+        let previous = db_entities::prelude::MileageReading::find()
+            .filter(mileage_reading::Column::ClientId.eq(client_id as i32))
+            .order_by_desc(mileage_reading::Column::RecordedAt)
+            .order_by_desc(mileage_reading::Column::Id)
+            .one(txn.connection())
+            .await?
+            .map(|reading| reading.mileage as u32);
+        let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+        let reason = match previous {
+            Some(previous) if mileage < previous => match reason {
+                Some(reason) => Some(reason.to_owned()),
+                None => {
+                    return Err(DbError::MileageDecreased {
+                        client_id,
+                        previous,
+                        mileage,
+                    })
+                }
+            },
+            // Only decreases are flagged
+            _ => None,
+        };
+
+        let reading = mileage_reading::ActiveModel {
+            client_id: Set(client_id as i32),
+            order_id: Set(order_id as i32),
+            mileage: Set(mileage as i32),
+            recorded_at: Set(Utc::now()),
+            reason: Set(reason),
+            ..Default::default()
+        }
+        .insert(txn.connection())
+        .await?;
+        txn.commit().await?;
+        Ok(reading.into())
+    }
+
+    /// Takes parts for an unfinished order from the stock of its location, for mechanics
+    #[named]
+    pub async fn use_part(
+        &self,
+        order_id: u32,
+        part_number: &str,
+        quantity: u32,
+    ) -> Result<PartUsage, DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Mechanic) {
+            return Err(DbError::Permission);
+        }
+
+        let txn = self.storage.begin().await?;
+        let Some(order) = txn.order_for_update(order_id).await? else {
+            return Err(DbError::Order(order_id));
+        };
+        if !self.works_at(order.location_id()) {
+            return Err(DbError::Permission);
+        }
+        if order.finished() {
+            return Err(DbError::OrderFinished(order_id));
+        }
+
+        let item = take_stock(txn.connection(), order.location_id(), part_number, quantity).await?;
+        let part = order_part::ActiveModel {
+            order_id: Set(order_id as i32),
+            part_number: Set(part_number.to_owned()),
+            name: Set(item.name().to_owned()),
+            quantity: Set(quantity as i32),
+            ..Default::default()
+        }
+        .insert(txn.connection())
+        .await?;
+        txn.commit().await?;
+        Ok(part.into())
+    }
+
+    /// Orders, reports, parts and mileage of a client's car. Clients see their own car,
+    /// employees every car, as cars are brought to any location.
+    #[named]
+    pub async fn get_vehicle_history(&self, client_id: u32) -> Result<VehicleHistory, DbError> {
+        self.login_check(function_name!())?;
+        if matches!(self.user.user_type(), UserType::Client) && self.user.id() != client_id {
+            return Err(DbError::Permission);
+        }
+
+        self.vehicle_history(client_id).await
+    }
+
+    /// History of a client's car without checking who asks
+    pub(super) async fn vehicle_history(&self, client_id: u32) -> Result<VehicleHistory, DbError> {
+        let car = match self.storage.client(client_id).await? {
+            Some(client) if !client.deleted => {
+                client.car.ok_or(DbError::NoCarRegistered(client_id))?
+            }
+            _ => return Err(DbError::Client(client_id)),
+        };
+        let orders = self.storage.client_orders(client_id).await?;
+        let reports = self.storage.client_reports(client_id).await?;
+        let parts = db_entities::prelude::OrderPart::find()
+            .filter(order_part::Column::OrderId.is_in(orders.iter().map(|order| order.id() as i32)))
+            .order_by_asc(order_part::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect();
+        let mileage = db_entities::prelude::MileageReading::find()
+            .filter(mileage_reading::Column::ClientId.eq(client_id as i32))
+            .order_by_asc(mileage_reading::Column::RecordedAt)
+            .order_by_asc(mileage_reading::Column::Id)
+            .all(self.db())
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect();
+
+        Ok(VehicleHistory::new(
+            client_id, car, orders, reports, parts, mileage,
+        ))
+    }
+}
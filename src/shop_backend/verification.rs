@@ -0,0 +1,225 @@
+use super::settings::{SIGNING_KEY, UNVERIFIED_CLIENT_RESTRICTIONS};
+
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use chrono::{DateTime, Duration, Utc};
+use function_name::named;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use std::env;
+
+const VERIFICATION_TOKEN_TTL_HOURS: i64 = 24;
+/// Minimum time between two verification emails sent to the same client
+const VERIFICATION_RESEND_INTERVAL_SECONDS: i64 = 60;
+
+/// What clients cannot do before verifying their email address
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnverifiedClientRestrictions {
+    pub block_orders: bool,
+    pub block_car_registration: bool,
+}
+
+impl Default for UnverifiedClientRestrictions {
+    fn default() -> Self {
+        UnverifiedClientRestrictions {
+            block_orders: true,
+            block_car_registration: false,
+        }
+    }
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// Key for signing tokens, taken from SHOP_SECRET_KEY environment variable
+    /// or generated once and stored in the database
+    pub(super) async fn load_signing_key(&self) -> Result<Vec<u8>, DbErr> {
+        if let Ok(key) = env::var("SHOP_SECRET_KEY") {
+            return Ok(key.into_bytes());
+        }
+
+        if let Some(key) = self.stored_signing_key().await? {
+            return Ok(key);
+        }
+
+        let mut key = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        let encoded = hex::encode(&key);
+        // Backends starting at the same time all use whichever key was stored first
+        if !self.insert_setting(SIGNING_KEY, &encoded).await? {
+            if let Some(stored) = self.stored_signing_key().await? {
+                return Ok(stored);
+            }
+            // Replaces a stored key that cannot be decoded
+            self.set_setting(SIGNING_KEY, &encoded).await?;
+        }
+        Ok(key)
+    }
+
+    async fn stored_signing_key(&self) -> Result<Option<Vec<u8>>, DbErr> {
+        Ok(self
+            .setting::<String>(SIGNING_KEY)
+            .await?
+            .and_then(|key| hex::decode(key).ok()))
+    }
+
+    fn sign(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    /// Token binding a client to an email address, so that it stops working if the address changes
+    fn verification_token(&self, client_id: u32, email: &str, expires_at: DateTime<Utc>) -> String {
+        let payload = format!("{client_id}.{}", expires_at.timestamp());
+        let signature = self
+            .sign(&format!("{payload}.{email}"))
+            .finalize()
+            .into_bytes();
+        format!("{payload}.{}", hex::encode(signature))
+    }
+
+    pub(super) async fn send_verification_email(
+        &self,
+        client_id: u32,
+        email: &str,
+    ) -> Result<(), NotifyError> {
+        let token = self.verification_token(
+            client_id,
+            email,
+            Utc::now() + Duration::hours(VERIFICATION_TOKEN_TTL_HOURS),
+        );
+
+        self.email_notifier()?
+            .notify(
+                email,
+                "Verify your email address",
+                &format!(
+                    "Use the following token to verify your email address: {token}\n\
+                     It expires in {VERIFICATION_TOKEN_TTL_HOURS} hours."
+                ),
+            )
+            .await
+    }
+
+    /// Marks the email address a token was sent to as verified
+    pub async fn verify_email(&self, token: &str) -> Result<(), VerificationError> {
+        let mut parts = token.trim().splitn(3, '.');
+        let (Some(client_id), Some(expires_at), Some(signature)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(VerificationError::InvalidToken);
+        };
+
+        let (Ok(client_id), Ok(expires_at), Ok(signature)) = (
+            client_id.parse::<u32>(),
+            expires_at.parse::<i64>(),
+            hex::decode(signature),
+        ) else {
+            return Err(VerificationError::InvalidToken);
+        };
+
+        let Some(mut client) = self.storage.client(client_id).await? else {
+            return Err(VerificationError::InvalidToken);
+        };
+
+        self.sign(&format!("{client_id}.{expires_at}.{}", client.email))
+            .verify_slice(&signature)
+            .map_err(|_| VerificationError::InvalidToken)?;
+
+        if expires_at < Utc::now().timestamp() {
+            return Err(VerificationError::TokenExpired);
+        }
+
+        if client.email_verified {
+            return Err(VerificationError::AlreadyVerified);
+        }
+
+        client.email_verified = true;
+        self.storage.update_client(&client).await?;
+        Ok(())
+    }
+
+    /// Sends the verification email to the logged in client again
+    #[named]
+    pub async fn resend_verification_email(&self) -> Result<(), VerificationError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Client) {
+            return Err(VerificationError::Permission);
+        }
+
+        let Some(mut client) = self.storage.client(self.user.id()).await? else {
+            return Err(VerificationError::Client(self.user.id()));
+        };
+
+        if client.email_verified {
+            return Err(VerificationError::AlreadyVerified);
+        }
+
+        if let Some(sent_at) = client.verification_sent_at {
+            let wait = VERIFICATION_RESEND_INTERVAL_SECONDS - (Utc::now() - sent_at).num_seconds();
+            if wait > 0 {
+                return Err(VerificationError::TooManyRequests(wait));
+            }
+        }
+
+        self.send_verification_email(self.user.id(), &client.email)
+            .await?;
+
+        client.verification_sent_at = Some(Utc::now());
+        self.storage.update_client(&client).await?;
+        Ok(())
+    }
+
+    pub async fn unverified_client_restrictions(
+        &self,
+    ) -> Result<UnverifiedClientRestrictions, DbError> {
+        Ok(self
+            .setting(UNVERIFIED_CLIENT_RESTRICTIONS)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Only for managers
+    #[named]
+    pub async fn set_unverified_client_restrictions(
+        &self,
+        restrictions: UnverifiedClientRestrictions,
+    ) -> Result<(), DbError> {
+        self.login_check(function_name!())?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(DbError::Permission);
+        }
+
+        self.set_setting(UNVERIFIED_CLIENT_RESTRICTIONS, &restrictions)
+            .await?;
+        Ok(())
+    }
+
+    /// Fails if the logged in user is a client with an unverified email address
+    /// and `restricted` selects a restriction that is turned on
+    pub(super) async fn unverified_check(
+        &self,
+        restricted: impl FnOnce(&UnverifiedClientRestrictions) -> bool,
+    ) -> Result<(), DbError> {
+        if !matches!(self.user.user_type(), UserType::Client) {
+            return Ok(());
+        }
+
+        let verified = self
+            .storage
+            .client(self.user.id())
+            .await?
+            .is_some_and(|client| client.email_verified);
+
+        if !verified && restricted(&self.unverified_client_restrictions().await?) {
+            return Err(DbError::EmailNotVerified);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use crate::db_entities::{webhook_delivery, webhook_subscription};
+use crate::storage::Storage;
+use crate::{UserType, *};
+
+use chrono::{Duration, Utc};
+use function_name::named;
+use reqwest::{header::CONTENT_TYPE, Url};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+
+/// Failed deliveries are given up on after this many attempts, they can still be replayed
+const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+/// Delay before the first retry, doubled after every failed attempt
+const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+/// How long a delivery being sent is hidden from other dispatchers
+const DELIVERY_LEASE_SECONDS: i64 = 300;
+/// Subscribers that do not answer within this time are treated as failed
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+impl ShopBackend {
+    fn webhook_permission_check(&self, func_name: &str) -> Result<(), WebhookError> {
+        self.login_check(func_name)?;
+        if !matches!(self.user.user_type(), UserType::Manager) {
+            return Err(WebhookError::Permission);
+        }
+        Ok(())
+    }
+
+    /// Registers an endpoint that will receive `event_types`, only for managers.
+    /// Requests are signed with `secret`, see [`webhook_signature`].
+    #[named]
+    pub async fn create_webhook_subscription(
+        &self,
+        url: &str,
+        secret: &str,
+        event_types: &[WebhookEventType],
+    ) -> Result<WebhookSubscription, WebhookError> {
+        self.webhook_permission_check(function_name!())?;
+
+        if !Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+            return Err(WebhookError::InvalidUrl(url.to_owned()));
+        }
+
+        if secret.is_empty() {
+            return Err(WebhookError::EmptySecret);
+        }
+
+        let event_types = event_types
+            .iter()
+            .fold(Vec::new(), |mut unique, event_type| {
+                if !unique.contains(event_type) {
+                    unique.push(*event_type);
+                }
+                unique
+            });
+        if event_types.is_empty() {
+            return Err(WebhookError::NoEventTypes);
+        }
+
+        let subscription = webhook_subscription::ActiveModel {
+            url: Set(url.to_owned()),
+            secret: Set(secret.to_owned()),
+            event_types: Set(webhook_subscription::EventTypes(event_types)),
+            active: Set(true),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(self.db())
+        .await?;
+
+        Ok(subscription.into())
+    }
+
+    /// Only for managers
+    #[named]
+    pub async fn get_webhook_subscriptions(
+        &self,
+    ) -> Result<Vec<WebhookSubscription>, WebhookError> {
+        self.webhook_permission_check(function_name!())?;
+
+        let subscriptions = db_entities::prelude::WebhookSubscription::find()
+            .order_by_asc(webhook_subscription::Column::Id)
+            .all(self.db())
+            .await?;
+        Ok(subscriptions.into_iter().map(|m| m.into()).collect())
+    }
+
+    /// Pauses or resumes sending events to a subscription, only for managers
+    #[named]
+    pub async fn set_webhook_subscription_active(
+        &self,
+        subscription_id: u32,
+        active: bool,
+    ) -> Result<(), WebhookError> {
+        self.webhook_permission_check(function_name!())?;
+
+        let Some(subscription) =
+            db_entities::prelude::WebhookSubscription::find_by_id(subscription_id as i32)
+                .one(self.db())
+                .await?
+        else {
+            return Err(WebhookError::Subscription(subscription_id));
+        };
+
+        let mut subscription: webhook_subscription::ActiveModel = subscription.into();
+        subscription.active = Set(active);
+        subscription.update(self.db()).await?;
+        Ok(())
+    }
+
+    /// Removes a subscription together with its delivery log, only for managers
+    #[named]
+    pub async fn delete_webhook_subscription(
+        &self,
+        subscription_id: u32,
+    ) -> Result<(), WebhookError> {
+        self.webhook_permission_check(function_name!())?;
+
+        let deleted =
+            db_entities::prelude::WebhookSubscription::delete_by_id(subscription_id as i32)
+                .exec(self.db())
+                .await?;
+        if deleted.rows_affected == 0 {
+            return Err(WebhookError::Subscription(subscription_id));
+        }
+        Ok(())
+    }
+
+    /// Delivery log of a subscription, newest first, only for managers
+    #[named]
+    pub async fn get_webhook_deliveries(
+        &self,
+        subscription_id: u32,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        self.webhook_permission_check(function_name!())?;
+
+        if db_entities::prelude::WebhookSubscription::find_by_id(subscription_id as i32)
+            .one(self.db())
+            .await?
+            .is_none()
+        {
+            return Err(WebhookError::Subscription(subscription_id));
+        }
+
+        let deliveries = db_entities::prelude::WebhookDelivery::find()
+            .filter(webhook_delivery::Column::SubscriptionId.eq(subscription_id as i32))
+            .order_by_desc(webhook_delivery::Column::Id)
+            .all(self.db())
+            .await?;
+        Ok(deliveries.into_iter().map(|m| m.into()).collect())
+    }
+
+    /// Sends the payload of an earlier delivery again as a new delivery with the same
+    /// event ID, only for managers. The delivery is sent by [`ShopBackend::deliver_pending`].
+    #[named]
+    pub async fn replay_webhook_delivery(
+        &self,
+        delivery_id: u32,
+    ) -> Result<WebhookDelivery, WebhookError> {
+        self.webhook_permission_check(function_name!())?;
+
+        let Some(delivery) = db_entities::prelude::WebhookDelivery::find_by_id(delivery_id as i32)
+            .one(self.db())
+            .await?
+        else {
+            return Err(WebhookError::Delivery(delivery_id));
+        };
+
+        let now = Utc::now();
+        let replay = webhook_delivery::ActiveModel {
+            subscription_id: Set(delivery.subscription_id),
+            event_id: Set(delivery.event_id),
+            event_type: Set(delivery.event_type),
+            payload: Set(delivery.payload),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(self.db())
+        .await?;
+        Ok(replay.into())
+    }
+}
+
+impl<S: Storage> ShopBackend<S> {
+    /// Sends all webhook deliveries that are due, returns how many succeeded.
+    /// Called by [`ShopBackend::deliver_pending`].
+    pub async fn dispatch_webhooks(&self) -> Result<usize, DbError> {
+        let Some(db) = self.storage.outbox() else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        let due = db_entities::prelude::WebhookDelivery::find()
+            .find_also_related(db_entities::prelude::WebhookSubscription)
+            .filter(webhook_delivery::Column::DeliveredAt.is_null())
+            .filter(webhook_delivery::Column::Attempts.lt(MAX_DELIVERY_ATTEMPTS))
+            .filter(webhook_delivery::Column::NextAttemptAt.lte(now))
+            .order_by_asc(webhook_delivery::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut delivered = 0;
+        for (delivery, subscription) in due {
+            // Claim the delivery by counting the attempt, so that other dispatchers skip it
+            let attempts = delivery.attempts + 1;
+            let claimed = db_entities::prelude::WebhookDelivery::update_many()
+                .col_expr(webhook_delivery::Column::Attempts, attempts.into())
+                .col_expr(
+                    webhook_delivery::Column::NextAttemptAt,
+                    (now + Duration::seconds(DELIVERY_LEASE_SECONDS)).into(),
+                )
+                .filter(webhook_delivery::Column::Id.eq(delivery.id))
+                .filter(webhook_delivery::Column::Attempts.eq(delivery.attempts))
+                .filter(webhook_delivery::Column::DeliveredAt.is_null())
+                .exec(db)
+                .await?;
+            if claimed.rows_affected != 1 {
+                continue;
+            }
+
+            let mut delivery: webhook_delivery::ActiveModel = delivery.into();
+            delivery.attempts = Set(attempts);
+
+            let result = match subscription {
+                Some(subscription) if subscription.active => {
+                    self.send_webhook(&subscription, &delivery).await
+                }
+                _ => {
+                    // Nothing to retry until the subscription is turned back on and replayed
+                    delivery.attempts = Set(MAX_DELIVERY_ATTEMPTS);
+                    Err((None, String::from("subscription is disabled")))
+                }
+            };
+
+            match result {
+                Ok(status) => {
+                    delivery.delivered_at = Set(Some(Utc::now()));
+                    delivery.response_status = Set(Some(status));
+                    delivery.last_error = Set(None);
+                    delivered += 1;
+                }
+                Err((status, error)) => {
+                    delivery.response_status = Set(status);
+                    delivery.last_error = Set(Some(error));
+                    delivery.next_attempt_at =
+                        Set(Utc::now()
+                            + Duration::seconds(RETRY_BASE_DELAY_SECONDS << (attempts - 1)));
+                }
+            }
+            delivery.update(db).await?;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Posts a signed payload, returns the response status or the status and reason of a failure
+    async fn send_webhook(
+        &self,
+        subscription: &webhook_subscription::Model,
+        delivery: &webhook_delivery::ActiveModel,
+    ) -> Result<i32, (Option<i32>, String)> {
+        let payload = delivery.payload.as_ref();
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .http_client
+            .post(&subscription.url)
+            .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, delivery.event_type.as_ref().to_string())
+            .header(DELIVERY_HEADER, delivery.id.as_ref().to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                webhook_signature(&subscription.secret, timestamp, payload),
+            )
+            .body(payload.clone())
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16() as i32)
+        } else {
+            Err((Some(status.as_u16() as i32), format!("HTTP {status}")))
+        }
+    }
+}
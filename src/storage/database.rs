@@ -0,0 +1,920 @@
+use super::*;
+use crate::db_entities::{
+    self, client, employee, inspection_item, inspection_result, labor_entry, labor_line, location,
+    notification_outbox, notification_outbox::Channel, order, payment, repair_suggestion, report,
+    setting, webhook_delivery, webhook_subscription,
+};
+use crate::webhooks::{generate_event_id, WebhookEvent};
+use crate::{InspectionStatus, NotificationEvent};
+
+use sea_orm::sea_query::{Expr, Func, LikeExpr, OnConflict, Query, SimpleExpr};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, DbBackend, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, SqlErr, TransactionTrait,
+};
+
+/// Storage in a SQLite, MySQL or Postgres database, through a connection or a transaction.
+/// Rows read for update are locked with `SELECT ... FOR UPDATE` on MySQL and Postgres,
+/// SQLite locks the whole database for writing transactions instead.
+#[derive(Clone, Debug)]
+pub struct SeaOrmStorage<C = DatabaseConnection> {
+    conn: C,
+    sms_enabled: bool,
+}
+
+impl SeaOrmStorage {
+    /// `db` has to be migrated already, which [`ShopBackend::init`](crate::ShopBackend::init) does
+    pub fn new(db: DatabaseConnection) -> Self {
+        SeaOrmStorage {
+            conn: db,
+            sms_enabled: false,
+        }
+    }
+}
+
+impl<C> SeaOrmStorage<C> {
+    pub fn connection(&self) -> &C {
+        &self.conn
+    }
+
+    /// Text messages are only added to the outbox when something can send them
+    pub(crate) fn set_sms_enabled(&mut self, enabled: bool) {
+        self.sms_enabled = enabled;
+    }
+}
+
+impl From<client::Model> for ClientRecord {
+    fn from(value: client::Model) -> Self {
+        ClientRecord {
+            id: value.id as u32,
+            name: value.name,
+            email: value.email,
+            password_hash: value.password_hash,
+            car: value.car,
+            phone: value.phone,
+            address: value.address,
+            communication_preferences: value.communication_preferences,
+            email_verified: value.email_verified,
+            deleted: value.deleted,
+            verification_sent_at: value.verification_sent_at,
+        }
+    }
+}
+
+impl From<employee::Model> for EmployeeRecord {
+    fn from(value: employee::Model) -> Self {
+        EmployeeRecord {
+            id: value.id as u32,
+            name: value.name,
+            role: value.role,
+            password_hash: value.password_hash,
+            email: value.email,
+            totp_enabled: value.totp_enabled,
+            location_id: value.location_id.map(|id| id as u32),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> ClientRepository for SeaOrmStorage<C> {
+    async fn client(&self, id: u32) -> Result<Option<ClientRecord>, DbErr> {
+        Ok(db_entities::prelude::Client::find_by_id(id as i32)
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn client_for_update(&self, id: u32) -> Result<Option<ClientRecord>, DbErr> {
+        Ok(db_entities::prelude::Client::find_by_id(id as i32)
+            .lock_exclusive()
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn client_by_email(&self, email: &str) -> Result<Option<ClientRecord>, DbErr> {
+        Ok(db_entities::prelude::Client::find()
+            .filter(client::Column::Email.eq(email))
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn insert_client(&self, client: ClientRecord) -> Result<ClientRecord, DbErr> {
+        Ok(client::ActiveModel {
+            name: Set(client.name),
+            email: Set(client.email),
+            password_hash: Set(client.password_hash),
+            car: Set(client.car),
+            phone: Set(client.phone),
+            address: Set(client.address),
+            communication_preferences: Set(client.communication_preferences),
+            email_verified: Set(client.email_verified),
+            deleted: Set(client.deleted),
+            verification_sent_at: Set(client.verification_sent_at),
+            ..Default::default()
+        }
+        .insert(&self.conn)
+        .await?
+        .into())
+    }
+
+    async fn update_client(&self, client: &ClientRecord) -> Result<bool, DbErr> {
+        let client = client.clone();
+        let updated = client::Model {
+            id: client.id as i32,
+            name: client.name,
+            email: client.email,
+            password_hash: client.password_hash,
+            car: client.car,
+            phone: client.phone,
+            address: client.address,
+            communication_preferences: client.communication_preferences,
+            email_verified: client.email_verified,
+            deleted: client.deleted,
+            verification_sent_at: client.verification_sent_at,
+        }
+        .into_active_model()
+        .reset_all()
+        .update(&self.conn)
+        .await;
+
+        match updated {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> VehicleRepository for SeaOrmStorage<C> {
+    async fn car(&self, client_id: u32) -> Result<Option<Car>, DbErr> {
+        Ok(db_entities::prelude::Client::find_by_id(client_id as i32)
+            .one(&self.conn)
+            .await?
+            .and_then(|client| client.car))
+    }
+
+    async fn set_car(&self, client_id: u32, car: &Car) -> Result<(), DbErr> {
+        db_entities::prelude::Client::update_many()
+            .col_expr(client::Column::Car, Some(car.clone()).into())
+            .filter(client::Column::Id.eq(client_id as i32))
+            .exec(&self.conn)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> EmployeeRepository for SeaOrmStorage<C> {
+    async fn employee(&self, id: u32) -> Result<Option<EmployeeRecord>, DbErr> {
+        Ok(db_entities::prelude::Employee::find_by_id(id as i32)
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> OrderRepository for SeaOrmStorage<C> {
+    async fn order(&self, id: u32) -> Result<Option<Order>, DbErr> {
+        Ok(db_entities::prelude::Order::find_by_id(id as i32)
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn order_for_update(&self, id: u32) -> Result<Option<Order>, DbErr> {
+        Ok(db_entities::prelude::Order::find_by_id(id as i32)
+            .lock_exclusive()
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn orders(
+        &self,
+        finished: Option<bool>,
+        location_id: Option<u32>,
+    ) -> Result<Vec<Order>, DbErr> {
+        let mut query = db_entities::prelude::Order::find().order_by_asc(order::Column::Id);
+        if let Some(finished) = finished {
+            query = query.filter(order::Column::Finished.eq(finished));
+        }
+        if let Some(location_id) = location_id {
+            query = query.filter(order::Column::LocationId.eq(location_id as i32));
+        }
+        Ok(query
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    async fn client_orders(&self, client_id: u32) -> Result<Vec<Order>, DbErr> {
+        Ok(db_entities::prelude::Order::find()
+            .filter(order::Column::ClientId.eq(client_id as i32))
+            .order_by_asc(order::Column::Id)
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    async fn insert_order(
+        &self,
+        client_id: u32,
+        location_id: u32,
+        service: Service,
+        complaint: Option<&str>,
+    ) -> Result<Order, DbErr> {
+        Ok(order::ActiveModel {
+            client_id: Set(client_id as i32),
+            location_id: Set(location_id as i32),
+            service: Set(service),
+            version: Set(1),
+            created_at: Set(Some(Utc::now())),
+            complaint: Set(complaint.map(str::to_owned)),
+            ..Default::default()
+        }
+        .insert(&self.conn)
+        .await?
+        .into())
+    }
+
+    async fn complaint(&self, order_id: u32) -> Result<Option<String>, DbErr> {
+        Ok(db_entities::prelude::Order::find_by_id(order_id as i32)
+            .one(&self.conn)
+            .await?
+            .and_then(|order| order.complaint))
+    }
+
+    async fn update_order(&self, order: &Order) -> Result<Option<Order>, DbErr> {
+        let updated = db_entities::prelude::Order::update(order::ActiveModel {
+            id: Set(order.id() as i32),
+            client_id: Set(order.client_id() as i32),
+            location_id: Set(order.location_id() as i32),
+            service: Set(order.service()),
+            finished: Set(order.finished()),
+            version: Set(order.version() as i32 + 1),
+            created_at: Set(order.created_at()),
+            finished_at: Set(order.finished_at()),
+            mechanic_id: Set(order.mechanic_id().map(|id| id as i32)),
+            converted_to_repair: Set(order.converted_to_repair()),
+            ..Default::default()
+        })
+        .filter(order::Column::Version.eq(order.version() as i32))
+        .exec(&self.conn)
+        .await;
+
+        match updated {
+            Ok(order) => Ok(Some(order.into())),
+            Err(DbErr::RecordNotUpdated) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> LocationRepository for SeaOrmStorage<C> {
+    async fn location(&self, id: u32) -> Result<Option<Location>, DbErr> {
+        Ok(db_entities::prelude::Location::find_by_id(id as i32)
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn locations(&self) -> Result<Vec<Location>, DbErr> {
+        Ok(db_entities::prelude::Location::find()
+            .order_by_asc(location::Column::Id)
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    async fn insert_location(&self, name: &str, address: Option<&str>) -> Result<Location, DbErr> {
+        Ok(location::ActiveModel {
+            name: Set(name.to_owned()),
+            address: Set(address.map(str::to_owned)),
+            ..Default::default()
+        }
+        .insert(&self.conn)
+        .await?
+        .into())
+    }
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> ReportRepository for SeaOrmStorage<C> {
+    async fn report(&self, id: u32) -> Result<Option<Report>, DbErr> {
+        Ok(db_entities::prelude::Report::find_by_id(id as i32)
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn client_reports(&self, client_id: u32) -> Result<Vec<Report>, DbErr> {
+        Ok(db_entities::prelude::Report::find()
+            .filter(report::Column::ClientId.eq(client_id as i32))
+            .order_by_asc(report::Column::Id)
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect())
+    }
+
+    async fn current_report(&self, order_id: u32) -> Result<Option<Report>, DbErr> {
+        Ok(db_entities::prelude::Report::find()
+            .filter(report::Column::OrderId.eq(order_id as i32))
+            .filter(report::Column::SupersededBy.is_null())
+            .one(&self.conn)
+            .await?
+            .map(|m| m.into()))
+    }
+
+    async fn insert_report(
+        &self,
+        client_id: u32,
+        order_id: u32,
+        location_id: u32,
+        cost: u32,
+        revision: u32,
+    ) -> Result<Option<Report>, DbErr> {
+        let inserted = report::ActiveModel {
+            client_id: Set(client_id as i32),
+            order_id: Set(order_id as i32),
+            location_id: Set(location_id as i32),
+            cost: Set(cost as i32),
+            version: Set(1),
+            revision: Set(revision as i32),
+            superseded_by: Set(None),
+            created_at: Set(Some(Utc::now())),
+            ..Default::default()
+        }
+        .insert(&self.conn)
+        .await;
+
+        match inserted {
+            Ok(report) => Ok(Some(report.into())),
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn supersede_report(
+        &self,
+        report: &Report,
+        superseded_by: u32,
+    ) -> Result<Option<Report>, DbErr> {
+        let updated = db_entities::prelude::Report::update(report::ActiveModel {
+            id: Set(report.id() as i32),
+            superseded_by: Set(Some(superseded_by as i32)),
+            version: Set(report.version() as i32 + 1),
+            ..Default::default()
+        })
+        .filter(report::Column::Version.eq(report.version() as i32))
+        .filter(report::Column::SupersededBy.is_null())
+        .exec(&self.conn)
+        .await;
+
+        match updated {
+            Ok(report) => Ok(Some(report.into())),
+            Err(DbErr::RecordNotUpdated) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn search_reports(
+        &self,
+        search: &ReportSearch,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Report>, u64), DbErr> {
+        let mut condition = Condition::all();
+        if let Some(client_id) = search.client_id {
+            condition = condition.add(report::Column::ClientId.eq(client_id as i32));
+        }
+        if let Some(order_id) = search.order_id {
+            condition = condition.add(report::Column::OrderId.eq(order_id as i32));
+        }
+        if let Some(location_id) = search.location_id {
+            condition = condition.add(report::Column::LocationId.eq(location_id as i32));
+        }
+        if let Some(vehicle) = &search.vehicle {
+            let pattern = format!("%{}%", escape_like(&vehicle.to_lowercase()));
+            let backend = self.conn.get_database_backend();
+            let clients = Query::select()
+                .column(client::Column::Id)
+                .from(client::Entity)
+                .cond_where(
+                    Condition::any()
+                        .add(
+                            Expr::expr(lowercase_car_field(backend, "make"))
+                                .like(LikeExpr::new(&pattern).escape('!')),
+                        )
+                        .add(
+                            Expr::expr(lowercase_car_field(backend, "model"))
+                                .like(LikeExpr::new(&pattern).escape('!')),
+                        ),
+                )
+                .to_owned();
+            condition = condition.add(report::Column::ClientId.in_subquery(clients));
+        }
+        if let Some(from) = search.issued_from {
+            condition = condition.add(report::Column::CreatedAt.gte(from));
+        }
+        if let Some(until) = search.issued_until {
+            condition = condition.add(report::Column::CreatedAt.lte(until));
+        }
+        if let Some(min_cost) = search.min_cost {
+            condition = condition.add(report::Column::Cost.gte(min_cost as i32));
+        }
+        if let Some(max_cost) = search.max_cost {
+            condition = condition.add(report::Column::Cost.lte(max_cost as i32));
+        }
+        if let Some(paid) = search.paid {
+            let paid_amount = Query::select()
+                .expr(Func::coalesce([
+                    Expr::col((payment::Entity, payment::Column::Amount)).sum(),
+                    Expr::val(0).into(),
+                ]))
+                .from(payment::Entity)
+                .and_where(
+                    Expr::col((payment::Entity, payment::Column::ReportId))
+                        .equals((report::Entity, report::Column::Id)),
+                )
+                .to_owned();
+            let paid_amount =
+                SimpleExpr::SubQuery(None, Box::new(paid_amount.into_sub_query_statement()));
+            let cost = Expr::col((report::Entity, report::Column::Cost));
+            condition = condition.add(if paid {
+                cost.lte(paid_amount)
+            } else {
+                cost.gt(paid_amount)
+            });
+        }
+        if !search.include_superseded {
+            condition = condition.add(report::Column::SupersededBy.is_null());
+        }
+
+        let paginator = db_entities::prelude::Report::find()
+            .filter(condition)
+            .order_by_asc(report::Column::Id)
+            .paginate(&self.conn, per_page);
+        let total = paginator.num_items().await?;
+        let reports = paginator.fetch_page(page).await?;
+        Ok((reports.into_iter().map(|m| m.into()).collect(), total))
+    }
+}
+
+/// `field` of the JSON in `client.car` in lower case, each database has its own JSON functions
+fn lowercase_car_field(backend: DbBackend, field: &str) -> SimpleExpr {
+    Expr::cust(match backend {
+        DbBackend::MySql => {
+            format!("LOWER(JSON_UNQUOTE(JSON_EXTRACT(`client`.`car`, '$.{field}')))")
+        }
+        DbBackend::Postgres => format!(r#"LOWER("client"."car" ->> '{field}')"#),
+        DbBackend::Sqlite => format!(r#"LOWER(json_extract("client"."car", '$.{field}'))"#),
+    })
+}
+
+/// Escapes the wildcards of LIKE patterns with `!`
+fn escape_like(text: &str) -> String {
+    text.replace('!', "!!")
+        .replace('%', "!%")
+        .replace('_', "!_")
+}
+
+#[async_trait]
+impl<C: ConnectionTrait + Send + Sync> SettingsRepository for SeaOrmStorage<C> {
+    async fn setting_value(&self, key: &str) -> Result<Option<serde_json::Value>, DbErr> {
+        Ok(db_entities::prelude::Setting::find_by_id(key)
+            .one(&self.conn)
+            .await?
+            .map(|setting| setting.value))
+    }
+
+    async fn set_setting_value(&self, key: &str, value: serde_json::Value) -> Result<(), DbErr> {
+        let setting = setting::ActiveModel {
+            key: Set(key.to_owned()),
+            value: Set(value),
+        };
+        db_entities::prelude::Setting::insert(setting)
+            .on_conflict(
+                OnConflict::column(setting::Column::Key)
+                    .update_column(setting::Column::Value)
+                    .to_owned(),
+            )
+            .exec(&self.conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_setting_value(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<bool, DbErr> {
+        let setting = setting::ActiveModel {
+            key: Set(key.to_owned()),
+            value: Set(value),
+        };
+        let inserted = db_entities::prelude::Setting::insert(setting)
+            .on_conflict(
+                OnConflict::column(setting::Column::Key)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.conn)
+            .await?;
+        Ok(inserted == 1)
+    }
+}
+
+/// Connection or transaction a [`SeaOrmStorage`] works with
+pub trait SeaOrmConnection: ConnectionTrait + TransactionTrait + Send + Sync + 'static {
+    /// The connection itself, transactions have no outbox of their own
+    fn outbox(&self) -> Option<&DatabaseConnection>;
+}
+
+impl SeaOrmConnection for DatabaseConnection {
+    fn outbox(&self) -> Option<&DatabaseConnection> {
+        Some(self)
+    }
+}
+
+impl SeaOrmConnection for DatabaseTransaction {
+    fn outbox(&self) -> Option<&DatabaseConnection> {
+        None
+    }
+}
+
+#[async_trait]
+impl<C: SeaOrmConnection> Storage for SeaOrmStorage<C> {
+    type Transaction = SeaOrmStorage<DatabaseTransaction>;
+
+    async fn begin(&self) -> Result<Self::Transaction, DbErr> {
+        Ok(SeaOrmStorage {
+            conn: self.conn.begin().await?,
+            sms_enabled: self.sms_enabled,
+        })
+    }
+
+    async fn enqueue_messages(&self, event: &ShopEvent) -> Result<(), DbErr> {
+        self.enqueue_event_messages(event).await
+    }
+
+    async fn suggest_repairs(&self, order_id: u32) -> Result<(), DbErr> {
+        self.insert_repair_suggestions(order_id).await
+    }
+
+    async fn end_labor(&self, order_id: u32, ended_at: DateTime<Utc>) -> Result<(), DbErr> {
+        self.end_labor_entries(order_id, ended_at).await
+    }
+
+    async fn copy_labor_lines(
+        &self,
+        report_id: u32,
+        corrected_report_id: u32,
+    ) -> Result<(), DbErr> {
+        self.copy_report_labor_lines(report_id, corrected_report_id)
+            .await
+    }
+
+    fn outbox(&self) -> Option<&DatabaseConnection> {
+        self.conn.outbox()
+    }
+}
+
+#[async_trait]
+impl Transaction for SeaOrmStorage<DatabaseTransaction> {
+    async fn commit(self) -> Result<(), DbErr> {
+        self.conn.commit().await
+    }
+}
+
+impl<C: ConnectionTrait + Send + Sync> SeaOrmStorage<C> {
+    /// Adds the notification and webhook deliveries for `event` to the outbox
+    async fn enqueue_event_messages(&self, event: &ShopEvent) -> Result<(), DbErr> {
+        let (client_id, notification, webhook) = match *event {
+            ShopEvent::OrderRegistered {
+                order_id,
+                client_id,
+                service,
+                ..
+            } => (
+                client_id,
+                NotificationEvent::OrderReceived { order_id, service },
+                WebhookEvent::OrderCreated {
+                    order_id,
+                    client_id,
+                    service,
+                },
+            ),
+            ShopEvent::ServiceChanged {
+                order_id,
+                client_id,
+                service,
+                ..
+            } => {
+                let finished = self.order(order_id).await?.is_some_and(|o| o.finished());
+                (
+                    client_id,
+                    NotificationEvent::EstimateReady { order_id },
+                    WebhookEvent::OrderStatusChanged {
+                        order_id,
+                        client_id,
+                        service,
+                        finished,
+                    },
+                )
+            }
+            ShopEvent::OrderClosed {
+                order_id,
+                client_id,
+                ..
+            } => {
+                let Some(order) = self.order(order_id).await? else {
+                    return Ok(());
+                };
+                (
+                    client_id,
+                    NotificationEvent::OrderStatusChanged {
+                        order_id,
+                        status: String::from("finished"),
+                    },
+                    WebhookEvent::OrderStatusChanged {
+                        order_id,
+                        client_id,
+                        service: order.service(),
+                        finished: true,
+                    },
+                )
+            }
+            ShopEvent::ReportRegistered {
+                report_id,
+                order_id,
+                client_id,
+                cost,
+            } => (
+                client_id,
+                NotificationEvent::ReportIssued {
+                    report_id,
+                    order_id,
+                    cost,
+                },
+                WebhookEvent::ReportCreated {
+                    report_id,
+                    order_id,
+                    client_id,
+                    cost,
+                    supersedes: None,
+                },
+            ),
+            ShopEvent::ReportCorrected {
+                report_id,
+                superseded_report_id,
+                order_id,
+                client_id,
+                cost,
+            } => (
+                client_id,
+                NotificationEvent::ReportCorrected {
+                    report_id,
+                    superseded_report_id,
+                    order_id,
+                    cost,
+                },
+                WebhookEvent::ReportCreated {
+                    report_id,
+                    order_id,
+                    client_id,
+                    cost,
+                    supersedes: Some(superseded_report_id),
+                },
+            ),
+            ShopEvent::PaymentRecorded {
+                payment_id,
+                report_id,
+                client_id,
+                amount,
+            } => (
+                client_id,
+                NotificationEvent::PaymentReceived { report_id, amount },
+                WebhookEvent::PaymentRecorded {
+                    payment_id,
+                    report_id,
+                    client_id,
+                    amount,
+                },
+            ),
+            // Messages stay between the client and the shop, subscribers are not told
+            ShopEvent::OrderMessageSent {
+                order_id,
+                client_id,
+                employee_id: Some(_),
+                ..
+            } => {
+                let notification = NotificationEvent::MessageReceived { order_id };
+                return self
+                    .enqueue_client_notification(client_id, &notification)
+                    .await;
+            }
+            ShopEvent::LoggedIn { .. }
+            | ShopEvent::ClientRegistered { .. }
+            | ShopEvent::CarRegistered { .. }
+            | ShopEvent::OrderTransferred { .. }
+            | ShopEvent::OrderMessageSent { .. } => return Ok(()),
+        };
+
+        enqueue_notification(&self.conn, client_id, &notification, self.sms_enabled).await?;
+        enqueue_webhook(&self.conn, &webhook).await
+    }
+
+    /// Adds a notification that no shop event causes, such as a reminder, to the outbox
+    pub(crate) async fn enqueue_client_notification(
+        &self,
+        client_id: u32,
+        event: &NotificationEvent,
+    ) -> Result<(), DbErr> {
+        enqueue_notification(&self.conn, client_id, event, self.sms_enabled).await
+    }
+
+    /// Ends the open labor entries of the order
+    async fn end_labor_entries(&self, order_id: u32, ended_at: DateTime<Utc>) -> Result<(), DbErr> {
+        db_entities::prelude::LaborEntry::update_many()
+            .col_expr(labor_entry::Column::EndedAt, Some(ended_at).into())
+            .filter(labor_entry::Column::OrderId.eq(order_id as i32))
+            .filter(labor_entry::Column::EndedAt.is_null())
+            .exec(&self.conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Inserts the labor lines of `report_id` again for `corrected_report_id`
+    async fn copy_report_labor_lines(
+        &self,
+        report_id: u32,
+        corrected_report_id: u32,
+    ) -> Result<(), DbErr> {
+        let lines = labor_line::Entity::find()
+            .filter(labor_line::Column::ReportId.eq(report_id as i32))
+            .order_by_asc(labor_line::Column::Id)
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|line| labor_line::ActiveModel {
+                report_id: Set(corrected_report_id as i32),
+                mechanic_id: Set(line.mechanic_id),
+                minutes: Set(line.minutes),
+                rate: Set(line.rate),
+                amount: Set(line.amount),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            return Ok(());
+        }
+        labor_line::Entity::insert_many(lines)
+            .exec(&self.conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Adds a repair suggestion for every failed item of the order's checklist, in
+    /// checklist order
+    async fn insert_repair_suggestions(&self, order_id: u32) -> Result<(), DbErr> {
+        let failed = inspection_result::Entity::find()
+            .find_also_related(inspection_item::Entity)
+            .filter(inspection_result::Column::OrderId.eq(order_id as i32))
+            .filter(inspection_result::Column::Status.eq(InspectionStatus::Fail))
+            .order_by_asc(inspection_item::Column::Position)
+            .all(&self.conn)
+            .await?;
+
+        let now = Utc::now();
+        let suggestions = failed
+            .into_iter()
+            .filter_map(|(result, item)| Some((result, item?)))
+            .map(|(result, item)| repair_suggestion::ActiveModel {
+                order_id: Set(order_id as i32),
+                item_id: Set(item.id),
+                description: Set(repair_description(&item, &result)),
+                created_at: Set(now),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        if suggestions.is_empty() {
+            return Ok(());
+        }
+        repair_suggestion::Entity::insert_many(suggestions)
+            .exec(&self.conn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// E.g. `Brakes: Front pad thickness (1.5 mm) - uneven wear`
+fn repair_description(item: &inspection_item::Model, result: &inspection_result::Model) -> String {
+    let mut description = format!("{}: {}", item.section, item.name);
+    if let (Some(measurement), Some(unit)) = (result.measurement, &item.unit) {
+        description.push_str(&format!(" ({measurement} {unit})"));
+    }
+    if let Some(comment) = &result.comment {
+        description.push_str(&format!(" - {comment}"));
+    }
+    description
+}
+
+/// Adds messages about `event` for every channel the client agreed to to the outbox
+async fn enqueue_notification(
+    db: &impl ConnectionTrait,
+    client_id: u32,
+    event: &NotificationEvent,
+    sms_enabled: bool,
+) -> Result<(), DbErr> {
+    let Some(client) = db_entities::prelude::Client::find_by_id(client_id as i32)
+        .one(db)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    if client.deleted {
+        return Ok(());
+    }
+
+    let preferences = client.communication_preferences.unwrap_or_default();
+    let mut recipients = vec![(Channel::InApp, client.id.to_string())];
+    if preferences.email {
+        recipients.push((Channel::Email, client.email.clone()));
+    }
+    if let (true, true, Some(phone)) = (preferences.sms, sms_enabled, &client.phone) {
+        recipients.push((Channel::Sms, phone.clone()));
+    }
+
+    let (subject, body) = event.render();
+    let now = Utc::now();
+    db_entities::prelude::NotificationOutbox::insert_many(recipients.into_iter().map(
+        |(channel, recipient)| notification_outbox::ActiveModel {
+            client_id: Set(client.id),
+            channel: Set(channel),
+            recipient: Set(recipient),
+            subject: Set(subject.clone()),
+            body: Set(body.clone()),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            created_at: Set(now),
+            ..Default::default()
+        },
+    ))
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Adds a delivery of `event` for every active subscription to it
+async fn enqueue_webhook(db: &impl ConnectionTrait, event: &WebhookEvent) -> Result<(), DbErr> {
+    let event_type = event.event_type();
+    let subscriptions = db_entities::prelude::WebhookSubscription::find()
+        .filter(webhook_subscription::Column::Active.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|subscription| subscription.event_types.0.contains(&event_type))
+        .collect::<Vec<_>>();
+
+    if subscriptions.is_empty() {
+        return Ok(());
+    }
+
+    let event_id = generate_event_id();
+    let now = Utc::now();
+    let payload = event.payload(&event_id, now);
+    db_entities::prelude::WebhookDelivery::insert_many(subscriptions.into_iter().map(
+        |subscription| webhook_delivery::ActiveModel {
+            subscription_id: Set(subscription.id),
+            event_id: Set(event_id.clone()),
+            event_type: Set(event_type),
+            payload: Set(payload.clone()),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            created_at: Set(now),
+            ..Default::default()
+        },
+    ))
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
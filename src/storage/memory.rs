@@ -0,0 +1,522 @@
+use super::*;
+
+use tokio::sync::{Mutex as WriteLock, OwnedMutexGuard};
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug)]
+struct Data {
+    clients: BTreeMap<u32, ClientRecord>,
+    employees: BTreeMap<u32, EmployeeRecord>,
+    locations: BTreeMap<u32, Location>,
+    orders: BTreeMap<u32, Order>,
+    /// Complaints of the orders that have one
+    complaints: BTreeMap<u32, String>,
+    reports: BTreeMap<u32, Report>,
+    settings: HashMap<String, serde_json::Value>,
+}
+
+impl Default for Data {
+    /// Like migrated databases, starts with one location
+    fn default() -> Self {
+        Data {
+            clients: BTreeMap::new(),
+            employees: BTreeMap::new(),
+            locations: BTreeMap::from([(1, Location::new(1, "Main workshop", None))]),
+            orders: BTreeMap::new(),
+            complaints: BTreeMap::new(),
+            reports: BTreeMap::new(),
+            settings: HashMap::new(),
+        }
+    }
+}
+
+fn next_id<T>(map: &BTreeMap<u32, T>) -> u32 {
+    map.last_key_value().map_or(1, |(id, _)| id + 1)
+}
+
+/// Storage that keeps everything in memory and loses it when dropped.
+///
+/// Clones share the same data, so several backends can work on one shop.
+/// Transactions are serialized: a second one waits until the first is committed or dropped,
+/// so reading for update does not need to lock anything else.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryStorage {
+    data: Arc<Mutex<Data>>,
+    /// Data the changes are copied to on commit, for transactions
+    parent: Option<Arc<Mutex<Data>>>,
+    writer: Arc<WriteLock<()>>,
+    /// Held by top level transactions, so that only one of them runs at a time
+    _write_guard: Option<Arc<OwnedMutexGuard<()>>>,
+}
+
+impl InMemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an employee, there is no other way of creating employees.
+    /// Returns the ID of the employee.
+    pub fn insert_employee(
+        &self,
+        name: &str,
+        role: Role,
+        password_hash: &str,
+        email: Option<&str>,
+        location_id: Option<u32>,
+    ) -> u32 {
+        let mut data = self.data.lock().unwrap();
+        let id = next_id(&data.employees);
+        data.employees.insert(
+            id,
+            EmployeeRecord {
+                id,
+                name: name.to_owned(),
+                role,
+                password_hash: password_hash.to_owned(),
+                email: email.map(str::to_owned),
+                totp_enabled: false,
+                location_id,
+            },
+        );
+        id
+    }
+
+    fn read<T>(&self, f: impl FnOnce(&Data) -> T) -> T {
+        f(&self.data.lock().unwrap())
+    }
+
+    /// Outside of transactions writes wait for running transactions to finish,
+    /// so that committing one does not undo them
+    async fn write<T>(&self, f: impl FnOnce(&mut Data) -> T) -> T {
+        let _guard = match self.parent {
+            Some(_) => None,
+            None => Some(self.writer.lock().await),
+        };
+        f(&mut self.data.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl ClientRepository for InMemoryStorage {
+    async fn client(&self, id: u32) -> Result<Option<ClientRecord>, DbErr> {
+        Ok(self.read(|data| data.clients.get(&id).cloned()))
+    }
+
+    async fn client_for_update(&self, id: u32) -> Result<Option<ClientRecord>, DbErr> {
+        self.client(id).await
+    }
+
+    async fn client_by_email(&self, email: &str) -> Result<Option<ClientRecord>, DbErr> {
+        Ok(self.read(|data| {
+            data.clients
+                .values()
+                .find(|client| client.email == email)
+                .cloned()
+        }))
+    }
+
+    async fn insert_client(&self, client: ClientRecord) -> Result<ClientRecord, DbErr> {
+        self.write(|data| {
+            if data.clients.values().any(|c| c.email == client.email) {
+                return Err(DbErr::Custom(format!(
+                    "email {} already registered",
+                    client.email
+                )));
+            }
+
+            let client = ClientRecord {
+                id: next_id(&data.clients),
+                ..client
+            };
+            data.clients.insert(client.id, client.clone());
+            Ok(client)
+        })
+        .await
+    }
+
+    async fn update_client(&self, client: &ClientRecord) -> Result<bool, DbErr> {
+        self.write(|data| {
+            if data
+                .clients
+                .values()
+                .any(|c| c.id != client.id && c.email == client.email)
+            {
+                return Ok(false);
+            }
+            match data.clients.get_mut(&client.id) {
+                Some(stored) => {
+                    *stored = client.clone();
+                    Ok(true)
+                }
+                None => Err(DbErr::RecordNotUpdated),
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl VehicleRepository for InMemoryStorage {
+    async fn car(&self, client_id: u32) -> Result<Option<Car>, DbErr> {
+        Ok(self.read(|data| {
+            data.clients
+                .get(&client_id)
+                .and_then(|client| client.car.clone())
+        }))
+    }
+
+    async fn set_car(&self, client_id: u32, car: &Car) -> Result<(), DbErr> {
+        self.write(|data| {
+            if let Some(client) = data.clients.get_mut(&client_id) {
+                client.car = Some(car.clone());
+            }
+        })
+        .await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EmployeeRepository for InMemoryStorage {
+    async fn employee(&self, id: u32) -> Result<Option<EmployeeRecord>, DbErr> {
+        Ok(self.read(|data| data.employees.get(&id).cloned()))
+    }
+}
+
+#[async_trait]
+impl LocationRepository for InMemoryStorage {
+    async fn location(&self, id: u32) -> Result<Option<Location>, DbErr> {
+        Ok(self.read(|data| data.locations.get(&id).cloned()))
+    }
+
+    async fn locations(&self) -> Result<Vec<Location>, DbErr> {
+        Ok(self.read(|data| data.locations.values().cloned().collect()))
+    }
+
+    async fn insert_location(&self, name: &str, address: Option<&str>) -> Result<Location, DbErr> {
+        Ok(self
+            .write(|data| {
+                let location = Location::new(next_id(&data.locations), name, address);
+                data.locations.insert(location.id(), location.clone());
+                location
+            })
+            .await)
+    }
+}
+
+#[async_trait]
+impl OrderRepository for InMemoryStorage {
+    async fn order(&self, id: u32) -> Result<Option<Order>, DbErr> {
+        Ok(self.read(|data| data.orders.get(&id).copied()))
+    }
+
+    async fn order_for_update(&self, id: u32) -> Result<Option<Order>, DbErr> {
+        self.order(id).await
+    }
+
+    async fn orders(
+        &self,
+        finished: Option<bool>,
+        location_id: Option<u32>,
+    ) -> Result<Vec<Order>, DbErr> {
+        Ok(self.read(|data| {
+            data.orders
+                .values()
+                .filter(|order| finished.is_none_or(|finished| order.finished() == finished))
+                .filter(|order| location_id.is_none_or(|id| order.location_id() == id))
+                .copied()
+                .collect()
+        }))
+    }
+
+    async fn client_orders(&self, client_id: u32) -> Result<Vec<Order>, DbErr> {
+        Ok(self.read(|data| {
+            data.orders
+                .values()
+                .filter(|order| order.client_id() == client_id)
+                .copied()
+                .collect()
+        }))
+    }
+
+    async fn insert_order(
+        &self,
+        client_id: u32,
+        location_id: u32,
+        service: Service,
+        complaint: Option<&str>,
+    ) -> Result<Order, DbErr> {
+        Ok(self
+            .write(|data| {
+                let order = Order::registered(
+                    next_id(&data.orders),
+                    client_id,
+                    location_id,
+                    service,
+                    Some(Utc::now()),
+                );
+                data.orders.insert(order.id(), order);
+                if let Some(complaint) = complaint {
+                    data.complaints.insert(order.id(), complaint.to_owned());
+                }
+                order
+            })
+            .await)
+    }
+
+    async fn complaint(&self, order_id: u32) -> Result<Option<String>, DbErr> {
+        Ok(self.read(|data| data.complaints.get(&order_id).cloned()))
+    }
+
+    async fn update_order(&self, order: &Order) -> Result<Option<Order>, DbErr> {
+        Ok(self
+            .write(|data| match data.orders.get_mut(&order.id()) {
+                Some(stored) if stored.version() == order.version() => {
+                    *stored = order.at_version(order.version() + 1);
+                    Some(*stored)
+                }
+                _ => None,
+            })
+            .await)
+    }
+}
+
+#[async_trait]
+impl ReportRepository for InMemoryStorage {
+    async fn report(&self, id: u32) -> Result<Option<Report>, DbErr> {
+        Ok(self.read(|data| data.reports.get(&id).copied()))
+    }
+
+    async fn client_reports(&self, client_id: u32) -> Result<Vec<Report>, DbErr> {
+        Ok(self.read(|data| {
+            data.reports
+                .values()
+                .filter(|report| report.client_id() == client_id)
+                .copied()
+                .collect()
+        }))
+    }
+
+    async fn current_report(&self, order_id: u32) -> Result<Option<Report>, DbErr> {
+        Ok(self.read(|data| {
+            data.reports
+                .values()
+                .find(|report| report.order_id() == order_id && report.superseded_by().is_none())
+                .copied()
+        }))
+    }
+
+    async fn insert_report(
+        &self,
+        client_id: u32,
+        order_id: u32,
+        location_id: u32,
+        cost: u32,
+        revision: u32,
+    ) -> Result<Option<Report>, DbErr> {
+        Ok(self
+            .write(|data| {
+                if data
+                    .reports
+                    .values()
+                    .any(|report| report.order_id() == order_id && report.revision() == revision)
+                {
+                    return None;
+                }
+
+                let id = next_id(&data.reports);
+                let report = Report::new(
+                    id,
+                    client_id,
+                    order_id,
+                    location_id,
+                    cost,
+                    revision,
+                    None,
+                    1,
+                    Some(Utc::now()),
+                );
+                data.reports.insert(id, report);
+                Some(report)
+            })
+            .await)
+    }
+
+    async fn supersede_report(
+        &self,
+        report: &Report,
+        superseded_by: u32,
+    ) -> Result<Option<Report>, DbErr> {
+        Ok(self
+            .write(|data| match data.reports.get_mut(&report.id()) {
+                Some(stored)
+                    if stored.version() == report.version() && stored.superseded_by().is_none() =>
+                {
+                    *stored = Report::new(
+                        stored.id(),
+                        stored.client_id(),
+                        stored.order_id(),
+                        stored.location_id(),
+                        stored.cost(),
+                        stored.revision(),
+                        Some(superseded_by),
+                        stored.version() + 1,
+                        stored.created_at(),
+                    );
+                    Some(*stored)
+                }
+                _ => None,
+            })
+            .await)
+    }
+
+    /// There are no payments in memory, so only reports that cost nothing count as paid
+    async fn search_reports(
+        &self,
+        search: &ReportSearch,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Report>, u64), DbErr> {
+        let vehicle = search.vehicle.as_deref().map(str::to_lowercase);
+        Ok(self.read(|data| {
+            let car_matches = |client_id: u32| {
+                let Some(vehicle) = &vehicle else {
+                    return true;
+                };
+                data.clients
+                    .get(&client_id)
+                    .and_then(|client| client.car.as_ref())
+                    .is_some_and(|car| {
+                        car.make.to_lowercase().contains(vehicle)
+                            || car.model.to_lowercase().contains(vehicle)
+                    })
+            };
+            let issued_within = |report: &Report| match report.created_at() {
+                Some(created_at) => {
+                    search.issued_from.is_none_or(|from| created_at >= from)
+                        && search.issued_until.is_none_or(|until| created_at <= until)
+                }
+                None => search.issued_from.is_none() && search.issued_until.is_none(),
+            };
+
+            let matching = data
+                .reports
+                .values()
+                .filter(|report| {
+                    search.client_id.is_none_or(|id| report.client_id() == id)
+                        && search.order_id.is_none_or(|id| report.order_id() == id)
+                        && search
+                            .location_id
+                            .is_none_or(|id| report.location_id() == id)
+                        && search.min_cost.is_none_or(|cost| report.cost() >= cost)
+                        && search.max_cost.is_none_or(|cost| report.cost() <= cost)
+                        && search.paid.is_none_or(|paid| (report.cost() == 0) == paid)
+                        && (search.include_superseded || report.superseded_by().is_none())
+                        && issued_within(report)
+                        && car_matches(report.client_id())
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            let total = matching.len() as u64;
+            let reports = matching
+                .into_iter()
+                .skip((page * per_page) as usize)
+                .take(per_page as usize)
+                .collect();
+            (reports, total)
+        }))
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for InMemoryStorage {
+    async fn setting_value(&self, key: &str) -> Result<Option<serde_json::Value>, DbErr> {
+        Ok(self.read(|data| data.settings.get(key).cloned()))
+    }
+
+    async fn set_setting_value(&self, key: &str, value: serde_json::Value) -> Result<(), DbErr> {
+        self.write(|data| {
+            data.settings.insert(key.to_owned(), value);
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn insert_setting_value(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<bool, DbErr> {
+        Ok(self
+            .write(|data| {
+                if data.settings.contains_key(key) {
+                    return false;
+                }
+                data.settings.insert(key.to_owned(), value);
+                true
+            })
+            .await)
+    }
+}
+
+#[async_trait]
+impl Storage for InMemoryStorage {
+    type Transaction = InMemoryStorage;
+
+    async fn begin(&self) -> Result<Self::Transaction, DbErr> {
+        // Nested transactions already run alone, like savepoints
+        let write_guard = match self.parent {
+            Some(_) => None,
+            None => Some(Arc::new(self.writer.clone().lock_owned().await)),
+        };
+        let snapshot = self.read(Data::clone);
+
+        Ok(InMemoryStorage {
+            data: Arc::new(Mutex::new(snapshot)),
+            parent: Some(self.data.clone()),
+            writer: self.writer.clone(),
+            _write_guard: write_guard,
+        })
+    }
+
+    /// There is no outbox in memory, changes are only published to subscribers
+    async fn enqueue_messages(&self, _event: &ShopEvent) -> Result<(), DbErr> {
+        Ok(())
+    }
+
+    /// There are no inspection checklists in memory, so nothing failed them
+    async fn suggest_repairs(&self, _order_id: u32) -> Result<(), DbErr> {
+        Ok(())
+    }
+
+    /// Time is not tracked in memory, so nobody is clocked on
+    async fn end_labor(&self, _order_id: u32, _ended_at: DateTime<Utc>) -> Result<(), DbErr> {
+        Ok(())
+    }
+
+    /// Time is not tracked in memory, so no labor is billed
+    async fn copy_labor_lines(
+        &self,
+        _report_id: u32,
+        _corrected_report_id: u32,
+    ) -> Result<(), DbErr> {
+        Ok(())
+    }
+
+    fn outbox(&self) -> Option<&DatabaseConnection> {
+        None
+    }
+}
+
+#[async_trait]
+impl Transaction for InMemoryStorage {
+    async fn commit(self) -> Result<(), DbErr> {
+        let Some(parent) = &self.parent else {
+            return Err(DbErr::Custom(String::from("no transaction to commit")));
+        };
+        *parent.lock().unwrap() = self.read(Data::clone);
+        Ok(())
+    }
+}
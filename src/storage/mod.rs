@@ -0,0 +1,253 @@
+//! Persistence used by [`ShopBackend`](crate::ShopBackend)
+//!
+//! The backend talks to storage through the repository traits below, see [`Storage`]
+//! for what that covers. [`InMemoryStorage`] keeps everything in memory, which is meant
+//! for tests and demos.
+
+mod database;
+mod memory;
+
+use crate::{
+    Car, CommunicationPreferences, Location, Order, Report, ReportSearch, Role, Service, ShopEvent,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{DatabaseConnection, DbErr};
+
+pub use database::SeaOrmStorage;
+pub use memory::InMemoryStorage;
+
+/// Everything stored about a client, including what is never shown to users
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClientRecord {
+    pub id: u32,
+    pub name: String,
+    pub email: String,
+    pub password_hash: String,
+    pub car: Option<Car>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub communication_preferences: Option<CommunicationPreferences>,
+    pub email_verified: bool,
+    pub deleted: bool,
+    pub verification_sent_at: Option<DateTime<Utc>>,
+}
+
+impl From<ClientRecord> for crate::Client {
+    fn from(value: ClientRecord) -> Self {
+        crate::Client::new(
+            value.id,
+            &value.name,
+            &value.email,
+            value.car,
+            value.phone.as_deref(),
+            value.address.as_deref(),
+            value.communication_preferences.unwrap_or_default(),
+            value.email_verified,
+        )
+    }
+}
+
+/// What logging an employee in needs to know
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmployeeRecord {
+    pub id: u32,
+    pub name: String,
+    pub role: Role,
+    pub password_hash: String,
+    pub email: Option<String>,
+    pub totp_enabled: bool,
+    /// Workshop the employee works at, `None` for employees working at all of them
+    pub location_id: Option<u32>,
+}
+
+#[async_trait]
+pub trait ClientRepository {
+    async fn client(&self, id: u32) -> Result<Option<ClientRecord>, DbErr>;
+
+    /// Like [`ClientRepository::client`], but also locks the client until the transaction
+    /// ends, so that concurrent transactions cannot change it in between
+    async fn client_for_update(&self, id: u32) -> Result<Option<ClientRecord>, DbErr>;
+
+    async fn client_by_email(&self, email: &str) -> Result<Option<ClientRecord>, DbErr>;
+
+    /// Stores a new client, the ID of `client` is ignored and the assigned one returned
+    async fn insert_client(&self, client: ClientRecord) -> Result<ClientRecord, DbErr>;
+
+    /// Stores `client`, returns `false` without changing anything if another client
+    /// already has its email
+    async fn update_client(&self, client: &ClientRecord) -> Result<bool, DbErr>;
+}
+
+#[async_trait]
+pub trait VehicleRepository {
+    async fn car(&self, client_id: u32) -> Result<Option<Car>, DbErr>;
+
+    async fn set_car(&self, client_id: u32, car: &Car) -> Result<(), DbErr>;
+}
+
+#[async_trait]
+pub trait EmployeeRepository {
+    async fn employee(&self, id: u32) -> Result<Option<EmployeeRecord>, DbErr>;
+}
+
+#[async_trait]
+pub trait LocationRepository {
+    async fn location(&self, id: u32) -> Result<Option<Location>, DbErr>;
+
+    async fn locations(&self) -> Result<Vec<Location>, DbErr>;
+
+    async fn insert_location(&self, name: &str, address: Option<&str>) -> Result<Location, DbErr>;
+}
+
+#[async_trait]
+pub trait OrderRepository {
+    async fn order(&self, id: u32) -> Result<Option<Order>, DbErr>;
+
+    /// Like [`OrderRepository::order`], but also locks the order until the transaction ends
+    async fn order_for_update(&self, id: u32) -> Result<Option<Order>, DbErr>;
+
+    /// All orders by ID, or only the finished or unfinished ones, or only the ones at
+    /// `location_id`
+    async fn orders(
+        &self,
+        finished: Option<bool>,
+        location_id: Option<u32>,
+    ) -> Result<Vec<Order>, DbErr>;
+
+    async fn client_orders(&self, client_id: u32) -> Result<Vec<Order>, DbErr>;
+
+    /// Stores a new order with the client's description of the problem
+    async fn insert_order(
+        &self,
+        client_id: u32,
+        location_id: u32,
+        service: Service,
+        complaint: Option<&str>,
+    ) -> Result<Order, DbErr>;
+
+    /// The client's description of the problem, `None` if the order has none or does not exist
+    async fn complaint(&self, order_id: u32) -> Result<Option<String>, DbErr>;
+
+    /// Stores `order` if nobody changed it since [`Order::version`] and returns it with
+    /// the next version, returns `None` if it was changed in the meantime
+    async fn update_order(&self, order: &Order) -> Result<Option<Order>, DbErr>;
+}
+
+#[async_trait]
+pub trait ReportRepository {
+    async fn report(&self, id: u32) -> Result<Option<Report>, DbErr>;
+
+    async fn client_reports(&self, client_id: u32) -> Result<Vec<Report>, DbErr>;
+
+    /// Report of an order that was not superseded by a correction
+    async fn current_report(&self, order_id: u32) -> Result<Option<Report>, DbErr>;
+
+    /// Stores a new report, returns `None` if the order already has a report with `revision`
+    async fn insert_report(
+        &self,
+        client_id: u32,
+        order_id: u32,
+        location_id: u32,
+        cost: u32,
+        revision: u32,
+    ) -> Result<Option<Report>, DbErr>;
+
+    /// Marks `report` as superseded by the report `superseded_by` if nobody changed it since
+    /// [`Report::version`] and returns it with the next version, otherwise returns `None`
+    async fn supersede_report(
+        &self,
+        report: &Report,
+        superseded_by: u32,
+    ) -> Result<Option<Report>, DbErr>;
+
+    /// Reports matching `search` ordered by ID, the page `page` of `per_page` reports
+    /// counting from 0, and how many reports match on all pages
+    async fn search_reports(
+        &self,
+        search: &ReportSearch,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Report>, u64), DbErr>;
+}
+
+/// Values of backend settings, stored as JSON
+#[async_trait]
+pub trait SettingsRepository {
+    async fn setting_value(&self, key: &str) -> Result<Option<serde_json::Value>, DbErr>;
+
+    async fn set_setting_value(&self, key: &str, value: serde_json::Value) -> Result<(), DbErr>;
+
+    /// Stores `value` unless `key` already has one, returns whether it did
+    async fn insert_setting_value(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+    ) -> Result<bool, DbErr>;
+}
+
+/// Everything [`ShopBackend`](crate::ShopBackend) needs from where it keeps its data.
+///
+/// The repository traits cover clients, employees, vehicles, locations, orders, reports
+/// and settings. What the backend does with only those is available for every storage:
+/// logins, registration and email verification, client profiles, cars, registering,
+/// closing and transferring orders, registering, correcting and searching reports,
+/// locations, settings, the order board and
+/// [`ShopBackend::transaction`](crate::ShopBackend::transaction).
+///
+/// Everything else is only implemented for [`SeaOrmStorage`], the default, and queries
+/// the database directly: notifications, webhooks, payments, labor tracking, maintenance,
+/// messages and notes, attachments, inspections, trouble codes, vehicle history, analytics,
+/// two-factor authentication, password changes and resets, account deletion and data
+/// export. Moving one of those to another storage means adding a repository trait for it
+/// first.
+#[async_trait]
+pub trait Storage:
+    ClientRepository
+    + VehicleRepository
+    + EmployeeRepository
+    + LocationRepository
+    + OrderRepository
+    + ReportRepository
+    + SettingsRepository
+    + Send
+    + Sync
+    + 'static
+{
+    type Transaction: Transaction;
+
+    /// Starts a transaction, changes made through it are only visible to others after
+    /// [`Transaction::commit`] and are discarded if it is dropped
+    async fn begin(&self) -> Result<Self::Transaction, DbErr>;
+
+    /// Stores messages about `event` to be sent to clients and webhook subscribers.
+    /// Called inside the transaction making the change, so that messages are stored
+    /// if and only if the change is. Storages without an outbox do nothing here.
+    async fn enqueue_messages(&self, event: &ShopEvent) -> Result<(), DbErr>;
+
+    /// Suggests a repair for every item that failed the inspection checklist of the order.
+    /// Called inside the transaction changing an inspection to a repair. Storages without
+    /// checklists do nothing here.
+    async fn suggest_repairs(&self, order_id: u32) -> Result<(), DbErr>;
+
+    /// Clocks off everyone still working on the order at `ended_at`. Called inside the
+    /// transaction closing the order. Storages without time tracking do nothing here.
+    async fn end_labor(&self, order_id: u32, ended_at: DateTime<Utc>) -> Result<(), DbErr>;
+
+    /// Bills the labor lines of the report `report_id` again on the report correcting it.
+    /// Called inside the transaction correcting the report. Storages without time tracking
+    /// do nothing here.
+    async fn copy_labor_lines(&self, report_id: u32, corrected_report_id: u32)
+        -> Result<(), DbErr>;
+
+    /// Database holding the messages stored by [`Storage::enqueue_messages`], which
+    /// [`ShopBackend::deliver_pending`](crate::ShopBackend::deliver_pending) sends. Inside
+    /// transactions and for storages without an outbox this is `None`.
+    fn outbox(&self) -> Option<&DatabaseConnection>;
+}
+
+#[async_trait]
+pub trait Transaction: Storage {
+    async fn commit(self) -> Result<(), DbErr>;
+}
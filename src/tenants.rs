@@ -0,0 +1,123 @@
+//! Hosting several independent shops in one deployment
+//!
+//! Every shop (tenant) has a database of its own, e.g. a SQLite file or a database on a
+//! shared Postgres server. Backends handed out by [`TenantDirectory`] are bound to the
+//! database of their tenant, so every query they run is isolated from other shops
+//! without having to filter by tenant. Events and order boards are per tenant as well.
+
+use crate::migrator::Migrator;
+use crate::{EventBus, FileBlobStore, LoginError, SeaOrmStorage, ShopBackend, TenantError, User};
+
+use regex::Regex;
+use sea_orm::{Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::{Arc, LazyLock, RwLock};
+
+static TENANT_NAME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").unwrap());
+
+#[derive(Clone)]
+struct Tenant {
+    db: DatabaseConnection,
+    events: EventBus,
+}
+
+/// Shops hosted by this deployment, cheap to clone
+#[derive(Clone, Default)]
+pub struct TenantDirectory {
+    tenants: Arc<RwLock<BTreeMap<String, Tenant>>>,
+}
+
+impl TenantDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hosts the shops listed in SHOP_TENANTS environment variable, as comma separated
+    /// `name=database URL` pairs, e.g. `north=sqlite:./north.db?mode=rwc,south=sqlite:./south.db?mode=rwc`
+    pub async fn from_env() -> Result<Self, TenantError> {
+        let directory = Self::new();
+        let tenants = env::var("SHOP_TENANTS").unwrap_or_default();
+        for tenant in tenants.split(',').filter(|t| !t.trim().is_empty()) {
+            let Some((name, db_url)) = tenant.split_once('=') else {
+                return Err(TenantError::InvalidName(tenant.trim().to_owned()));
+            };
+            directory.add_tenant(name.trim(), db_url.trim()).await?;
+        }
+        Ok(directory)
+    }
+
+    /// Connects to the database of a new shop and migrates it.
+    /// Names are lowercase letters and digits separated by dashes.
+    pub async fn add_tenant(&self, name: &str, db_url: &str) -> Result<(), TenantError> {
+        if !TENANT_NAME_REGEX.is_match(name) {
+            return Err(TenantError::InvalidName(name.to_owned()));
+        }
+
+        let db = Database::connect(db_url).await?;
+        Migrator::up(&db, None).await?;
+        self.tenants.write().unwrap().insert(
+            name.to_owned(),
+            Tenant {
+                db,
+                events: EventBus::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Names of all hosted shops
+    pub fn tenants(&self) -> Vec<String> {
+        self.tenants.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Backend working on the shop `name` that nobody is logged in to. Backends of
+    /// the same shop share an [`EventBus`].
+    pub async fn backend(&self, name: &str) -> Result<ShopBackend, TenantError> {
+        let tenant = self
+            .tenants
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| TenantError::UnknownTenant(name.to_owned()))?;
+
+        let mut backend = ShopBackend::with_storage(SeaOrmStorage::new(tenant.db)).await?;
+        backend.set_event_bus(tenant.events);
+        backend.set_tenant(name);
+        // Shops must not see each other's attachments
+        backend.set_blob_store(FileBlobStore::new(
+            FileBlobStore::from_env().root().join(name),
+        ));
+        Ok(backend)
+    }
+
+    /// Logs a client in to the shop `tenant`, which resolves the shop first
+    pub async fn client_login(
+        &self,
+        tenant: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<(ShopBackend, User), LoginError> {
+        let mut backend = self.backend(tenant).await?;
+        let user = backend.client_login(email, password).await?;
+        Ok((backend, user))
+    }
+
+    /// Logs an employee in to the shop `tenant`. Employees with two-factor authentication
+    /// log in through [`TenantDirectory::backend`] instead, whose backend keeps the
+    /// pending login for [`ShopBackend::employee_login_second_factor`].
+    pub async fn employee_login(
+        &self,
+        tenant: &str,
+        id: u32,
+        password: &str,
+    ) -> Result<(ShopBackend, User), LoginError> {
+        let mut backend = self.backend(tenant).await?;
+        let user = backend.employee_login(id, password).await?;
+        Ok((backend, user))
+    }
+}
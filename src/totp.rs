@@ -0,0 +1,134 @@
+//! RFC 6238 time-based one-time passwords
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+pub(crate) const TOTP_PERIOD: u64 = 30;
+pub(crate) const TOTP_DIGITS: u32 = 6;
+const TOTP_SECRET_LEN: usize = 20;
+/// Number of periods before and after the current one in which a code is still accepted
+const TOTP_SKEW: i64 = 1;
+const ISSUER: &str = "Car Repair Shop";
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Secret of a new TOTP enrollment, to be entered into an authenticator app
+#[derive(Clone, Debug)]
+pub struct TotpEnrollment {
+    secret: String,
+    uri: String,
+}
+
+impl TotpEnrollment {
+    pub(crate) fn new(secret: &str, account: &str) -> Self {
+        TotpEnrollment {
+            secret: secret.to_owned(),
+            uri: otpauth_uri(secret, account),
+        }
+    }
+
+    /// Base32 encoded secret for manual entry
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// `otpauth://` URI to be shown as a QR code
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+pub(crate) fn generate_secret() -> String {
+    let mut bytes = [0u8; TOTP_SECRET_LEN];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base32_encode(&bytes)
+}
+
+fn otpauth_uri(secret: &str, account: &str) -> String {
+    let label = urlencoding::encode(&format!("{ISSUER}:{account}")).into_owned();
+    format!(
+        "otpauth://totp/{label}?secret={secret}&issuer={}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD}",
+        urlencoding::encode(ISSUER)
+    )
+}
+
+pub(crate) fn current_step(unix_time: i64) -> i64 {
+    unix_time / TOTP_PERIOD as i64
+}
+
+/// Returns the time step `code` is valid for if it matches any step within the allowed skew
+pub(crate) fn verify(secret: &str, code: &str, step: i64) -> Option<i64> {
+    let key = base32_decode(secret)?;
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    (step - TOTP_SKEW..=step + TOTP_SKEW).find(|&s| s >= 0 && hotp(&key, s as u64) == code)
+}
+
+/// Code an authenticator app shows for the base32 encoded `secret` at `unix_time`,
+/// `None` if the secret is not base32
+pub fn totp_code(secret: &str, unix_time: i64) -> Option<String> {
+    let key = base32_decode(secret)?;
+    Some(hotp(&key, current_step(unix_time) as u64))
+}
+
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// RFC 4648 base32 without padding, as used for TOTP secrets
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Decodes base32 with or without padding, in either case. `None` for other characters.
+pub fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.trim_end_matches('=').chars() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
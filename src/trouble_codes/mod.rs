@@ -0,0 +1,57 @@
+//! OBD-II diagnostic trouble codes (DTCs) read off cars
+//!
+//! Codes have five characters: the system (P powertrain, B body, C chassis, U network),
+//! a digit telling generic codes from manufacturer specific ones, and three hexadecimal
+//! digits. Descriptions of common generic codes are embedded, manufacturer specific codes
+//! have to be looked up in the manufacturer's documentation.
+
+use crate::{Car, TroubleCode};
+
+use serde::Serialize;
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Tab separated code and description per line
+static GENERIC_CODES: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+    include_str!("generic_codes.tsv")
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect()
+});
+
+/// `code` in upper case without surrounding whitespace, `None` if it is not a valid code
+pub fn normalize_trouble_code(code: &str) -> Option<String> {
+    let code = code.trim().to_ascii_uppercase();
+    let bytes = code.as_bytes();
+    let valid = bytes.len() == 5
+        && matches!(bytes[0], b'P' | b'B' | b'C' | b'U')
+        && matches!(bytes[1], b'0'..=b'3')
+        && bytes[2..].iter().all(u8::is_ascii_hexdigit);
+    valid.then_some(code)
+}
+
+/// Whether a valid, normalized code means the same for every manufacturer
+pub fn is_generic_trouble_code(code: &str) -> bool {
+    match code.as_bytes() {
+        [b'P', b'0' | b'2', ..] => true,
+        [b'P', b'3', group, ..] => matches!(group, b'4'..=b'9'),
+        [_, b'0' | b'3', ..] => true,
+        _ => false,
+    }
+}
+
+/// Description of a generic code, `None` for manufacturer specific and unknown codes
+pub fn describe_trouble_code(code: &str) -> Option<&'static str> {
+    GENERIC_CODES.get(code).copied()
+}
+
+/// Trouble code found by [`ShopBackend::search_trouble_codes`](crate::ShopBackend::search_trouble_codes)
+/// with the car it was read from
+#[derive(Clone, Debug, Serialize)]
+pub struct TroubleCodeMatch {
+    pub trouble_code: TroubleCode,
+    pub client_id: u32,
+    /// Car of the client now, which may have changed since the code was recorded
+    pub car: Option<Car>,
+}
@@ -1,8 +1,11 @@
-#[derive(Clone, Debug)]
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
 pub enum UserType {
     Client,
     Technician,
     Mechanic,
+    Manager,
     NotLoggedIn,
 }
 
@@ -12,16 +15,18 @@ impl std::fmt::Display for UserType {
             UserType::Client => "Client",
             UserType::Technician => "Technician",
             UserType::Mechanic => "Mechanic",
+            UserType::Manager => "Manager",
             UserType::NotLoggedIn => "Not logged in",
         })
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct User {
     id: u32,
     name: String,
     user_type: UserType,
+    location_id: Option<u32>,
 }
 
 impl User {
@@ -31,6 +36,14 @@ impl User {
             id,
             name: name.to_string(),
             user_type,
+            location_id: None,
+        }
+    }
+
+    pub(crate) fn at_location(self, location_id: Option<u32>) -> Self {
+        User {
+            location_id,
+            ..self
         }
     }
 
@@ -39,6 +52,7 @@ impl User {
             id: 0,
             name: String::new(),
             user_type: UserType::NotLoggedIn,
+            location_id: None,
         }
     }
 
@@ -53,4 +67,9 @@ impl User {
     pub fn user_type(&self) -> UserType {
         self.user_type.clone()
     }
+
+    /// Workshop the employee works at, `None` for clients and employees working at all of them
+    pub fn location_id(&self) -> Option<u32> {
+        self.location_id
+    }
 }
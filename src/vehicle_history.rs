@@ -0,0 +1,183 @@
+use crate::export::escape_html;
+use crate::{Car, ExportError, MileageReading, Order, PartUsage, Report};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use std::fmt::Write;
+
+/// One order of a car with what came of it
+#[derive(Clone, Debug, Serialize)]
+pub struct ServiceRecord {
+    order: Order,
+    /// Current report, corrections replace the original
+    report: Option<Report>,
+    parts: Vec<PartUsage>,
+    mileage: Option<u32>,
+}
+
+impl ServiceRecord {
+    pub fn order(&self) -> &Order {
+        &self.order
+    }
+
+    pub fn report(&self) -> Option<&Report> {
+        self.report.as_ref()
+    }
+
+    pub fn parts(&self) -> &[PartUsage] {
+        &self.parts
+    }
+
+    /// Odometer when the car was checked in for the order
+    pub fn mileage(&self) -> Option<u32> {
+        self.mileage
+    }
+}
+
+/// Everything the shop did to a car, oldest first
+#[derive(Clone, Debug, Serialize)]
+pub struct VehicleHistory {
+    generated_at: DateTime<Utc>,
+    client_id: u32,
+    car: Car,
+    services: Vec<ServiceRecord>,
+    mileage: Vec<MileageReading>,
+}
+
+impl VehicleHistory {
+    pub(crate) fn new(
+        client_id: u32,
+        car: Car,
+        orders: Vec<Order>,
+        reports: Vec<Report>,
+        parts: Vec<PartUsage>,
+        mileage: Vec<MileageReading>,
+    ) -> Self {
+        let services = orders
+            .into_iter()
+            .map(|order| ServiceRecord {
+                report: reports
+                    .iter()
+                    .find(|report| {
+                        report.order_id() == order.id() && report.superseded_by().is_none()
+                    })
+                    .copied(),
+                parts: parts
+                    .iter()
+                    .filter(|part| part.order_id() == order.id())
+                    .cloned()
+                    .collect(),
+                mileage: mileage
+                    .iter()
+                    .rev()
+                    .find(|reading| reading.order_id() == order.id())
+                    .map(|reading| reading.mileage()),
+                order,
+            })
+            .collect();
+
+        VehicleHistory {
+            generated_at: Utc::now(),
+            client_id,
+            car,
+            services,
+            mileage,
+        }
+    }
+
+    pub fn generated_at(&self) -> DateTime<Utc> {
+        self.generated_at
+    }
+
+    pub fn client_id(&self) -> u32 {
+        self.client_id
+    }
+
+    pub fn car(&self) -> &Car {
+        &self.car
+    }
+
+    pub fn services(&self) -> &[ServiceRecord] {
+        &self.services
+    }
+
+    /// Odometer readings, oldest first
+    pub fn mileage(&self) -> &[MileageReading] {
+        &self.mileage
+    }
+
+    /// Most recent odometer reading
+    pub fn latest_mileage(&self) -> Option<u32> {
+        self.mileage.last().map(|reading| reading.mileage())
+    }
+
+    pub fn to_json(&self) -> Result<String, ExportError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Printable HTML document for buyers of the car, without the owner's details or costs
+    pub fn to_html(&self) -> String {
+        let mut services = String::new();
+        for service in &self.services {
+            let order = service.order();
+            let parts = service
+                .parts()
+                .iter()
+                .map(|part| escape_html(&part.to_string()))
+                .collect::<Vec<_>>()
+                .join("<br>");
+            writeln!(
+                services,
+                "<tr><td>{date}</td><td>{service}</td><td>{mileage}</td><td>{status}</td><td>{parts}</td></tr>",
+                date = order
+                    .created_at()
+                    .map(|at| at.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                service = order.service(),
+                mileage = service.mileage().map(|m| format!("{m} km")).unwrap_or_default(),
+                status = if order.finished() { "finished" } else { "in progress" },
+            )
+            .unwrap();
+        }
+
+        let mut readings = String::new();
+        for reading in &self.mileage {
+            writeln!(
+                readings,
+                "<tr><td>{date}</td><td>{mileage} km</td><td>{note}</td></tr>",
+                date = reading.recorded_at().format("%Y-%m-%d"),
+                mileage = reading.mileage(),
+                note = reading
+                    .reason()
+                    .map(|reason| format!("Lower than before: {}", escape_html(reason)))
+                    .unwrap_or_default(),
+            )
+            .unwrap();
+        }
+
+        format!(
+            "<!DOCTYPE html>\n\
+             <html>\n\
+             <head><meta charset=\"utf-8\"><title>Service history</title></head>\n\
+             <body>\n\
+             <h1>Service history of {make} {model}</h1>\n\
+             <p>Generated: {generated}</p>\n\
+             <h2>Services</h2>\n\
+             <table>\n\
+             <tr><th>Date</th><th>Service</th><th>Mileage</th><th>Status</th><th>Parts</th></tr>\n\
+             {services}\
+             </table>\n\
+             <h2>Mileage</h2>\n\
+             <table>\n\
+             <tr><th>Date</th><th>Mileage</th><th>Note</th></tr>\n\
+             {readings}\
+             </table>\n\
+             </body>\n\
+             </html>\n",
+            make = escape_html(&self.car.make),
+            model = escape_html(&self.car.model),
+            generated = self.generated_at.format("%Y-%m-%d"),
+        )
+    }
+}
@@ -0,0 +1,126 @@
+//! Signed HTTP callbacks sent to external systems when shop data changes
+//!
+//! Every request is a JSON `POST` with the following headers:
+//! - [`EVENT_HEADER`] with the event type, such as `order.created`
+//! - [`DELIVERY_HEADER`] with the ID of the delivery, different for every replay
+//! - [`TIMESTAMP_HEADER`] with the Unix time the request was signed at
+//! - [`SIGNATURE_HEADER`] with `sha256=` followed by the hex encoded HMAC-SHA256
+//!   of `{timestamp}.{body}`, keyed with the subscription secret
+//!
+//! Receivers should check the signature with [`verify_webhook_signature`], reject old
+//! timestamps, and skip events whose `id` they have already processed.
+
+use crate::{Service, WebhookEventType};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Serialize;
+use sha2::Sha256;
+
+pub const EVENT_HEADER: &str = "X-Shop-Event";
+pub const DELIVERY_HEADER: &str = "X-Shop-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Shop-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Shop-Signature";
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Data sent along with each webhook event type
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum WebhookEvent {
+    OrderCreated {
+        order_id: u32,
+        client_id: u32,
+        service: Service,
+    },
+    OrderStatusChanged {
+        order_id: u32,
+        client_id: u32,
+        service: Service,
+        finished: bool,
+    },
+    ReportCreated {
+        report_id: u32,
+        order_id: u32,
+        client_id: u32,
+        cost: u32,
+        /// Report replaced by this one if it is a correction
+        #[serde(skip_serializing_if = "Option::is_none")]
+        supersedes: Option<u32>,
+    },
+    PaymentRecorded {
+        payment_id: u32,
+        report_id: u32,
+        client_id: u32,
+        amount: u32,
+    },
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    id: &'a str,
+    #[serde(rename = "type")]
+    event_type: WebhookEventType,
+    created_at: DateTime<Utc>,
+    data: &'a WebhookEvent,
+}
+
+impl WebhookEvent {
+    pub(crate) fn event_type(&self) -> WebhookEventType {
+        match self {
+            WebhookEvent::OrderCreated { .. } => WebhookEventType::OrderCreated,
+            WebhookEvent::OrderStatusChanged { .. } => WebhookEventType::OrderStatusChanged,
+            WebhookEvent::ReportCreated { .. } => WebhookEventType::ReportCreated,
+            WebhookEvent::PaymentRecorded { .. } => WebhookEventType::PaymentRecorded,
+        }
+    }
+
+    /// JSON body of the request, identical for all subscribers and replays of the event
+    pub(crate) fn payload(&self, event_id: &str, created_at: DateTime<Utc>) -> String {
+        serde_json::to_string(&Envelope {
+            id: event_id,
+            event_type: self.event_type(),
+            created_at,
+            data: self,
+        })
+        .expect("webhook events are always serializable")
+    }
+}
+
+pub(crate) fn generate_event_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("evt_{}", hex::encode(bytes))
+}
+
+fn mac(secret: &str, timestamp: i64, body: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    mac
+}
+
+/// Value of the [`SIGNATURE_HEADER`] for a request body signed at `timestamp`
+pub fn webhook_signature(secret: &str, timestamp: i64, body: &str) -> String {
+    format!(
+        "{SIGNATURE_PREFIX}{}",
+        hex::encode(mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+/// Checks the [`SIGNATURE_HEADER`] of a received request in constant time
+pub fn verify_webhook_signature(secret: &str, timestamp: i64, body: &str, signature: &str) -> bool {
+    let Some(signature) = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|signature| hex::decode(signature).ok())
+    else {
+        return false;
+    };
+
+    mac(secret, timestamp, body)
+        .verify_slice(&signature)
+        .is_ok()
+}
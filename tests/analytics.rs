@@ -0,0 +1,149 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use chrono::{Datelike, Duration, Utc, Weekday};
+use common::{setup_with_business, PASSWORD};
+
+fn this_year() -> AnalyticsFilter {
+    let now = Utc::now();
+    AnalyticsFilter::new(now - Duration::days(365), now + Duration::days(1))
+}
+
+#[async_std::test]
+async fn revenue_counts_current_reports() {
+    let (backend, _) = setup_with_business("analytics-revenue").await;
+    let today = Utc::now().date_naive();
+
+    let days = backend
+        .revenue_by_period(&this_year(), Period::Day)
+        .await
+        .unwrap();
+    assert_eq!(
+        days,
+        [PeriodRevenue {
+            period_start: today,
+            reports: 2,
+            revenue: 16000,
+        }]
+    );
+    let weeks = backend
+        .revenue_by_period(&this_year(), Period::Week)
+        .await
+        .unwrap();
+    assert_eq!(weeks[0].period_start.weekday(), Weekday::Mon);
+    assert!(today - weeks[0].period_start < Duration::days(7));
+    let months = backend
+        .revenue_by_period(&this_year(), Period::Month)
+        .await
+        .unwrap();
+    assert_eq!(months[0].period_start, today.with_day(1).unwrap());
+
+    let services = backend.revenue_by_service(&this_year()).await.unwrap();
+    assert_eq!(services.len(), 2);
+    assert_eq!(services[0].service, Service::Inspection);
+    assert_eq!(services[0].revenue, 4000);
+    assert_eq!(services[1].service, Service::Repair);
+    assert_eq!(services[1].revenue, 12000);
+
+    let mechanics = backend.revenue_by_mechanic(&this_year()).await.unwrap();
+    assert_eq!(
+        mechanics,
+        [MechanicRevenue {
+            mechanic_id: 2,
+            name: String::from("mechanic"),
+            reports: 2,
+            revenue: 16000,
+        }]
+    );
+
+    let future = AnalyticsFilter::new(
+        Utc::now() + Duration::days(1),
+        Utc::now() + Duration::days(2),
+    );
+    assert!(backend
+        .revenue_by_period(&future, Period::Day)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[async_std::test]
+async fn summary_covers_operations() {
+    let (backend, _) = setup_with_business("analytics-summary").await;
+
+    let summary = backend.operations_summary(&this_year()).await.unwrap();
+    assert_eq!(summary.reports, 2);
+    assert_eq!(summary.revenue, 16000);
+    assert_eq!(summary.average_ticket, Some(8000.0));
+    assert_eq!(summary.finished_orders, 2);
+    assert!(summary.average_turnaround_hours.unwrap() < 1.0);
+    assert_eq!(summary.inspections, 2);
+    assert_eq!(summary.converted_to_repair, 1);
+    assert_eq!(summary.conversion_rate(), Some(0.5));
+    assert_eq!(summary.outstanding_receivables, 3000);
+
+    let receivables = backend.outstanding_receivables(None).await.unwrap();
+    assert_eq!(receivables.len(), 1);
+    assert_eq!(receivables[0].report_id, 3);
+    assert_eq!(receivables[0].order_id, 2);
+    assert_eq!(receivables[0].outstanding(), 3000);
+}
+
+#[async_std::test]
+async fn results_export_to_csv() {
+    let (backend, _) = setup_with_business("analytics-csv").await;
+
+    let mechanics = backend.revenue_by_mechanic(&this_year()).await.unwrap();
+    assert_eq!(
+        to_csv(&mechanics),
+        "mechanic_id,name,reports,revenue\r\n2,mechanic,2,16000\r\n"
+    );
+    let summary = backend.operations_summary(&this_year()).await.unwrap();
+    let csv = to_csv(&[summary]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("2,16000,8000.00,2,"));
+    assert!(lines[1].ends_with(",2,1,0.5000,3000"));
+
+    let receivable = Receivable {
+        report_id: 1,
+        order_id: 1,
+        client_id: 1,
+        location_id: 1,
+        cost: 100,
+        paid: 0,
+        issued_at: None,
+    };
+    assert_eq!(
+        to_csv(&[receivable]).lines().nth(1),
+        Some("1,1,1,1,100,0,100,")
+    );
+}
+
+#[async_std::test]
+async fn analytics_are_for_managers() {
+    let (mut backend, _) = setup_with_business("analytics-permission").await;
+    let north = backend.create_location("North", None).await.unwrap();
+    backend.set_employee_location(1, Some(1)).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let mut filter = this_year();
+    filter.location_id = Some(north.id());
+    assert!(matches!(
+        backend.operations_summary(&filter).await,
+        Err(DbError::Permission)
+    ));
+    filter.location_id = None;
+    assert_eq!(
+        backend.operations_summary(&filter).await.unwrap().reports,
+        2
+    );
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.revenue_by_service(&this_year()).await,
+        Err(DbError::Permission)
+    ));
+}
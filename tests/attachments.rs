@@ -0,0 +1,161 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup_with_attachments, PASSWORD};
+use image::{ImageFormat, RgbImage};
+
+use std::io::Cursor;
+
+fn photo(width: u32, height: u32) -> Vec<u8> {
+    let mut png = Cursor::new(Vec::new());
+    RgbImage::from_pixel(width, height, image::Rgb([200, 30, 30]))
+        .write_to(&mut png, ImageFormat::Png)
+        .unwrap();
+    png.into_inner()
+}
+
+#[async_std::test]
+async fn photos_get_thumbnails_and_are_stored_once() {
+    let (mut backend, _, store) = setup_with_attachments("attachments-dedup").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+
+    let content = photo(1024, 512);
+    let first = backend
+        .add_attachment(1, "brakes.png", &content, Visibility::Client)
+        .await
+        .unwrap();
+    assert_eq!(first.content_type(), "image/png");
+    assert_eq!(first.size(), content.len() as u64);
+    assert!(first.has_thumbnail());
+    let second = backend
+        .add_attachment(1, "brakes again.png", &content, Visibility::Internal)
+        .await
+        .unwrap();
+    assert_eq!(first.hash(), second.hash());
+
+    let thumbnail = backend
+        .get_attachment_thumbnail(first.id())
+        .await
+        .unwrap()
+        .unwrap();
+    let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+
+    // The content stays until the last attachment using it is deleted
+    backend.delete_attachment(first.id()).await.unwrap();
+    assert_eq!(
+        backend.get_attachment_content(second.id()).await.unwrap(),
+        content
+    );
+    backend.delete_attachment(second.id()).await.unwrap();
+    assert!(store.get(first.hash()).await.is_err());
+    assert!(matches!(
+        backend.get_attachment_content(second.id()).await,
+        Err(AttachmentError::Attachment(_))
+    ));
+
+    // Content attached again after it was deleted is stored again
+    let third = backend
+        .add_attachment(1, "brakes.png", &content, Visibility::Client)
+        .await
+        .unwrap();
+    assert!(third.has_thumbnail());
+    assert_eq!(
+        backend.get_attachment_content(third.id()).await.unwrap(),
+        content
+    );
+}
+
+#[async_std::test]
+async fn clients_only_see_attachments_shared_with_them() {
+    let (mut backend, _, _) = setup_with_attachments("attachments-visibility").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let internal = backend
+        .add_attachment(1, "worn pads.png", &photo(8, 8), Visibility::Internal)
+        .await
+        .unwrap();
+    let document = backend
+        .add_attachment(1, "quote.pdf", b"%PDF-1.4 quote", Visibility::Client)
+        .await
+        .unwrap();
+    assert_eq!(document.content_type(), "application/pdf");
+    assert!(!document.has_thumbnail());
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let attachments = backend.get_attachments(1).await.unwrap();
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].file_name(), "quote.pdf");
+    assert_eq!(
+        backend
+            .get_attachment_thumbnail(document.id())
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(matches!(
+        backend.get_attachment_content(internal.id()).await,
+        Err(AttachmentError::Attachment(_))
+    ));
+    // Clients cannot hide their own uploads from themselves
+    let upload = backend
+        .add_attachment(1, "dashboard.png", &photo(4, 4), Visibility::Internal)
+        .await
+        .unwrap();
+    assert_eq!(upload.visibility(), Visibility::Client);
+    assert_eq!(upload.employee_id(), None);
+    assert!(matches!(
+        backend.delete_attachment(document.id()).await,
+        Err(AttachmentError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    backend
+        .set_attachment_visibility(internal.id(), Visibility::Client)
+        .await
+        .unwrap();
+    assert_eq!(backend.get_attachments(1).await.unwrap().len(), 3);
+}
+
+#[async_std::test]
+async fn attachments_are_limited_in_size_and_type() {
+    let (mut backend, _, _) = setup_with_attachments("attachments-limits").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+
+    assert!(matches!(
+        backend
+            .add_attachment(1, "notes.txt", b"plain text", Visibility::Client)
+            .await,
+        Err(AttachmentError::UnsupportedType)
+    ));
+    assert!(matches!(
+        backend
+            .add_attachment(1, "empty.png", b"", Visibility::Client)
+            .await,
+        Err(AttachmentError::Empty)
+    ));
+    assert!(matches!(
+        backend
+            .add_attachment(1, " ", &photo(4, 4), Visibility::Client)
+            .await,
+        Err(AttachmentError::EmptyFileName)
+    ));
+    let mut large = b"%PDF-".to_vec();
+    large.resize(MAX_ATTACHMENT_SIZE as usize + 1, b' ');
+    assert!(matches!(
+        backend
+            .add_attachment(1, "manual.pdf", &large, Visibility::Client)
+            .await,
+        Err(AttachmentError::TooLarge { .. })
+    ));
+    assert!(matches!(
+        backend
+            .add_attachment(2, "brakes.png", &photo(4, 4), Visibility::Client)
+            .await,
+        Err(AttachmentError::Order(2))
+    ));
+}
@@ -0,0 +1,215 @@
+use car_repair_shop_backend::*;
+use sea_orm::{ConnectionTrait, Database};
+
+use std::sync::LazyLock;
+
+/// `ShopBackend::init` reads the database URL from the environment
+static SETUP: LazyLock<async_std::sync::Mutex<()>> =
+    LazyLock::new(|| async_std::sync::Mutex::new(()));
+
+/// Plain text password of every user created by [`setup`]
+pub const PASSWORD: &str = "Passw0rd!";
+
+/// Backend with a fresh database containing a manager (ID 1), a mechanic (ID 2),
+/// a technician (ID 3) and a verified client with a car
+pub async fn setup(name: &str) -> (ShopBackend, String) {
+    let path = std::env::temp_dir().join(format!("shop-{name}-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite:{}?mode=rwc", path.display());
+    let hash = bcrypt::hash(PASSWORD, 4).unwrap();
+
+    let backend = {
+        let _guard = SETUP.lock().await;
+        std::env::set_var("SHOP_DB_URL", &url);
+        std::env::remove_var("SHOP_SMTP_HOST");
+        ShopBackend::init().await.unwrap()
+    };
+
+    let db = Database::connect(&url).await.unwrap();
+    db.execute_unprepared(&format!(
+        "INSERT INTO employee (password_hash, name, role) VALUES \
+         ('{hash}', 'manager', 'Manager'), ('{hash}', 'mechanic', 'Mechanic'), \
+         ('{hash}', 'technician', 'Technician')"
+    ))
+    .await
+    .unwrap();
+
+    let mut backend = backend;
+    backend
+        .register_client("client", "client@example.com", &hash)
+        .await
+        .unwrap();
+    db.execute_unprepared("UPDATE client SET email_verified = 1")
+        .await
+        .unwrap();
+    backend.register_car(1, "VW", "Golf").await.unwrap();
+    backend.log_out().await.unwrap();
+
+    (backend, hash)
+}
+
+/// [`setup`] with an order of the client at location 1 for each of `services`,
+/// registered by the manager, nobody logged in
+#[allow(dead_code)] // Not every test needs orders
+pub async fn setup_with_orders(name: &str, services: &[Service]) -> (ShopBackend, String) {
+    let (mut backend, hash) = setup(name).await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    for service in services {
+        backend.register_order(1, 1, service, None).await.unwrap();
+    }
+    backend.log_out().await.unwrap();
+    (backend, hash)
+}
+
+/// [`setup_with_orders`] with an inspection of the client, closed if `finished`, and the
+/// technician logged in
+#[allow(dead_code)] // Only for tests of reports
+pub async fn setup_for_reports(name: &str, finished: bool) -> (ShopBackend, String) {
+    let (mut backend, hash) = setup_with_orders(name, &[Service::Inspection]).await;
+    if finished {
+        backend.employee_login(2, PASSWORD).await.unwrap();
+        backend.close_order(1, 1).await.unwrap();
+        backend.log_out().await.unwrap();
+    }
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    (backend, hash)
+}
+
+/// Makes `backend` keep attachments in a fresh directory named after `name`
+#[allow(dead_code)] // Only for tests storing attachments
+pub fn use_fresh_blob_store(backend: &mut ShopBackend, name: &str) -> FileBlobStore {
+    let store = FileBlobStore::new(std::env::temp_dir().join(format!("shop-blobs-{name}")));
+    let _ = std::fs::remove_dir_all(store.root());
+    backend.set_blob_store(store.clone());
+    store
+}
+
+/// [`setup_with_orders`] with a repair of the client and attachments kept in a fresh
+/// directory, nobody logged in
+#[allow(dead_code)] // Only for tests of attachments
+pub async fn setup_with_attachments(name: &str) -> (ShopBackend, String, FileBlobStore) {
+    let (mut backend, hash) = setup_with_orders(name, &[Service::Repair]).await;
+    let store = use_fresh_blob_store(&mut backend, name);
+    (backend, hash, store)
+}
+
+#[allow(dead_code)] // Only for tests of inspections
+pub fn inspection_item(section: &str, name: &str, unit: Option<&str>) -> NewInspectionItem {
+    NewInspectionItem {
+        section: section.to_owned(),
+        name: name.to_owned(),
+        unit: unit.map(str::to_owned),
+    }
+}
+
+/// [`setup_with_orders`] with a brake and tire checklist and an inspection of the client's
+/// car, nobody logged in
+#[allow(dead_code)] // Only for tests of inspections
+pub async fn setup_with_inspection(name: &str) -> (ShopBackend, String, InspectionTemplate) {
+    let (mut backend, hash) = setup_with_orders(name, &[Service::Inspection]).await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let template = backend
+        .add_inspection_template(
+            "Safety check",
+            &[
+                inspection_item("Brakes", "Front pad thickness", Some("mm")),
+                inspection_item("Tires", "Front left tread depth", Some("mm")),
+                inspection_item("Brakes", "Brake fluid", None),
+                inspection_item("Lights", "Headlights", None),
+            ],
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+    (backend, hash, template)
+}
+
+/// [`setup`] with a finished and reported order of the client with a complaint, a message,
+/// a mileage reading, a trouble code and attachments, nobody logged in
+#[allow(dead_code)] // Only for tests of data export
+pub async fn setup_with_client_data(name: &str) -> ShopBackend {
+    let (mut backend, _) = setup(name).await;
+    use_fresh_blob_store(&mut backend, name);
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, Some("Squealing brakes"))
+        .await
+        .unwrap();
+    backend.record_mileage(1, 84_000, None).await.unwrap();
+    backend
+        .add_attachment(
+            1,
+            "internal.pdf",
+            b"%PDF-1.4 internal",
+            Visibility::Internal,
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    backend
+        .send_order_message(1, "Please call me at 555-0100")
+        .await
+        .unwrap();
+    backend
+        .add_attachment(
+            1,
+            "dashboard/photo.pdf",
+            b"%PDF-1.4 dashboard",
+            Visibility::Client,
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend
+        .record_trouble_code(1, "P0420", None, false)
+        .await
+        .unwrap();
+    backend.close_order(1, 1).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    backend.register_report(1, 12_550).await.unwrap();
+    backend.log_out().await.unwrap();
+    backend
+}
+
+/// [`setup_with_orders`] with an inspection that became a repair, an inspection with a
+/// corrected report and an unfinished repair, some of it paid, and the manager logged in
+#[allow(dead_code)] // Only for tests of analytics
+pub async fn setup_with_business(name: &str) -> (ShopBackend, String) {
+    let (mut backend, hash) = setup_with_orders(
+        name,
+        &[Service::Inspection, Service::Inspection, Service::Repair],
+    )
+    .await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend.change_inspection_to_repair(1, 1).await.unwrap();
+    let order = backend.close_order(1, 2).await.unwrap();
+    assert_eq!(order.mechanic_id(), Some(2));
+    assert!(order.converted_to_repair());
+    assert!(order.finished_at() >= order.created_at());
+    backend.close_order(2, 1).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    backend.register_report(1, 12000).await.unwrap();
+    let report = backend.register_report(2, 3000).await.unwrap();
+    let corrected = backend
+        .correct_report(report.id(), report.version(), 4000)
+        .await
+        .unwrap();
+    backend.record_payment(1, 12000).await.unwrap();
+    backend.record_payment(corrected.id(), 1000).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    (backend, hash)
+}
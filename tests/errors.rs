@@ -0,0 +1,118 @@
+use car_repair_shop_backend::*;
+
+use serde_json::json;
+
+#[test]
+fn errors_serialize_with_code_and_fields() {
+    let error = DbError::ReportAlreadyIssued {
+        order_id: 3,
+        report_id: 7,
+    };
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({
+            "code": "report_already_issued",
+            "message": "order 3 already has report 7, correct it instead",
+            "order_id": 3,
+            "report_id": 7,
+        })
+    );
+
+    let error = DbError::Conflict("order", 2);
+    assert_eq!(error.code(), "conflict");
+    assert_eq!(serde_json::to_value(&error).unwrap()["entity"], "order");
+
+    let error = LoginError::SecondFactorRequired(4);
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({
+            "code": "second_factor_required",
+            "message": "employee 4 has to enter a two-factor authentication code",
+            "employee_id": 4,
+        })
+    );
+
+    let error = RegisterClientError::EmailAlreadyRegistered(String::from("a@example.com"));
+    assert_eq!(
+        serde_json::to_value(&error).unwrap()["code"],
+        "email_already_registered"
+    );
+}
+
+#[test]
+fn internal_errors_hide_their_details() {
+    let error = DbError::Database(sea_orm::DbErr::Custom(String::from(
+        "no such table: secret_table",
+    )));
+    assert!(error.to_string().contains("secret_table"));
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({
+            "code": "database_error",
+            "message": "internal error, try again later",
+        })
+    );
+
+    let error = LoginError::Tenant(TenantError::Database(sea_orm::DbErr::Custom(String::from(
+        "connection refused",
+    ))));
+    assert_eq!(
+        serde_json::to_value(&error).unwrap()["message"],
+        "internal error, try again later"
+    );
+
+    let error = VerificationError::Notify(NotifyError(String::from("smtp.internal:25 refused")));
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        json!({
+            "code": "notify_error",
+            "message": "internal error, try again later",
+        })
+    );
+}
+
+#[test]
+fn every_error_type_has_codes() {
+    assert_eq!(
+        serde_json::to_value(TwoFactorError::InvalidCode).unwrap(),
+        json!({
+            "code": "invalid_two_factor_code",
+            "message": "incorrect two-factor authentication code",
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(PasswordChangeError::Policy(PasswordPolicyError::TooShort(
+            12
+        )))
+        .unwrap(),
+        json!({
+            "code": "password_too_short",
+            "message": "password must be at least 12 characters long",
+            "min_length": 12,
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(ProfileError::PhoneIncorrectFormat(String::from("abc"))).unwrap()
+            ["phone"],
+        "abc"
+    );
+    assert_eq!(
+        serde_json::to_value(VerificationError::TooManyRequests(42)).unwrap()
+            ["retry_after_seconds"],
+        42
+    );
+    assert_eq!(
+        serde_json::to_value(WebhookError::Delivery(5)).unwrap(),
+        json!({
+            "code": "webhook_delivery_not_found",
+            "message": "webhook delivery 5 does not exist",
+            "delivery_id": 5,
+        })
+    );
+    let error = ExportError::Io(std::io::Error::other("disk full"));
+    assert_eq!(error.code(), "io_error");
+    assert_eq!(
+        serde_json::to_value(&error).unwrap()["message"],
+        "internal error, try again later"
+    );
+}
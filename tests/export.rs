@@ -0,0 +1,111 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup_with_client_data, PASSWORD};
+use sea_orm::{ConnectionTrait, Statement};
+
+use std::io::{Cursor, Read};
+
+fn zip_file(zip: &[u8], name: &str) -> Option<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+    let mut file = archive.by_name(name).ok()?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content).unwrap();
+    Some(content)
+}
+
+#[async_std::test]
+async fn export_contains_everything_about_the_client() {
+    let mut backend = setup_with_client_data("export-contents").await;
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let export = backend.export_client_data(1).await.unwrap();
+    assert_eq!(export.client().email(), "client@example.com");
+    assert_eq!(export.vehicles().len(), 1);
+    assert_eq!(export.orders().len(), 1);
+    assert_eq!(export.reports().len(), 1);
+    assert_eq!(export.complaints()[0].complaint(), "Squealing brakes");
+    assert_eq!(export.messages().len(), 1);
+    assert_eq!(export.mileage_readings()[0].mileage(), 84_000);
+    assert_eq!(export.trouble_codes()[0].code(), "P0420");
+    // Internal attachments are not the client's to see
+    assert_eq!(export.attachments().len(), 1);
+    assert!(matches!(
+        backend.export_client_data(2).await,
+        Err(DbError::Permission)
+    ));
+
+    let json: serde_json::Value = serde_json::from_str(&export.to_json().unwrap()).unwrap();
+    assert_eq!(json["client"]["name"], "client");
+    assert_eq!(json["vehicles"][0]["make"], "VW");
+    assert_eq!(json["complaints"][0]["order_id"], 1);
+    assert_eq!(json["messages"][0]["body"], "Please call me at 555-0100");
+    assert_eq!(json["trouble_codes"][0]["code"], "P0420");
+    assert_eq!(json["attachments"][0]["file_name"], "dashboard/photo.pdf");
+
+    let zip = export.to_zip().unwrap();
+    let data = zip_file(&zip, "data.json").unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&data).unwrap(),
+        json
+    );
+    let invoice = String::from_utf8(zip_file(&zip, "invoices/report-1.html").unwrap()).unwrap();
+    assert!(invoice.contains("$125.50"));
+    let attachment_id = export.attachments()[0].id();
+    assert_eq!(
+        zip_file(
+            &zip,
+            &format!("attachments/{attachment_id}-dashboard_photo.pdf")
+        )
+        .unwrap(),
+        b"%PDF-1.4 dashboard"
+    );
+}
+
+#[async_std::test]
+async fn deleted_accounts_keep_no_personal_data() {
+    let mut backend = setup_with_client_data("export-deleted").await;
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend.delete_client_account(1).await.unwrap();
+    let export = backend.export_client_data(1).await.unwrap();
+    assert_eq!(export.orders().len(), 1);
+    assert_eq!(export.reports().len(), 1);
+    assert!(export.complaints().is_empty());
+    assert!(export.messages().is_empty());
+    assert!(export.mileage_readings().is_empty());
+    assert!(export.trouble_codes().is_empty());
+    assert!(export.attachments().is_empty());
+    assert!(export.notifications().is_empty());
+
+    let json = export.to_json().unwrap();
+    for personal in [
+        "client@example.com",
+        "\"name\": \"client\"",
+        "Squealing brakes",
+        "555-0100",
+        "photo.pdf",
+    ] {
+        assert!(!json.contains(personal), "{personal} was exported");
+    }
+    let zip = export.to_zip().unwrap();
+    let invoice = String::from_utf8(zip_file(&zip, "invoices/report-1.html").unwrap()).unwrap();
+    assert!(!invoice.contains("client@example.com"));
+
+    // Internal attachments and queued notifications are gone as well
+    let db = backend.storage().connection();
+    for table in ["attachment", "blob", "notification_outbox", "order_note"] {
+        let row = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                format!("SELECT COUNT(*) AS count FROM {table}"),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.try_get::<i64>("", "count").unwrap(), 0, "{table}");
+    }
+}
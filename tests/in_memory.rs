@@ -0,0 +1,140 @@
+use car_repair_shop_backend::*;
+
+use async_trait::async_trait;
+
+use std::sync::{Arc, Mutex};
+
+/// Keeps the bodies of sent emails
+#[derive(Clone, Default)]
+struct Outbox(Arc<Mutex<Vec<String>>>);
+
+#[async_trait]
+impl Notifier for Outbox {
+    async fn notify(
+        &self,
+        _recipient: &str,
+        _subject: &str,
+        body: &str,
+    ) -> Result<(), NotifyError> {
+        self.0.lock().unwrap().push(body.to_owned());
+        Ok(())
+    }
+}
+
+impl Outbox {
+    fn last_token(&self) -> String {
+        let bodies = self.0.lock().unwrap();
+        let body = bodies.last().expect("no email sent");
+        let first_line = body.lines().next().unwrap();
+        first_line.rsplit(' ').next().unwrap().to_owned()
+    }
+}
+
+#[async_std::test]
+async fn order_flow_without_database() {
+    let hash = bcrypt::hash("Passw0rd!", 4).unwrap();
+    let storage = InMemoryStorage::new();
+    let mechanic = storage.insert_employee("mechanic", Role::Mechanic, &hash, None, None);
+    let technician = storage.insert_employee("technician", Role::Technician, &hash, None, None);
+
+    let mut backend = ShopBackend::with_storage(storage.clone()).await.unwrap();
+    let outbox = Outbox::default();
+    backend.set_notifier(outbox.clone());
+
+    let client = backend
+        .register_client("client", "client@example.com", &hash)
+        .await
+        .unwrap();
+    backend.verify_email(&outbox.last_token()).await.unwrap();
+    backend
+        .register_car(client.id(), "VW", "Golf")
+        .await
+        .unwrap();
+    backend
+        .register_order(
+            client.id(),
+            1,
+            &Service::Inspection,
+            Some("Rattling exhaust"),
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(mechanic, "Passw0rd!").await.unwrap();
+    assert_eq!(backend.get_unfinished_orders().await.unwrap().len(), 1);
+    assert_eq!(
+        backend.get_complaint(1).await.unwrap().as_deref(),
+        Some("Rattling exhaust")
+    );
+    backend.change_inspection_to_repair(1, 1).await.unwrap();
+    backend.close_order(1, 2).await.unwrap();
+    assert!(backend.get_unfinished_orders().await.unwrap().is_empty());
+    backend.log_out().await.unwrap();
+
+    backend
+        .employee_login(technician, "Passw0rd!")
+        .await
+        .unwrap();
+    backend.register_report(1, 250).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    // A second backend on the same storage sees everything
+    let mut other = ShopBackend::with_storage(storage).await.unwrap();
+    other
+        .client_login("client@example.com", "Passw0rd!")
+        .await
+        .unwrap();
+    let orders = other.get_client_orders().await.unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].service(), Service::Repair);
+    assert!(orders[0].finished());
+    let reports = other.get_client_reports().await.unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].cost(), 250);
+    let golf = ReportSearch {
+        vehicle: Some(String::from("golf")),
+        ..Default::default()
+    };
+    assert_eq!(other.search_reports(&golf, 0, 10).await.unwrap().total, 1);
+    assert!(other
+        .get_client(client.id())
+        .await
+        .unwrap()
+        .email_verified());
+}
+
+#[async_std::test]
+async fn dropped_transactions_change_nothing() {
+    let storage = InMemoryStorage::new();
+
+    let txn = storage.begin().await.unwrap();
+    let order = txn.insert_order(1, 1, Service::Repair, None).await.unwrap();
+    assert!(txn.order(order.id()).await.unwrap().is_some());
+    assert!(storage.order(order.id()).await.unwrap().is_none());
+    drop(txn);
+    assert!(storage.orders(None, None).await.unwrap().is_empty());
+
+    let txn = storage.begin().await.unwrap();
+    txn.insert_order(1, 1, Service::Repair, None).await.unwrap();
+    txn.commit().await.unwrap();
+    assert_eq!(storage.orders(None, None).await.unwrap().len(), 1);
+}
+
+#[async_std::test]
+async fn backends_started_together_share_the_signing_key() {
+    let hash = bcrypt::hash("Passw0rd!", 4).unwrap();
+    let storage = InMemoryStorage::new();
+    let first = async_std::task::spawn(ShopBackend::with_storage(storage.clone()));
+    let second = async_std::task::spawn(ShopBackend::with_storage(storage));
+    let mut first = first.await.unwrap();
+    let second = second.await.unwrap();
+
+    let outbox = Outbox::default();
+    first.set_notifier(outbox.clone());
+    first
+        .register_client("client", "client@example.com", &hash)
+        .await
+        .unwrap();
+    second.verify_email(&outbox.last_token()).await.unwrap();
+}
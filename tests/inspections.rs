@@ -0,0 +1,162 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{inspection_item, setup_with_inspection, PASSWORD};
+
+#[async_std::test]
+async fn templates_group_items_by_section() {
+    let (mut backend, _, template) = setup_with_inspection("inspections-templates").await;
+
+    let sections = template.sections();
+    assert_eq!(
+        sections
+            .iter()
+            .map(|(section, items)| (*section, items.len()))
+            .collect::<Vec<_>>(),
+        [("Brakes", 2), ("Tires", 1), ("Lights", 1)]
+    );
+    assert_eq!(template.items()[0].unit(), Some("mm"));
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.add_inspection_template("Empty", &[]).await,
+        Err(DbError::EmptyInspectionTemplate)
+    ));
+    assert!(matches!(
+        backend
+            .add_inspection_template("Nameless item", &[inspection_item("Brakes", " ", None)])
+            .await,
+        Err(DbError::EmptyInspectionTemplate)
+    ));
+    let templates = backend.get_inspection_templates().await.unwrap();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].items().len(), 4);
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend
+            .add_inspection_template("Mine", &[inspection_item("Brakes", "Pads", None)])
+            .await,
+        Err(DbError::Permission)
+    ));
+}
+
+#[async_std::test]
+async fn mechanics_fill_in_the_checklist() {
+    let (mut backend, _, template) = setup_with_inspection("inspections-checklist").await;
+    let [pads, tread, fluid, _] = template.items() else {
+        panic!("template should have four items");
+    };
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend
+            .record_inspection_result(1, pads.id(), InspectionStatus::Pass, Some(9.0), None)
+            .await,
+        Err(DbError::NoChecklist(1))
+    ));
+    assert!(matches!(
+        backend.start_inspection(1, 7).await,
+        Err(DbError::InspectionTemplate(7))
+    ));
+    backend.start_inspection(1, template.id()).await.unwrap();
+
+    assert!(matches!(
+        backend
+            .record_inspection_result(1, pads.id(), InspectionStatus::Fail, None, None)
+            .await,
+        Err(DbError::MissingMeasurement(_))
+    ));
+    backend
+        .record_inspection_result(1, pads.id(), InspectionStatus::Advisory, Some(3.0), None)
+        .await
+        .unwrap();
+    // Checking an item again replaces its result
+    let result = backend
+        .record_inspection_result(
+            1,
+            pads.id(),
+            InspectionStatus::Fail,
+            Some(1.5),
+            Some(" Uneven wear "),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.comment(), Some("Uneven wear"));
+    let result = backend
+        .record_inspection_result(1, fluid.id(), InspectionStatus::Pass, Some(1.0), None)
+        .await
+        .unwrap();
+    assert_eq!(result.measurement(), None);
+    backend
+        .record_inspection_result(1, tread.id(), InspectionStatus::Advisory, Some(3.2), None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let checklist = backend.get_inspection_checklist(1).await.unwrap();
+    assert_eq!(checklist.results().len(), 3);
+    assert_eq!(checklist.unchecked().len(), 1);
+    let failed = checklist.with_status(InspectionStatus::Fail);
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].1.measurement(), Some(1.5));
+    assert!(matches!(
+        backend
+            .record_inspection_result(1, tread.id(), InspectionStatus::Pass, Some(8.0), None)
+            .await,
+        Err(DbError::Permission)
+    ));
+}
+
+#[async_std::test]
+async fn failed_items_become_repair_suggestions() {
+    let (mut backend, _, template) = setup_with_inspection("inspections-suggestions").await;
+    let [pads, tread, _, lights] = template.items() else {
+        panic!("template should have four items");
+    };
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend.start_inspection(1, template.id()).await.unwrap();
+    for (item, status, measurement, comment) in [
+        (lights, InspectionStatus::Fail, None, Some("Left bulb out")),
+        (pads, InspectionStatus::Fail, Some(1.5), None),
+        (tread, InspectionStatus::Advisory, Some(3.0), None),
+    ] {
+        backend
+            .record_inspection_result(1, item.id(), status, measurement, comment)
+            .await
+            .unwrap();
+    }
+    // Starting the inspection was the second version of the order
+    let order = backend.change_inspection_to_repair(1, 2).await.unwrap();
+    assert!(matches!(
+        backend
+            .record_inspection_result(1, tread.id(), InspectionStatus::Fail, Some(1.0), None)
+            .await,
+        Err(DbError::NotInspection(1))
+    ));
+    assert_eq!(order.service(), Service::Repair);
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let suggestions = backend.get_repair_suggestions(1).await.unwrap();
+    assert_eq!(
+        suggestions
+            .iter()
+            .map(|suggestion| suggestion.description())
+            .collect::<Vec<_>>(),
+        [
+            "Brakes: Front pad thickness (1.5 mm)",
+            "Lights: Headlights - Left bulb out"
+        ]
+    );
+    assert_eq!(suggestions[1].item_id(), lights.id());
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup_with_orders, PASSWORD};
+use sea_orm::ConnectionTrait;
+
+#[async_std::test]
+async fn mechanics_work_on_one_order_at_a_time() {
+    let (mut backend, _) =
+        setup_with_orders("labor-clock", &[Service::Repair, Service::Inspection]).await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+
+    let entry = backend.clock_on(1).await.unwrap();
+    assert_eq!(entry.mechanic_id(), 2);
+    assert!(entry.ended_at().is_none());
+    assert!(matches!(
+        backend.clock_on(2).await,
+        Err(DbError::AlreadyClockedOn(1))
+    ));
+    assert!(matches!(
+        backend.clock_off(2).await,
+        Err(DbError::NotClockedOn(2))
+    ));
+
+    // Pausing the first order to work on the second one
+    let entry = backend.clock_off(1).await.unwrap();
+    assert!(entry.ended_at().is_some());
+    backend.clock_on(2).await.unwrap();
+    backend.clock_off(2).await.unwrap();
+    backend.clock_on(1).await.unwrap();
+    backend.clock_off(1).await.unwrap();
+    assert_eq!(backend.get_labor(1).await.unwrap().entries.len(), 2);
+
+    backend.close_order(1, 1).await.unwrap();
+    assert!(matches!(
+        backend.clock_on(1).await,
+        Err(DbError::OrderFinished(1))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.clock_on(2).await,
+        Err(DbError::Permission)
+    ));
+}
+
+#[async_std::test]
+async fn tracked_time_is_billed_on_reports() {
+    let (mut backend, _) =
+        setup_with_orders("labor-report", &[Service::Repair, Service::Inspection]).await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend.set_labor_estimate(1, Some(60)).await.unwrap();
+    // The estimate is a change of the order
+    assert!(matches!(
+        backend.close_order(1, 1).await,
+        Err(DbError::Conflict("order", 1))
+    ));
+    backend.close_order(1, 2).await.unwrap();
+
+    // An hour of work, a half hour break and another half hour
+    backend
+        .storage()
+        .connection()
+        .execute_unprepared(
+            "INSERT INTO labor_entry (order_id, mechanic_id, started_at, ended_at) VALUES \
+             (1, 2, '2024-03-15T10:00:00+00:00', '2024-03-15T11:00:00+00:00'), \
+             (1, 2, '2024-03-15T11:30:00+00:00', '2024-03-15T12:00:00+00:00')",
+        )
+        .await
+        .unwrap();
+    let labor = backend.get_labor(1).await.unwrap();
+    assert_eq!(labor.actual_minutes, 90);
+    assert_eq!(labor.actual_hours(), 1.5);
+    assert_eq!(labor.estimated_hours(), Some(1.0));
+    assert_eq!(labor.overrun_minutes(), Some(30));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.register_report_with_labor(1, 2500).await,
+        Err(DbError::LaborRateNotSet)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend.set_labor_rate(6000).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.register_report_with_labor(1, u32::MAX).await,
+        Err(DbError::CostTooHigh)
+    ));
+    let report = backend.register_report_with_labor(1, 2500).await.unwrap();
+    assert_eq!(report.cost(), 11500);
+    let lines = backend.get_labor_lines(report.id()).await.unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].mechanic_id(), 2);
+    assert_eq!(lines[0].minutes(), 90);
+    assert_eq!(lines[0].rate(), 6000);
+    assert_eq!(lines[0].amount(), 9000);
+    assert!(matches!(
+        backend.register_report_with_labor(1, 2500).await,
+        Err(DbError::ReportAlreadyIssued { .. })
+    ));
+
+    // Corrections bill the same labor
+    let corrected = backend
+        .correct_report(report.id(), report.version(), 12000)
+        .await
+        .unwrap();
+    let lines = backend.get_labor_lines(corrected.id()).await.unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].minutes(), 90);
+    assert_eq!(lines[0].amount(), 9000);
+}
+
+#[async_std::test]
+async fn closing_an_order_clocks_mechanics_off() {
+    let (mut backend, _) =
+        setup_with_orders("labor-close", &[Service::Repair, Service::Inspection]).await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+
+    backend.clock_on(1).await.unwrap();
+    // A second open entry of the mechanic, as a concurrent clock_on would add it
+    assert!(backend
+        .storage()
+        .connection()
+        .execute_unprepared(
+            "INSERT INTO labor_entry (order_id, mechanic_id, started_at) \
+             VALUES (2, 2, '2024-03-15T10:00:00+00:00')",
+        )
+        .await
+        .is_err());
+
+    backend.close_order(1, 1).await.unwrap();
+    let labor = backend.get_labor(1).await.unwrap();
+    assert!(labor.entries.iter().all(|entry| entry.ended_at().is_some()));
+    assert!(matches!(
+        backend.clock_off(1).await,
+        Err(DbError::NotClockedOn(1))
+    ));
+    backend.clock_on(2).await.unwrap();
+}
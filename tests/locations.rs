@@ -0,0 +1,85 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+#[async_std::test]
+async fn employees_work_at_their_location() {
+    let (mut backend, _) = setup("location-orders").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let north = backend.create_location("North", None).await.unwrap();
+    backend
+        .set_employee_location(2, Some(north.id()))
+        .await
+        .unwrap();
+    backend
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    // The mechanic works at the new location only
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.register_order(1, 1, &Service::Repair, None).await,
+        Err(DbError::Permission)
+    ));
+    assert!(backend.get_unfinished_orders().await.unwrap().is_empty());
+    assert!(matches!(
+        backend.close_order(1, 1).await,
+        Err(DbError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.transfer_order(1, 1, 9).await,
+        Err(DbError::Location(9))
+    ));
+    let order = backend.transfer_order(1, 1, north.id()).await.unwrap();
+    assert_eq!(order.location_id(), north.id());
+    let summaries = backend.get_location_summaries().await.unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0].unfinished_orders, 0);
+    assert_eq!(summaries[1].unfinished_orders, 1);
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert_eq!(backend.get_unfinished_orders().await.unwrap().len(), 1);
+    backend.close_order(1, 2).await.unwrap();
+}
+
+#[async_std::test]
+async fn stock_moves_between_locations() {
+    let (mut backend, _) = setup("location-stock").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let north = backend.create_location("North", None).await.unwrap();
+    backend.create_bay(north.id(), "Lift 1").await.unwrap();
+    assert_eq!(backend.get_bays(north.id()).await.unwrap().len(), 1);
+
+    backend
+        .add_stock(1, "OF-100", "Oil filter", 5)
+        .await
+        .unwrap();
+    let moved = backend
+        .transfer_stock("OF-100", 1, north.id(), 3)
+        .await
+        .unwrap();
+    assert_eq!(moved.quantity(), 3);
+    assert_eq!(moved.name(), "Oil filter");
+
+    assert!(matches!(
+        backend.transfer_stock("OF-100", 1, north.id(), 10).await,
+        Err(DbError::InsufficientStock { available: 2, .. })
+    ));
+    assert_eq!(backend.get_stock(1).await.unwrap()[0].quantity(), 2);
+    backend.set_employee_location(2, Some(1)).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    // Mechanics only see the stock of their own location
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.get_stock(north.id()).await,
+        Err(DbError::Permission)
+    ));
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use chrono::NaiveDate;
+use common::{setup, PASSWORD};
+use sea_orm::ConnectionTrait;
+
+#[async_std::test]
+async fn schedules_need_a_name_and_an_interval() {
+    let (mut backend, _) = setup("maintenance-schedules").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+
+    assert!(matches!(
+        backend
+            .add_maintenance_schedule(" ", &Service::Repair, None, Some(10_000), None)
+            .await,
+        Err(DbError::EmptyScheduleName)
+    ));
+    assert!(matches!(
+        backend
+            .add_maintenance_schedule("Oil change", &Service::Repair, None, Some(0), None)
+            .await,
+        Err(DbError::NoMaintenanceInterval)
+    ));
+    let schedule = backend
+        .add_maintenance_schedule(
+            "Timing belt",
+            &Service::Repair,
+            Some("Volvo"),
+            None,
+            Some(60),
+        )
+        .await
+        .unwrap();
+    assert!(!schedule.applies_to(&Car {
+        make: String::from("VW"),
+        model: String::from("Golf"),
+    }));
+    backend
+        .delete_maintenance_schedule(schedule.id())
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend.delete_maintenance_schedule(schedule.id()).await,
+        Err(DbError::MaintenanceSchedule(_))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend
+            .add_maintenance_schedule("Oil change", &Service::Repair, None, Some(10_000), None)
+            .await,
+        Err(DbError::Permission)
+    ));
+}
+
+#[async_std::test]
+async fn next_due_date_follows_time_and_mileage() {
+    let (mut backend, _) = setup("maintenance-due").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let oil_change = backend
+        .add_maintenance_schedule("Oil change", &Service::Repair, None, Some(10_000), Some(12))
+        .await
+        .unwrap();
+    backend
+        .add_maintenance_schedule(
+            "Timing belt",
+            &Service::Repair,
+            Some("Volvo"),
+            None,
+            Some(60),
+        )
+        .await
+        .unwrap();
+    for service in [Service::Repair, Service::Inspection] {
+        backend.register_order(1, 1, &service, None).await.unwrap();
+    }
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend.record_mileage(1, 100_000, None).await.unwrap();
+    backend.record_mileage(2, 105_000, None).await.unwrap();
+    backend.close_order(1, 1).await.unwrap();
+    // Oil changed in January, 5000 km driven in the half year after
+    backend
+        .storage()
+        .connection()
+        .execute_unprepared(
+            "UPDATE \"order\" SET finished_at = '2023-01-10T10:00:00+00:00' WHERE id = 1; \
+             UPDATE mileage_reading SET recorded_at = '2023-01-10T09:00:00+00:00' WHERE id = 1; \
+             UPDATE mileage_reading SET recorded_at = '2023-07-10T09:00:00+00:00' WHERE id = 2",
+        )
+        .await
+        .unwrap();
+
+    // A finished repair is only an oil change once it is recorded as one
+    let due = backend.get_maintenance_due(1).await.unwrap();
+    assert_eq!(due[0].last_order_id, None);
+    backend
+        .record_maintenance_done(1, oil_change.id())
+        .await
+        .unwrap();
+    backend
+        .record_maintenance_done(1, oil_change.id())
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend.record_maintenance_done(1, 99).await,
+        Err(DbError::MaintenanceSchedule(99))
+    ));
+
+    let due = backend.get_maintenance_due(1).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].schedule.name(), "Oil change");
+    assert_eq!(due[0].last_order_id, Some(1));
+    assert_eq!(due[0].due_mileage, Some(110_000));
+    // 110,000 km are expected before the twelve months are over
+    assert_eq!(due[0].due_on, NaiveDate::from_ymd_opt(2024, 1, 7));
+    backend.log_out().await.unwrap();
+
+    assert_eq!(backend.send_maintenance_reminders(30).await.unwrap(), 1);
+    assert_eq!(backend.send_maintenance_reminders(30).await.unwrap(), 0);
+    backend.deliver_pending().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let notifications = backend.get_notifications().await.unwrap();
+    assert!(notifications
+        .iter()
+        .any(|notification| notification.subject() == "Oil change due soon"));
+}
+
+#[async_std::test]
+async fn clients_can_opt_out_of_reminders() {
+    let (mut backend, _) = setup("maintenance-opt-out").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend
+        .add_maintenance_schedule("Inspection", &Service::Inspection, None, None, Some(1))
+        .await
+        .unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+    // Never inspected here, so due a month after the car was first brought in
+    backend
+        .storage()
+        .connection()
+        .execute_unprepared(
+            "UPDATE \"order\" SET created_at = '2024-01-01T10:00:00+00:00' WHERE id = 1",
+        )
+        .await
+        .unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let due = backend.get_maintenance_due(1).await.unwrap();
+    assert_eq!(due[0].last_order_id, None);
+    assert_eq!(due[0].due_on, NaiveDate::from_ymd_opt(2024, 2, 1));
+
+    let mut preferences = CommunicationPreferences {
+        maintenance_reminders: false,
+        ..Default::default()
+    };
+    backend
+        .set_communication_preferences(1, preferences)
+        .await
+        .unwrap();
+    assert_eq!(backend.send_maintenance_reminders(30).await.unwrap(), 0);
+
+    preferences.maintenance_reminders = true;
+    backend
+        .set_communication_preferences(1, preferences)
+        .await
+        .unwrap();
+    assert_eq!(backend.send_maintenance_reminders(30).await.unwrap(), 1);
+}
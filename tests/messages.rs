@@ -0,0 +1,123 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, setup_with_orders, PASSWORD};
+
+#[async_std::test]
+async fn only_employees_see_internal_notes() {
+    let (mut backend, _) = setup("messages-notes").await;
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend
+            .register_order(1, 1, &Service::Repair, Some("  "))
+            .await,
+        Err(DbError::EmptyText)
+    ));
+    let order = backend
+        .register_order(1, 1, &Service::Repair, Some(" Squeaking brakes "))
+        .await
+        .unwrap();
+    assert_eq!(order.id(), 1);
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert_eq!(
+        backend.get_complaint(1).await.unwrap().as_deref(),
+        Some("Squeaking brakes")
+    );
+    let note = backend
+        .add_order_note(1, "Front pads worn down to the metal")
+        .await
+        .unwrap();
+    assert_eq!(note.employee_id(), 2);
+    assert!(matches!(
+        backend.add_order_note(2, "Wrong order").await,
+        Err(DbError::Order(2))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(backend.get_complaint(1).await.unwrap().is_some());
+    assert!(matches!(
+        backend.get_order_notes(1).await,
+        Err(DbError::Permission)
+    ));
+    assert!(matches!(
+        backend.add_order_note(1, "Please hurry").await,
+        Err(DbError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    let notes = backend.get_order_notes(1).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].body(), "Front pads worn down to the metal");
+}
+
+#[async_std::test]
+async fn messages_are_marked_read_by_the_other_side() {
+    let (mut backend, _) = setup_with_orders("messages-thread", &[Service::Repair]).await;
+    let mut events = backend.subscribe();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let message = backend
+        .send_order_message(1, "Can I pick it up on Friday?")
+        .await
+        .unwrap();
+    assert!(message.from_client());
+    // Reading the thread does not mark the client's own messages as read
+    let thread = backend.get_order_messages(1).await.unwrap();
+    assert!(thread[0].read_at().is_none());
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let thread = backend.get_order_messages(1).await.unwrap();
+    assert!(thread[0].read_at().is_some());
+    let reply = backend
+        .send_order_message(1, "Yes, it will be ready by noon")
+        .await
+        .unwrap();
+    assert_eq!(reply.employee_id(), Some(2));
+    backend.log_out().await.unwrap();
+    let mut senders = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let ShopEvent::OrderMessageSent {
+            order_id: 1,
+            client_id: 1,
+            employee_id,
+            ..
+        } = event
+        {
+            senders.push(employee_id);
+        }
+    }
+    assert_eq!(senders, [None, Some(2)]);
+    backend.deliver_pending().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(backend
+        .get_notifications()
+        .await
+        .unwrap()
+        .iter()
+        .any(|notification| notification.subject() == "New message about order 1"));
+    let thread = backend.get_order_messages(1).await.unwrap();
+    assert_eq!(thread.len(), 2);
+    assert!(thread.iter().all(|message| message.read_at().is_some()));
+    assert!(matches!(
+        backend.send_order_message(2, "Hello?").await,
+        Err(DbError::Order(2))
+    ));
+}
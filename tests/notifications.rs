@@ -0,0 +1,225 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, Statement};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+struct Mail {
+    recipient: String,
+    data: String,
+}
+
+/// SMTP server on a random local port that refuses the recipient of the first
+/// `failures` connections with a temporary error and keeps every accepted mail
+struct FakeSmtp {
+    port: u16,
+    mails: Arc<Mutex<Vec<Mail>>>,
+}
+
+impl FakeSmtp {
+    fn start(failures: usize) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mails = Arc::new(Mutex::new(Vec::new()));
+
+        let received = mails.clone();
+        thread::spawn(move || {
+            runtime.block_on(async move {
+                for connection in 0.. {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut recipient = String::new();
+
+                    reader
+                        .get_mut()
+                        .write_all(b"220 localhost ESMTP\r\n")
+                        .await
+                        .unwrap();
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).await.unwrap() > 0 {
+                        let command = line.trim_end().to_owned();
+                        line.clear();
+                        let reply = if command.starts_with("EHLO") {
+                            "250-localhost\r\n250 OK\r\n"
+                        } else if let Some(to) = command.strip_prefix("RCPT TO:") {
+                            recipient = to.trim_matches(['<', '>']).to_owned();
+                            if connection < failures {
+                                "451 Try again later\r\n"
+                            } else {
+                                "250 OK\r\n"
+                            }
+                        } else if command == "DATA" {
+                            reader
+                                .get_mut()
+                                .write_all(b"354 Go ahead\r\n")
+                                .await
+                                .unwrap();
+                            let mut data = String::new();
+                            loop {
+                                reader.read_line(&mut line).await.unwrap();
+                                if line == ".\r\n" {
+                                    break;
+                                }
+                                data.push_str(&line);
+                                line.clear();
+                            }
+                            line.clear();
+                            received.lock().unwrap().push(Mail {
+                                recipient: recipient.clone(),
+                                data,
+                            });
+                            "250 Queued\r\n"
+                        } else if command == "QUIT" {
+                            reader.get_mut().write_all(b"221 Bye\r\n").await.unwrap();
+                            break;
+                        } else {
+                            "250 OK\r\n"
+                        };
+                        reader.get_mut().write_all(reply.as_bytes()).await.unwrap();
+                    }
+                }
+            });
+        });
+
+        FakeSmtp { port, mails }
+    }
+
+    fn received(&self) -> Vec<Mail> {
+        std::mem::take(&mut self.mails.lock().unwrap())
+    }
+}
+
+/// Keeps the text messages instead of sending them
+#[derive(Clone, Default)]
+struct Phone {
+    texts: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+#[async_trait]
+impl SmsGateway for Phone {
+    async fn send_sms(&self, phone: &str, text: &str) -> Result<(), NotifyError> {
+        self.texts
+            .lock()
+            .unwrap()
+            .push((phone.to_owned(), text.to_owned()));
+        Ok(())
+    }
+}
+
+/// Attempts and last error of the email in the outbox
+async fn email_attempts(backend: &ShopBackend) -> (i32, Option<String>) {
+    let db = backend.storage().connection();
+    let row = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT attempts, last_error FROM notification_outbox WHERE channel = 'Email'",
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    (
+        row.try_get("", "attempts").unwrap(),
+        row.try_get("", "last_error").unwrap(),
+    )
+}
+
+#[async_std::test]
+async fn notifications_are_sent_by_the_dispatch_job_and_retried() {
+    let (mut backend, _) = setup("notifications-smtp").await;
+    let smtp = FakeSmtp::start(1);
+    let phone = Phone::default();
+    backend.set_notifier(SmtpNotifier::new(
+        "127.0.0.1",
+        smtp.port,
+        "shop@example.com",
+    ));
+    backend.set_sms_notifier(SmsNotifier::new(phone.clone()));
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    backend
+        .update_profile(1, "client", "client@example.com", Some("+1 555 0100"), None)
+        .await
+        .unwrap();
+    backend
+        .set_communication_preferences(
+            1,
+            CommunicationPreferences {
+                email: true,
+                sms: true,
+                marketing: false,
+                maintenance_reminders: true,
+            },
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    // Registering the order only queued the messages
+    assert!(smtp.received().is_empty());
+    assert!(phone.texts.lock().unwrap().is_empty());
+
+    // The mail server refuses the first attempt, the in-app notification and the text go out
+    assert_eq!(backend.deliver_pending().await.unwrap(), 2);
+    assert!(smtp.received().is_empty());
+    let (attempts, last_error) = email_attempts(&backend).await;
+    assert_eq!(attempts, 1);
+    assert!(last_error.unwrap().contains("451"));
+    assert_eq!(
+        *phone.texts.lock().unwrap(),
+        [(
+            String::from("+1 555 0100"),
+            String::from("Order 1 received: We have received your order 1 for repair.")
+        )]
+    );
+
+    // The retry waits for its delay
+    assert_eq!(backend.deliver_pending().await.unwrap(), 0);
+    let db = backend.storage().connection();
+    db.execute_unprepared(
+        "UPDATE notification_outbox SET next_attempt_at = '2000-01-01 00:00:00+00:00'",
+    )
+    .await
+    .unwrap();
+    assert_eq!(backend.deliver_pending().await.unwrap(), 1);
+    assert_eq!(email_attempts(&backend).await, (2, None));
+
+    let mails = smtp.received();
+    assert_eq!(mails.len(), 1);
+    assert_eq!(mails[0].recipient, "client@example.com");
+    assert!(mails[0].data.contains("From: <shop@example.com>\r\n"));
+    assert!(mails[0].data.contains("Subject: Order 1 received\r\n"));
+    assert!(mails[0]
+        .data
+        .ends_with("\r\n\r\nWe have received your order 1 for repair.\r\n"));
+    assert_eq!(backend.deliver_pending().await.unwrap(), 0);
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(
+        backend.get_notifications().await.unwrap()[0].subject(),
+        "Order 1 received"
+    );
+}
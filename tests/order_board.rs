@@ -0,0 +1,187 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+async fn next(subscription: &mut BoardSubscription) -> BoardEvent {
+    async_std::future::timeout(Duration::from_secs(5), subscription.next())
+        .await
+        .expect("no board event")
+}
+
+fn order_change(event: &BoardEvent) -> (OrderChange, Order) {
+    match event.update() {
+        BoardUpdate::Order { change, order } => (*change, *order),
+        update => panic!("expected an order change, got {update:?}"),
+    }
+}
+
+#[async_std::test]
+async fn board_follows_orders_and_resumes_from_event_id() {
+    let (mut backend, _) = setup("order-board").await;
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let board = backend.order_board().await.unwrap();
+    let mut live = board.subscribe(None);
+    let snapshot = next(&mut live).await;
+    assert_eq!(snapshot.id(), 0);
+    assert!(matches!(snapshot.update(), BoardUpdate::Snapshot { orders } if orders.is_empty()));
+
+    backend
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+    backend.change_inspection_to_repair(1, 1).await.unwrap();
+    backend.close_order(1, 2).await.unwrap();
+
+    let created = next(&mut live).await;
+    assert_eq!(created.id(), 1);
+    assert_eq!(order_change(&created).0, OrderChange::Created);
+    let updated = next(&mut live).await;
+    assert_eq!(updated.id(), 2);
+    let (change, order) = order_change(&updated);
+    assert_eq!(change, OrderChange::Updated);
+    assert_eq!(order.service(), Service::Repair);
+    let closed = next(&mut live).await;
+    assert_eq!(closed.id(), 3);
+    let (change, order) = order_change(&closed);
+    assert_eq!(change, OrderChange::Closed);
+    assert!(order.finished());
+
+    // Resuming only replays what was missed
+    let mut resumed = board.subscribe(Some(1));
+    assert_eq!(next(&mut resumed).await.id(), 2);
+    assert_eq!(next(&mut resumed).await.id(), 3);
+
+    // Unknown event IDs start over with a snapshot
+    let mut restarted = board.subscribe(Some(99));
+    let snapshot = next(&mut restarted).await;
+    assert_eq!(snapshot.id(), 3);
+    match snapshot.update() {
+        BoardUpdate::Snapshot { orders } => {
+            assert_eq!(orders.len(), 1);
+            assert!(orders[0].finished());
+        }
+        update => panic!("expected a snapshot, got {update:?}"),
+    }
+}
+
+#[async_std::test]
+async fn employees_see_the_board_of_their_location() {
+    let (mut backend, _) = setup("order-board-location").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let north = backend.create_location("North", None).await.unwrap();
+    backend
+        .set_employee_location(2, Some(north.id()))
+        .await
+        .unwrap();
+    backend
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let board = backend.order_board().await.unwrap();
+    assert!(board.orders().is_empty());
+    let mut live = board.subscribe(None);
+    let snapshot = next(&mut live).await;
+    assert!(matches!(snapshot.update(), BoardUpdate::Snapshot { orders } if orders.is_empty()));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, None)
+        .await
+        .unwrap();
+    backend.transfer_order(1, 1, north.id()).await.unwrap();
+    backend.transfer_order(1, 2, 1).await.unwrap();
+
+    // The order at the other location never shows up
+    let (change, order) = order_change(&next(&mut live).await);
+    assert_eq!(change, OrderChange::Updated);
+    assert_eq!(order.id(), 1);
+    assert_eq!(order.location_id(), north.id());
+    let (change, order) = order_change(&next(&mut live).await);
+    assert_eq!(change, OrderChange::Removed);
+    assert_eq!(order.id(), 1);
+    assert!(board.orders().is_empty());
+}
+
+#[async_std::test]
+async fn board_is_served_over_server_sent_events() {
+    let (mut backend, _) = setup("order-board-http").await;
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, None)
+        .await
+        .unwrap();
+    let board = backend.order_board().await.unwrap();
+    backend.close_order(1, 1).await.unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    async_std::task::spawn(async move { board.serve(listener, "s3cret").await });
+
+    let (unauthorized, page, events) = async_std::task::spawn_blocking(move || {
+        let get = |request: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let unauthorized = [
+            get("GET / HTTP/1.1\r\n\r\n"),
+            get("GET /events?token=wrong HTTP/1.1\r\n\r\n"),
+            get("GET /events HTTP/1.1\r\nAuthorization: Bearer s3cre\r\n\r\n"),
+        ];
+        let page = get("GET /?token=s3cret HTTP/1.1\r\n\r\n");
+        // Query parameters are URL-decoded
+        assert!(get("GET /?token=s3%63ret HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 200 OK"));
+
+        let stream = TcpStream::connect(address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        (&stream)
+            .write_all(
+                b"GET /events HTTP/1.1\r\nAuthorization: Bearer s3cret\r\nLast-Event-ID: 0\r\n\r\n",
+            )
+            .unwrap();
+        let mut events = Vec::new();
+        for line in BufReader::new(stream).lines() {
+            let line = line.unwrap();
+            let done = line.starts_with("data:");
+            events.push(line);
+            if done {
+                break;
+            }
+        }
+        (unauthorized, page, events)
+    })
+    .await;
+
+    for response in unauthorized {
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized"));
+    }
+    assert!(page.starts_with("HTTP/1.1 200 OK"));
+    assert!(page.contains("new EventSource(\"/events\" + location.search)"));
+
+    assert_eq!(events[0], "HTTP/1.1 200 OK");
+    assert!(events.contains(&String::from("Content-Type: text/event-stream")));
+    assert!(events.contains(&String::from("id: 1")));
+    assert!(events.contains(&String::from("event: order")));
+    let data: serde_json::Value =
+        serde_json::from_str(events.last().unwrap().strip_prefix("data: ").unwrap()).unwrap();
+    assert_eq!(data["change"], "closed");
+    assert_eq!(data["order"]["id"], 1);
+    assert_eq!(data["order"]["finished"], true);
+    // Anyone at the reception desk sees the board, so it does not tell whose order it is
+    assert!(data["order"].get("client_id").is_none());
+}
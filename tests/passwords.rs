@@ -0,0 +1,138 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+use async_trait::async_trait;
+
+use std::sync::{Arc, Mutex};
+
+/// Keeps the bodies of sent emails
+#[derive(Clone, Default)]
+struct Outbox(Arc<Mutex<Vec<String>>>);
+
+#[async_trait]
+impl Notifier for Outbox {
+    async fn notify(
+        &self,
+        _recipient: &str,
+        _subject: &str,
+        body: &str,
+    ) -> Result<(), NotifyError> {
+        self.0.lock().unwrap().push(body.to_owned());
+        Ok(())
+    }
+}
+
+impl Outbox {
+    fn last_token(&self) -> String {
+        let bodies = self.0.lock().unwrap();
+        let body = bodies.last().expect("no email sent");
+        let first_line = body.lines().next().unwrap();
+        first_line.rsplit(' ').next().unwrap().to_owned()
+    }
+}
+
+#[async_std::test]
+async fn changed_password_is_used_for_the_next_login() {
+    let (mut backend, _) = setup("password-change").await;
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend.change_password("Wrong0ne!", "N3w-Secret").await,
+        Err(PasswordChangeError::IncorrectPassword)
+    ));
+    assert!(matches!(
+        backend.change_password(PASSWORD, "short").await,
+        Err(PasswordChangeError::Policy(_))
+    ));
+    backend
+        .change_password(PASSWORD, "N3w-Secret")
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    assert!(matches!(
+        backend.client_login("client@example.com", PASSWORD).await,
+        Err(LoginError::ClientIncorrectPassword(_))
+    ));
+    backend
+        .client_login("client@example.com", "N3w-Secret")
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend
+        .change_password(PASSWORD, "Mech4nic!")
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+    assert!(matches!(
+        backend.employee_login(2, PASSWORD).await,
+        Err(LoginError::EmployeeIncorrectPassword(2))
+    ));
+    backend.employee_login(2, "Mech4nic!").await.unwrap();
+}
+
+#[async_std::test]
+async fn reset_password_is_used_for_the_next_login() {
+    let (mut backend, _) = setup("password-reset").await;
+    let outbox = Outbox::default();
+    backend.set_notifier(outbox.clone());
+
+    backend
+        .request_password_reset("client@example.com")
+        .await
+        .unwrap();
+    let token = outbox.last_token();
+    assert!(matches!(
+        backend.reset_password(&token, "weak").await,
+        Err(PasswordChangeError::Policy(_))
+    ));
+    backend
+        .reset_password(&token, "R3set-Secret")
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend.reset_password(&token, "Other-S3cret").await,
+        Err(PasswordChangeError::InvalidResetToken)
+    ));
+
+    assert!(matches!(
+        backend.client_login("client@example.com", PASSWORD).await,
+        Err(LoginError::ClientIncorrectPassword(_))
+    ));
+    backend
+        .client_login("client@example.com", "R3set-Secret")
+        .await
+        .unwrap();
+}
+
+#[async_std::test]
+async fn reset_requests_do_not_reveal_accounts() {
+    let (backend, _) = setup("password-no-notifier").await;
+
+    // Without a notifier nothing is sent, which looks the same as an unknown address
+    backend
+        .request_password_reset("client@example.com")
+        .await
+        .unwrap();
+    backend
+        .request_password_reset("nobody@example.com")
+        .await
+        .unwrap();
+
+    let mut out = Vec::new();
+    ConsoleNotifier::new(&mut out)
+        .notify("client@example.com", "Password reset", "token")
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "To: client@example.com\nSubject: Password reset\n\ntoken\n\n"
+    );
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+#[async_std::test]
+async fn clients_only_edit_their_own_profile() {
+    let (mut backend, hash) = setup("profile-permission").await;
+
+    backend
+        .register_client("other", "other@example.com", &hash)
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend
+            .update_profile(1, "Mallory", "client@example.com", None, None)
+            .await,
+        Err(ProfileError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend
+            .update_profile(1, "Mallory", "client@example.com", None, None)
+            .await,
+        Err(ProfileError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let client = backend
+        .update_profile(
+            1,
+            " Alice ",
+            "client@example.com",
+            Some("+1 555 0100"),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(client.name(), "Alice");
+    assert_eq!(client.phone(), Some("+1 555 0100"));
+    // The address did not change, so it stays verified
+    assert!(client.email_verified());
+    assert_eq!(backend.user().name(), "Alice");
+}
+
+#[async_std::test]
+async fn emails_stay_unique() {
+    let (mut backend, hash) = setup("profile-duplicate").await;
+    backend
+        .register_client("other", "other@example.com", &hash)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend
+            .update_profile(1, "client", "other@example.com", None, None)
+            .await,
+        Err(ProfileError::EmailAlreadyRegistered(email)) if email == "other@example.com"
+    ));
+    let client = backend.get_client(1).await.unwrap();
+    assert_eq!(client.email(), "client@example.com");
+    assert!(client.email_verified());
+}
+
+#[async_std::test]
+async fn changing_the_email_resets_verification() {
+    let (mut backend, _) = setup("profile-email").await;
+
+    // Without a notifier the verification email cannot be sent, the change is kept anyway
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let client = backend
+        .update_profile(1, "Alice", "alice@example.com", None, None)
+        .await
+        .unwrap();
+    assert_eq!(client.email(), "alice@example.com");
+    assert!(!client.email_verified());
+    assert_eq!(backend.user().name(), "Alice");
+    assert!(!backend.get_client(1).await.unwrap().email_verified());
+    backend.log_out().await.unwrap();
+
+    assert!(matches!(
+        backend.client_login("client@example.com", PASSWORD).await,
+        Err(LoginError::EmailNotRegistered(_))
+    ));
+    backend
+        .client_login("alice@example.com", PASSWORD)
+        .await
+        .unwrap();
+}
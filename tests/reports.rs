@@ -0,0 +1,198 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup_for_reports, setup_with_orders, PASSWORD};
+
+#[async_std::test]
+async fn unfinished_order_has_no_report() {
+    let (backend, _) = setup_for_reports("report-unfinished", false).await;
+
+    assert!(matches!(
+        backend.register_report(1, 100).await,
+        Err(DbError::OrderNotFinished(1))
+    ));
+    assert!(matches!(
+        backend.register_report(2, 100).await,
+        Err(DbError::Order(2))
+    ));
+}
+
+#[async_std::test]
+async fn order_has_one_report() {
+    let (backend, _) = setup_for_reports("report-once", true).await;
+
+    let report = backend.register_report(1, 100).await.unwrap();
+    assert_eq!(report.revision(), 1);
+    assert!(matches!(
+        backend.register_report(1, 200).await,
+        Err(DbError::ReportAlreadyIssued {
+            order_id: 1,
+            report_id: 1
+        })
+    ));
+}
+
+#[async_std::test]
+async fn correction_supersedes_report() {
+    let (mut backend, _) = setup_for_reports("report-correction", true).await;
+    let mut events = backend.subscribe();
+
+    let report = backend.register_report(1, 100).await.unwrap();
+    let corrected = backend
+        .correct_report(report.id(), report.version(), 80)
+        .await
+        .unwrap();
+    assert_eq!(corrected.revision(), 2);
+    assert_eq!(corrected.order_id(), 1);
+
+    // The report was corrected already, so its version is stale
+    assert!(matches!(
+        backend
+            .correct_report(report.id(), report.version(), 90)
+            .await,
+        Err(DbError::Conflict("report", _))
+    ));
+
+    assert!(matches!(
+        events.try_recv(),
+        Ok(ShopEvent::ReportRegistered { cost: 100, .. })
+    ));
+    assert!(matches!(
+        events.try_recv(),
+        Ok(ShopEvent::ReportCorrected { cost: 80, .. })
+    ));
+
+    // Both reports are kept for the client
+    backend.log_out().await.unwrap();
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let reports = backend.get_client_reports().await.unwrap();
+    assert_eq!(reports.len(), 2);
+    let superseded = reports.iter().find(|r| r.id() == report.id()).unwrap();
+    assert_eq!(superseded.superseded_by(), Some(corrected.id()));
+}
+
+#[async_std::test]
+async fn reports_are_visible_to_owner_and_staff() {
+    let (mut backend, hash) = setup_for_reports("report-access", true).await;
+    let report = backend.register_report(1, 100).await.unwrap();
+    assert_eq!(backend.get_report(report.id()).await.unwrap().cost(), 100);
+    assert!(matches!(
+        backend.get_report(report.id() + 1).await,
+        Err(DbError::Report(_))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend.get_report(report.id()).await,
+        Err(DbError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(backend.get_report(report.id()).await.is_ok());
+    backend.log_out().await.unwrap();
+
+    backend
+        .register_client("other", "other@example.com", &hash)
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend.get_report(report.id()).await,
+        Err(DbError::Permission)
+    ));
+}
+
+#[async_std::test]
+async fn reports_are_searched_by_page() {
+    let (mut backend, _) = setup_with_orders("report-search", &[Service::Inspection; 3]).await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    for order_id in 1..=3 {
+        backend.close_order(order_id, 1).await.unwrap();
+    }
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    for order_id in 1..=3 {
+        backend
+            .register_report(order_id, order_id * 100)
+            .await
+            .unwrap();
+    }
+    backend.record_payment(2, 200).await.unwrap();
+    backend.correct_report(3, 1, 250).await.unwrap();
+
+    let all = ReportSearch::default();
+    let page = backend.search_reports(&all, 0, 2).await.unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(page.pages(), 2);
+    let ids: Vec<u32> = page.reports.iter().map(|r| r.id()).collect();
+    assert_eq!(ids, [1, 2]);
+    let page = backend.search_reports(&all, 1, 2).await.unwrap();
+    let ids: Vec<u32> = page.reports.iter().map(|r| r.id()).collect();
+    assert_eq!(ids, [4]);
+
+    let with_history = ReportSearch {
+        include_superseded: true,
+        ..Default::default()
+    };
+    let page = backend.search_reports(&with_history, 0, 10).await.unwrap();
+    assert_eq!(page.total, 4);
+
+    let unpaid = ReportSearch {
+        paid: Some(false),
+        min_cost: Some(150),
+        ..Default::default()
+    };
+    let page = backend.search_reports(&unpaid, 0, 10).await.unwrap();
+    let ids: Vec<u32> = page.reports.iter().map(|r| r.id()).collect();
+    assert_eq!(ids, [4]);
+
+    let paid = ReportSearch {
+        paid: Some(true),
+        vehicle: Some(String::from("golf")),
+        issued_from: Some(chrono::Utc::now() - chrono::Duration::hours(1)),
+        ..Default::default()
+    };
+    let page = backend.search_reports(&paid, 0, 10).await.unwrap();
+    let ids: Vec<u32> = page.reports.iter().map(|r| r.id()).collect();
+    assert_eq!(ids, [2]);
+
+    let other_car = ReportSearch {
+        vehicle: Some(String::from("Audi")),
+        ..Default::default()
+    };
+    let page = backend.search_reports(&other_car, 0, 10).await.unwrap();
+    assert_eq!(page.total, 0);
+
+    // Wildcards in the vehicle are matched literally
+    for vehicle in ["%", "o_f"] {
+        let wildcard = ReportSearch {
+            vehicle: Some(String::from(vehicle)),
+            ..Default::default()
+        };
+        let page = backend.search_reports(&wildcard, 0, 10).await.unwrap();
+        assert_eq!(page.total, 0, "{vehicle}");
+    }
+    backend.log_out().await.unwrap();
+
+    // Clients only find their own reports
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let page = backend.search_reports(&all, 0, 10).await.unwrap();
+    assert_eq!(page.total, 3);
+    let someone_else = ReportSearch {
+        client_id: Some(2),
+        ..Default::default()
+    };
+    let page = backend.search_reports(&someone_else, 0, 10).await.unwrap();
+    assert_eq!(page.total, 0);
+}
@@ -0,0 +1,113 @@
+use car_repair_shop_backend::*;
+use sea_orm::{ConnectionTrait, Database};
+
+/// Database URL of a fresh SQLite file
+fn database(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("shop-{name}-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    format!("sqlite:{}?mode=rwc", path.display())
+}
+
+/// Adds a manager (ID 1), a mechanic (ID 2) and a technician (ID 3) to a shop
+async fn add_employees(db_url: &str, hash: &str) {
+    let db = Database::connect(db_url).await.unwrap();
+    db.execute_unprepared(&format!(
+        "INSERT INTO employee (password_hash, name, role) VALUES \
+         ('{hash}', 'manager', 'Manager'), ('{hash}', 'mechanic', 'Mechanic'), \
+         ('{hash}', 'technician', 'Technician')"
+    ))
+    .await
+    .unwrap();
+}
+
+#[async_std::test]
+async fn shops_never_see_each_other() {
+    let hash = bcrypt::hash("Passw0rd!", 4).unwrap();
+    let north_db = database("tenant-north");
+    let south_db = database("tenant-south");
+    let directory = TenantDirectory::new();
+    directory.add_tenant("north", &north_db).await.unwrap();
+    directory.add_tenant("south", &south_db).await.unwrap();
+    add_employees(&north_db, &hash).await;
+    add_employees(&south_db, &hash).await;
+    assert_eq!(directory.tenants(), ["north", "south"]);
+
+    // A finished order with a report at the northern shop
+    let mut north = directory.backend("north").await.unwrap();
+    assert_eq!(north.tenant(), Some("north"));
+    north
+        .register_client("alice", "alice@example.com", &hash)
+        .await
+        .unwrap();
+    north.log_out().await.unwrap();
+    let (mut north, _) = directory
+        .employee_login("north", 1, "Passw0rd!")
+        .await
+        .unwrap();
+    north.register_car(1, "VW", "Golf").await.unwrap();
+    north
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+    north.log_out().await.unwrap();
+    north.employee_login(2, "Passw0rd!").await.unwrap();
+    north.close_order(1, 1).await.unwrap();
+    north.log_out().await.unwrap();
+    north.employee_login(3, "Passw0rd!").await.unwrap();
+    north.register_report(1, 100).await.unwrap();
+
+    // The same email address belongs to a different client at the southern shop
+    let mut south = directory.backend("south").await.unwrap();
+    let mut south_events = south.subscribe();
+    south
+        .register_client("bob", "alice@example.com", &hash)
+        .await
+        .unwrap();
+    assert!(south.get_client_orders().await.unwrap().is_empty());
+    assert!(south.get_client_reports().await.unwrap().is_empty());
+    south.log_out().await.unwrap();
+    assert!(matches!(
+        directory.client_login("south", "alice@example.com", "Passw0rd!").await,
+        Ok((_, user)) if user.name() == "bob"
+    ));
+
+    south.employee_login(1, "Passw0rd!").await.unwrap();
+    assert_eq!(south.get_client(1).await.unwrap().name(), "bob");
+    assert!(south.get_orders(None).await.unwrap().is_empty());
+    assert!(matches!(south.get_report(1).await, Err(DbError::Report(1))));
+    let reports = south
+        .search_reports(&ReportSearch::default(), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(reports.total, 0);
+
+    // Only the southern shop's own changes reach its subscribers
+    assert!(matches!(
+        south_events.try_recv(),
+        Ok(ShopEvent::ClientRegistered { .. })
+    ));
+    while let Ok(event) = south_events.try_recv() {
+        assert!(matches!(event, ShopEvent::LoggedIn { .. }));
+    }
+}
+
+#[async_std::test]
+async fn unknown_shops_are_rejected() {
+    let _ = bcrypt::hash("Passw0rd!", 4).unwrap();
+    let directory = TenantDirectory::new();
+
+    assert!(matches!(
+        directory.add_tenant("Not A Name", "sqlite::memory:").await,
+        Err(TenantError::InvalidName(_))
+    ));
+    assert!(matches!(
+        directory.backend("north").await,
+        Err(TenantError::UnknownTenant(_))
+    ));
+    let error = directory
+        .client_login("north", "alice@example.com", "Passw0rd!")
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.code(), "unknown_tenant");
+}
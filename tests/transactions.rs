@@ -0,0 +1,121 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+use tokio::sync::broadcast::error::TryRecvError;
+
+#[async_std::test]
+async fn failed_transaction_changes_nothing() {
+    let (mut backend, _) = setup("transaction-rollback").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let mut events = backend.subscribe();
+
+    let result = backend
+        .transaction(|shop| {
+            Box::pin(async move {
+                shop.register_order(1, 1, &Service::Repair, None).await?;
+                // Fails because the order is already a repair
+                shop.change_inspection_to_repair(1, 1).await.map(|_| ())
+            })
+        })
+        .await;
+
+    assert!(matches!(result, Err(DbError::NotInspection(1))));
+    assert!(backend.get_unfinished_orders().await.unwrap().is_empty());
+    assert!(matches!(events.try_recv(), Err(TryRecvError::Empty)));
+}
+
+#[async_std::test]
+async fn events_are_published_after_commit() {
+    let (mut backend, _) = setup("transaction-commit").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let mut events = backend.subscribe();
+
+    backend
+        .transaction(|shop| {
+            Box::pin(async move {
+                shop.register_order(1, 1, &Service::Inspection, None)
+                    .await?;
+                shop.change_inspection_to_repair(1, 1).await?;
+                Ok::<_, DbError>(())
+            })
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(backend.get_unfinished_orders().await.unwrap().len(), 1);
+    assert!(matches!(
+        events.try_recv(),
+        Ok(ShopEvent::OrderRegistered { order_id: 1, .. })
+    ));
+    assert!(matches!(
+        events.try_recv(),
+        Ok(ShopEvent::ServiceChanged {
+            service: Service::Repair,
+            ..
+        })
+    ));
+
+    backend.close_order(1, 2).await.unwrap();
+    assert!(backend.get_unfinished_orders().await.unwrap().is_empty());
+}
+
+#[async_std::test]
+async fn car_is_registered_once() {
+    let (mut backend, _) = setup("transaction-car").await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+
+    // The client from the setup already has a car
+    assert!(matches!(
+        backend.register_car(1, "Audi", "A4").await,
+        Err(DbError::CarAlreadyRegistered(1))
+    ));
+    assert_eq!(backend.get_car(1).await.unwrap().unwrap().model, "Golf");
+}
+
+#[async_std::test]
+async fn stale_order_version_is_a_conflict() {
+    let (mut backend, _) = setup("order-version").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+
+    let order = backend.change_inspection_to_repair(1, 1).await.unwrap();
+    assert_eq!(order.version(), 2);
+
+    // Someone still looking at the first version cannot close the order
+    assert!(matches!(
+        backend.close_order(1, 1).await,
+        Err(DbError::Conflict("order", 1))
+    ));
+    let order = backend.close_order(1, 2).await.unwrap();
+    assert!(order.finished());
+    assert_eq!(order.version(), 3);
+}
+
+#[async_std::test]
+async fn finished_orders_are_not_closed_again() {
+    let (mut backend, _) = setup("order-closed-twice").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, None)
+        .await
+        .unwrap();
+    let mut events = backend.subscribe();
+    let order = backend.close_order(1, 1).await.unwrap();
+
+    assert!(matches!(
+        backend.close_order(1, order.version()).await,
+        Err(DbError::OrderFinished(1))
+    ));
+    let mut closed = 0;
+    while let Ok(event) = events.try_recv() {
+        if matches!(event, ShopEvent::OrderClosed { order_id: 1, .. }) {
+            closed += 1;
+        }
+    }
+    assert_eq!(closed, 1);
+}
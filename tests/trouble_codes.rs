@@ -0,0 +1,175 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+use serde_json::json;
+
+#[test]
+fn codes_are_validated_and_described() {
+    assert_eq!(normalize_trouble_code(" p0420 ").as_deref(), Some("P0420"));
+    assert_eq!(normalize_trouble_code("u3a1f").as_deref(), Some("U3A1F"));
+    for invalid in ["P042", "P04201", "X0420", "P4420", "P04G0", ""] {
+        assert_eq!(normalize_trouble_code(invalid), None, "{invalid}");
+    }
+
+    for (code, generic) in [
+        ("P0420", true),
+        ("P2187", true),
+        ("P3400", true),
+        ("P3000", false),
+        ("P1234", false),
+        ("B1001", false),
+        ("U0100", true),
+        ("C3001", true),
+    ] {
+        assert_eq!(is_generic_trouble_code(code), generic, "{code}");
+    }
+
+    assert_eq!(
+        describe_trouble_code("P0420"),
+        Some("Catalyst System Efficiency Below Threshold (Bank 1)")
+    );
+    assert_eq!(describe_trouble_code("P1234"), None);
+}
+
+#[async_std::test]
+async fn mechanics_record_codes_of_unfinished_orders() {
+    let (mut backend, _) = setup("trouble-codes-record").await;
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        backend.record_trouble_code(1, "P042", None, false).await,
+        Err(DbError::InvalidTroubleCode(code)) if code == "P042"
+    ));
+    let misfire = backend
+        .record_trouble_code(
+            1,
+            "p0300",
+            Some(json!({"rpm": 2150, "coolant_temp_c": 91, "load_percent": 47.5})),
+            false,
+        )
+        .await
+        .unwrap();
+    assert_eq!(misfire.code(), "P0300");
+    assert_eq!(
+        misfire.description(),
+        Some("Random/Multiple Cylinder Misfire Detected")
+    );
+    assert_eq!(misfire.freeze_frame().unwrap()["rpm"], 2150);
+    let specific = backend
+        .record_trouble_code(1, "P1570", None, true)
+        .await
+        .unwrap();
+    assert_eq!(specific.description(), None);
+    assert!(specific.cleared());
+
+    assert!(backend
+        .clear_trouble_code(misfire.id())
+        .await
+        .unwrap()
+        .cleared());
+    assert!(matches!(
+        backend.clear_trouble_code(9).await,
+        Err(DbError::TroubleCode(9))
+    ));
+    backend.close_order(1, 1).await.unwrap();
+    assert!(matches!(
+        backend.record_trouble_code(1, "P0301", None, false).await,
+        Err(DbError::OrderFinished(1))
+    ));
+    assert!(matches!(
+        backend.clear_trouble_code(specific.id()).await,
+        Err(DbError::OrderFinished(1))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let codes = backend.get_trouble_codes(1).await.unwrap();
+    assert_eq!(codes.len(), 2);
+    assert!(codes.iter().all(TroubleCode::cleared));
+    assert!(matches!(
+        backend.record_trouble_code(1, "P0301", None, false).await,
+        Err(DbError::Permission)
+    ));
+}
+
+#[async_std::test]
+async fn history_is_searched_by_code_across_vehicles() {
+    let (mut backend, hash) = setup("trouble-codes-search").await;
+    backend
+        .register_client("other", "other@example.com", &hash)
+        .await
+        .unwrap();
+    backend.register_car(2, "Audi", "A4").await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let north = backend.create_location("North", None).await.unwrap();
+    for client_id in [1, 2, 1] {
+        backend
+            .register_order(client_id, 1, &Service::Inspection, None)
+            .await
+            .unwrap();
+    }
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    for (order_id, code) in [(1, "P0420"), (2, "P0420"), (3, "P0420"), (3, "P0171")] {
+        backend
+            .record_trouble_code(order_id, code, None, false)
+            .await
+            .unwrap();
+    }
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend.transfer_order(3, 1, north.id()).await.unwrap();
+    backend.set_employee_location(2, Some(1)).await.unwrap();
+    let matches = backend.search_trouble_codes(" p0420").await.unwrap();
+    assert_eq!(
+        matches
+            .iter()
+            .map(|found| found.trouble_code.order_id())
+            .collect::<Vec<_>>(),
+        [3, 2, 1]
+    );
+    assert_eq!(matches[1].client_id, 2);
+    assert_eq!(
+        matches[1].car.as_ref().map(|car| car.make.as_str()),
+        Some("Audi")
+    );
+    backend.log_out().await.unwrap();
+
+    // Orders moved to another location are not found by mechanics of the first one
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert_eq!(
+        backend.search_trouble_codes("P0420").await.unwrap().len(),
+        2
+    );
+    assert!(backend
+        .search_trouble_codes("P0171")
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        backend.search_trouble_codes("0420").await,
+        Err(DbError::InvalidTroubleCode(_))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert!(matches!(
+        backend.search_trouble_codes("P0420").await,
+        Err(DbError::Permission)
+    ));
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+/// "12345678901234567890", the secret of the RFC 4226 and RFC 6238 test vectors
+const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+#[test]
+fn hotp_matches_rfc_4226() {
+    // RFC 4226 Appendix D, the counter is the number of 30 second steps
+    let expected = [
+        "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871",
+        "520489",
+    ];
+    for (counter, code) in expected.into_iter().enumerate() {
+        assert_eq!(
+            totp_code(RFC_SECRET, counter as i64 * 30).as_deref(),
+            Some(code),
+            "counter {counter}"
+        );
+    }
+}
+
+#[test]
+fn totp_matches_rfc_6238() {
+    // RFC 6238 Appendix B for SHA-1, which lists 8 digit codes of which 6 are used here
+    for (time, code) in [
+        (59, "94287082"),
+        (1111111109, "07081804"),
+        (1111111111, "14050471"),
+        (1234567890, "89005924"),
+        (2000000000, "69279037"),
+        (20000000000, "65353130"),
+    ] {
+        assert_eq!(
+            totp_code(RFC_SECRET, time).as_deref(),
+            Some(&code[2..]),
+            "time {time}"
+        );
+    }
+    assert_eq!(totp_code("not base32!", 59), None);
+}
+
+#[test]
+fn base32_round_trips() {
+    // RFC 4648 section 10, without padding
+    for (data, encoded) in [
+        ("", ""),
+        ("f", "MY"),
+        ("fo", "MZXQ"),
+        ("foo", "MZXW6"),
+        ("foob", "MZXW6YQ"),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI"),
+    ] {
+        assert_eq!(base32_encode(data.as_bytes()), encoded);
+        assert_eq!(base32_decode(encoded).unwrap(), data.as_bytes());
+    }
+    assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+    assert_eq!(base32_decode("MZXW1"), None);
+
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    assert_eq!(base32_decode(&base32_encode(&bytes)).unwrap(), bytes);
+}
+
+#[async_std::test]
+async fn codes_cannot_be_replayed() {
+    let (mut backend, _) = setup("two-factor-replay").await;
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let enrollment = backend.begin_totp_enrollment().await.unwrap();
+    assert!(enrollment.uri().contains(enrollment.secret()));
+    assert!(matches!(
+        backend.confirm_totp_enrollment("000000x").await,
+        Err(TwoFactorError::InvalidCode)
+    ));
+    let code = totp_code(enrollment.secret(), now()).unwrap();
+    backend.confirm_totp_enrollment(&code).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    // The code used for enrolling is spent, the one of the next step is still accepted
+    assert!(matches!(
+        backend.employee_login(2, PASSWORD).await,
+        Err(LoginError::SecondFactorRequired(2))
+    ));
+    assert!(matches!(
+        backend.employee_login_second_factor(&code).await,
+        Err(LoginError::InvalidSecondFactor)
+    ));
+    let next = totp_code(enrollment.secret(), now() + 30).unwrap();
+    backend.employee_login_second_factor(&next).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap_err();
+    assert!(matches!(
+        backend.employee_login_second_factor(&next).await,
+        Err(LoginError::InvalidSecondFactor)
+    ));
+}
+
+#[async_std::test]
+async fn recovery_codes_work_once() {
+    let (mut backend, _) = setup("two-factor-recovery").await;
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let enrollment = backend.begin_totp_enrollment().await.unwrap();
+    let code = totp_code(enrollment.secret(), now()).unwrap();
+    let recovery_codes = backend.confirm_totp_enrollment(&code).await.unwrap();
+    assert!(!recovery_codes.is_empty());
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap_err();
+    backend
+        .employee_login_second_factor(&recovery_codes[0])
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap_err();
+    assert!(matches!(
+        backend
+            .employee_login_second_factor(&recovery_codes[0])
+            .await,
+        Err(LoginError::InvalidSecondFactor)
+    ));
+    backend
+        .employee_login_second_factor(&recovery_codes[1].to_uppercase())
+        .await
+        .unwrap();
+}
+
+#[async_std::test]
+async fn logins_are_abandoned_after_too_many_wrong_codes() {
+    let (mut backend, _) = setup("two-factor-attempts").await;
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    let enrollment = backend.begin_totp_enrollment().await.unwrap();
+    let code = totp_code(enrollment.secret(), now()).unwrap();
+    backend.confirm_totp_enrollment(&code).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap_err();
+    for _ in 0..4 {
+        assert!(matches!(
+            backend.employee_login_second_factor("000000").await,
+            Err(LoginError::InvalidSecondFactor)
+        ));
+    }
+    assert!(matches!(
+        backend.employee_login_second_factor("000000").await,
+        Err(LoginError::TooManySecondFactorAttempts)
+    ));
+    let next = totp_code(enrollment.secret(), now() + 30).unwrap();
+    assert!(matches!(
+        backend.employee_login_second_factor(&next).await,
+        Err(LoginError::NoPendingLogin)
+    ));
+
+    // Starting over with the password allows new attempts
+    backend.employee_login(2, PASSWORD).await.unwrap_err();
+    backend.employee_login_second_factor(&next).await.unwrap();
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup_with_orders, PASSWORD};
+
+/// Setup with two orders of the client, oil filters in stock and the mechanic logged in
+async fn setup_orders(name: &str) -> (ShopBackend, String) {
+    let (mut backend, hash) =
+        setup_with_orders(name, &[Service::Inspection, Service::Repair]).await;
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend
+        .add_stock(1, "OF-100", "Oil filter", 2)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    (backend, hash)
+}
+
+#[async_std::test]
+async fn mileage_only_decreases_with_a_reason() {
+    let (backend, _) = setup_orders("vehicle-mileage").await;
+
+    backend.record_mileage(1, 120_000, None).await.unwrap();
+    let error = backend.record_mileage(2, 5_000, None).await.unwrap_err();
+    assert!(matches!(
+        error,
+        DbError::MileageDecreased {
+            client_id: 1,
+            previous: 120_000,
+            mileage: 5_000,
+        }
+    ));
+    assert_eq!(error.code(), "mileage_decreased");
+    assert!(matches!(
+        backend.record_mileage(2, 5_000, Some(" ")).await,
+        Err(DbError::MileageDecreased { .. })
+    ));
+
+    let reading = backend
+        .record_mileage(2, 5_000, Some("Instrument cluster replaced"))
+        .await
+        .unwrap();
+    assert!(reading.flagged());
+    let reading = backend
+        .record_mileage(2, 6_000, Some("Not needed"))
+        .await
+        .unwrap();
+    assert!(!reading.flagged());
+}
+
+#[async_std::test]
+async fn history_collects_everything_done_to_the_car() {
+    let (mut backend, _) = setup_orders("vehicle-history").await;
+    backend.record_mileage(1, 120_000, None).await.unwrap();
+    let part = backend.use_part(1, "OF-100", 1).await.unwrap();
+    assert_eq!(part.name(), "Oil filter");
+    assert!(matches!(
+        backend.use_part(1, "OF-100", 5).await,
+        Err(DbError::InsufficientStock { available: 1, .. })
+    ));
+    backend.close_order(1, 1).await.unwrap();
+    assert!(matches!(
+        backend.use_part(1, "OF-100", 1).await,
+        Err(DbError::OrderFinished(1))
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(3, PASSWORD).await.unwrap();
+    backend.register_report(1, 4500).await.unwrap();
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    let history = backend.get_vehicle_history(1).await.unwrap();
+    assert_eq!(history.car().make, "VW");
+    assert_eq!(history.latest_mileage(), Some(120_000));
+    assert_eq!(history.services().len(), 2);
+    let service = &history.services()[0];
+    assert_eq!(service.order().id(), 1);
+    assert_eq!(service.report().unwrap().cost(), 4500);
+    assert_eq!(service.parts()[0].quantity(), 1);
+    assert_eq!(service.mileage(), Some(120_000));
+    assert!(history.services()[1].report().is_none());
+
+    let document = history.to_html();
+    assert!(document.contains("Service history of VW Golf"));
+    assert!(document.contains("120000 km"));
+    assert!(document.contains("1 x Oil filter (OF-100)"));
+    assert!(!document.contains("client@example.com"));
+    assert!(matches!(
+        backend.get_vehicle_history(2).await,
+        Err(DbError::Permission)
+    ));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+use async_trait::async_trait;
+
+use std::sync::{Arc, Mutex};
+
+/// Keeps the bodies of sent emails, or fails to send while `failing` is set
+#[derive(Clone, Default)]
+struct Outbox {
+    bodies: Arc<Mutex<Vec<String>>>,
+    failing: bool,
+}
+
+#[async_trait]
+impl Notifier for Outbox {
+    async fn notify(
+        &self,
+        _recipient: &str,
+        _subject: &str,
+        body: &str,
+    ) -> Result<(), NotifyError> {
+        if self.failing {
+            return Err(NotifyError(String::from("mail server down")));
+        }
+        self.bodies.lock().unwrap().push(body.to_owned());
+        Ok(())
+    }
+}
+
+impl Outbox {
+    fn last_token(&self) -> String {
+        let bodies = self.bodies.lock().unwrap();
+        let body = bodies.last().expect("no email sent");
+        let first_line = body.lines().next().unwrap();
+        first_line.rsplit(' ').next().unwrap().to_owned()
+    }
+}
+
+#[async_std::test]
+async fn registration_survives_a_failing_notifier() {
+    let (mut backend, _) = setup("verification-failing").await;
+    let outbox = Outbox::default();
+    backend.set_notifier(Outbox {
+        failing: true,
+        ..outbox.clone()
+    });
+
+    let hash = bcrypt::hash(PASSWORD, 4).unwrap();
+    let client = backend
+        .register_client("new", "new@example.com", &hash)
+        .await
+        .unwrap();
+    assert!(outbox.bodies.lock().unwrap().is_empty());
+
+    // Nothing was sent, so asking again right away is not throttled
+    backend.set_notifier(outbox.clone());
+    backend.resend_verification_email().await.unwrap();
+    assert!(matches!(
+        backend.resend_verification_email().await,
+        Err(VerificationError::TooManyRequests(_))
+    ));
+    backend.verify_email(&outbox.last_token()).await.unwrap();
+    assert!(matches!(
+        backend.resend_verification_email().await,
+        Err(VerificationError::AlreadyVerified)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("new@example.com", PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(backend.user().id(), client.id());
+}
+
+#[async_std::test]
+async fn registration_needs_a_bcrypt_hash() {
+    let (mut backend, _) = setup("verification-hash").await;
+
+    assert!(matches!(
+        backend
+            .register_client("new", "new@example.com", PASSWORD)
+            .await,
+        Err(RegisterClientError::PasswordNotHashed)
+    ));
+}
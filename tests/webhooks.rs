@@ -0,0 +1,251 @@
+mod common;
+
+use car_repair_shop_backend::*;
+use common::{setup, PASSWORD};
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const SECRET: &str = "whsec_test";
+
+struct Request {
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// HTTP server on a random local port answering with `statuses` in order, then 200
+struct Receiver {
+    url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl Receiver {
+    fn start(statuses: &[u16]) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mut statuses = statuses.iter().copied().collect::<VecDeque<_>>();
+
+        let received = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut headers = HashMap::new();
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    let Some((name, value)) = line.trim_end().split_once(": ") else {
+                        break;
+                    };
+                    headers.insert(name.to_lowercase(), value.to_owned());
+                }
+
+                let length = headers["content-length"].parse().unwrap();
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                received.lock().unwrap().push(Request {
+                    headers,
+                    body: String::from_utf8(body).unwrap(),
+                });
+
+                let status = statuses.pop_front().unwrap_or(200);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .unwrap();
+            }
+        });
+
+        Receiver { url, requests }
+    }
+
+    fn received(&self) -> Vec<Request> {
+        std::mem::take(&mut self.requests.lock().unwrap())
+    }
+}
+
+#[async_std::test]
+async fn webhook_deliveries_are_signed_retried_and_replayed() {
+    let (mut backend, _) = setup("webhooks-delivery").await;
+    let receiver = Receiver::start(&[500]);
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let subscription = backend
+        .create_webhook_subscription(
+            &receiver.url,
+            SECRET,
+            &[
+                WebhookEventType::OrderCreated,
+                WebhookEventType::OrderStatusChanged,
+            ],
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    backend
+        .register_order(1, 1, &Service::Repair, None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    // Nothing is sent until the deliveries are dispatched
+    assert!(receiver.received().is_empty());
+    assert_eq!(backend.dispatch_webhooks().await.unwrap(), 0);
+    // The first attempt failed and the retry is not due yet
+    assert_eq!(backend.dispatch_webhooks().await.unwrap(), 0);
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let deliveries = backend
+        .get_webhook_deliveries(subscription.id())
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    let failed = &deliveries[0];
+    assert_eq!(failed.event_type(), WebhookEventType::OrderCreated);
+    assert_eq!(failed.attempts(), 1);
+    assert_eq!(failed.response_status(), Some(500));
+    assert!(failed.delivered_at().is_none());
+
+    let replayed = backend.replay_webhook_delivery(failed.id()).await.unwrap();
+    assert_eq!(replayed.event_id(), failed.event_id());
+    assert_eq!(replayed.attempts(), 0);
+    assert_eq!(backend.dispatch_webhooks().await.unwrap(), 1);
+    let deliveries = backend
+        .get_webhook_deliveries(subscription.id())
+        .await
+        .unwrap();
+    let replayed = deliveries
+        .iter()
+        .find(|delivery| delivery.id() == replayed.id())
+        .unwrap();
+    assert_eq!(replayed.response_status(), Some(200));
+    assert!(replayed.delivered_at().is_some());
+
+    let requests = receiver.received();
+    assert_eq!(requests.len(), 2);
+    for request in requests.iter() {
+        let timestamp = request.headers["x-shop-timestamp"].parse().unwrap();
+        assert!(verify_webhook_signature(
+            SECRET,
+            timestamp,
+            &request.body,
+            &request.headers["x-shop-signature"],
+        ));
+        assert!(!verify_webhook_signature(
+            "wrong secret",
+            timestamp,
+            &request.body,
+            &request.headers["x-shop-signature"],
+        ));
+        assert_eq!(request.headers["x-shop-event"], "order.created");
+
+        let payload: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(payload["id"], failed.event_id());
+        assert_eq!(payload["type"], "order.created");
+        assert_eq!(payload["data"]["order_id"], 1);
+        assert_eq!(payload["data"]["client_id"], 1);
+    }
+    assert_eq!(requests[0].body, requests[1].body);
+    assert_ne!(
+        requests[0].headers["x-shop-delivery"],
+        requests[1].headers["x-shop-delivery"]
+    );
+}
+
+#[async_std::test]
+async fn webhooks_are_only_sent_for_subscribed_events() {
+    let (mut backend, _) = setup("webhooks-filter").await;
+    let receiver = Receiver::start(&[]);
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    let subscription = backend
+        .create_webhook_subscription(
+            &receiver.url,
+            SECRET,
+            &[WebhookEventType::OrderStatusChanged],
+        )
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend
+        .client_login("client@example.com", PASSWORD)
+        .await
+        .unwrap();
+    backend
+        .register_order(1, 1, &Service::Inspection, None)
+        .await
+        .unwrap();
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    backend.close_order(1, 1).await.unwrap();
+    backend.log_out().await.unwrap();
+    assert_eq!(backend.dispatch_webhooks().await.unwrap(), 1);
+
+    let requests = receiver.received();
+    assert_eq!(requests.len(), 1);
+    let payload: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(payload["type"], "order.status_changed");
+    assert_eq!(payload["data"]["finished"], true);
+    assert_eq!(payload["data"]["service"], "Inspection");
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    backend
+        .set_webhook_subscription_active(subscription.id(), false)
+        .await
+        .unwrap();
+    assert!(!backend.get_webhook_subscriptions().await.unwrap()[0].active());
+}
+
+#[async_std::test]
+async fn webhook_subscriptions_are_managed_by_managers() {
+    let (mut backend, _) = setup("webhooks-permissions").await;
+
+    backend.employee_login(2, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend
+            .create_webhook_subscription(
+                "http://127.0.0.1/hooks",
+                SECRET,
+                &[WebhookEventType::ReportCreated],
+            )
+            .await,
+        Err(WebhookError::Permission)
+    ));
+    backend.log_out().await.unwrap();
+
+    backend.employee_login(1, PASSWORD).await.unwrap();
+    assert!(matches!(
+        backend
+            .create_webhook_subscription(
+                "ftp://example.com",
+                SECRET,
+                &[WebhookEventType::ReportCreated]
+            )
+            .await,
+        Err(WebhookError::InvalidUrl(_))
+    ));
+    assert!(matches!(
+        backend
+            .create_webhook_subscription("https://example.com/hooks", SECRET, &[])
+            .await,
+        Err(WebhookError::NoEventTypes)
+    ));
+    assert!(matches!(
+        backend.delete_webhook_subscription(42).await,
+        Err(WebhookError::Subscription(42))
+    ));
+}